@@ -0,0 +1,213 @@
+/*-
+ * #%L
+ * OBKV Table Client Framework
+ * %%
+ * Copyright (C) 2021 OceanBase
+ * %%
+ * OBKV Table Client Framework is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *          http://license.coscl.org.cn/MulanPSL2
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ * #L%
+ */
+
+use crate::{
+    error::{CommonErrCode, Error::Common as CommonErr, Result},
+    rpc::protocol::{
+        payloads::{ObTableBatchOperation, ObTableOperationType},
+        query::{ObNewRange, ObTableQuery, ObTableQueryAndMutate, ObTableQueryAndMutateResult},
+    },
+    serde_obkv::value::Value,
+};
+
+/// Compare operators of the filter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    IsNull,
+    IsNotNull,
+}
+
+impl CompareOp {
+    pub fn as_str(&self) -> &str {
+        match self {
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Eq => "=",
+            CompareOp::Ne => "!=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::IsNull => "IS",
+            CompareOp::IsNotNull => "IS_NOT",
+        }
+    }
+}
+
+/// The condition of the check-and-mutate operations, which is checked
+/// against the row of the row key on the server.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CheckCondition {
+    /// The row exists.
+    Exists,
+    /// The row exists and the column compares with the value. The value is
+    /// ignored for [`CompareOp::IsNull`] and [`CompareOp::IsNotNull`].
+    Compare(String, CompareOp, Value),
+    /// The row exists and matches the filter expression, e.g.
+    /// `TableCompareFilter(>=, 'c2:10')`.
+    Filter(String),
+}
+
+impl CheckCondition {
+    /// Convert the condition to the filter string of the query.
+    pub fn to_filter_string(&self) -> Result<String> {
+        match self {
+            CheckCondition::Exists => Ok("".to_owned()),
+            CheckCondition::Compare(column, op, value) => {
+                let value = match op {
+                    CompareOp::IsNull | CompareOp::IsNotNull => "".to_owned(),
+                    _ => filter_literal(value)?,
+                };
+                Ok(format!(
+                    "TableCompareFilter({}, '{}:{}')",
+                    op.as_str(),
+                    column,
+                    value
+                ))
+            }
+            CheckCondition::Filter(filter) => Ok(filter.to_owned()),
+        }
+    }
+}
+
+fn filter_literal(value: &Value) -> Result<String> {
+    let literal = match value {
+        Value::Bool(v, _) => (*v as i8).to_string(),
+        Value::Int8(v, _) => v.to_string(),
+        Value::UInt8(v, _) => v.to_string(),
+        Value::Int32(v, _) => v.to_string(),
+        Value::Int64(v, _) => v.to_string(),
+        Value::UInt32(v, _) => v.to_string(),
+        Value::UInt64(v, _) => v.to_string(),
+        Value::Float(v, _) => v.to_string(),
+        Value::Double(v, _) => v.to_string(),
+        Value::String(v, _) => v.to_owned(),
+        Value::Bytes(v, _) => String::from_utf8_lossy(v).into_owned(),
+        Value::Decimal(v, _) => v.to_string(),
+        _ => {
+            return Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                format!("Unsupported value in compare condition: {:?}", value),
+            ))
+        }
+    };
+
+    // the quote can't be escaped in the filter expression
+    if literal.contains('\'') {
+        return Err(CommonErr(
+            CommonErrCode::InvalidParam,
+            format!("Quote is not allowed in compare condition: {}", literal),
+        ));
+    }
+    Ok(literal)
+}
+
+/// Build the query-and-mutate request which applies the mutation to the row
+/// of the row key if the condition is met, or if the condition is not met
+/// with `check_not_exists` set.
+pub(crate) fn new_check_and_mutate(
+    row_keys: Vec<Value>,
+    condition: &CheckCondition,
+    check_not_exists: bool,
+    op_type: ObTableOperationType,
+    columns: Option<Vec<String>>,
+    properties: Option<Vec<Value>>,
+) -> Result<ObTableQueryAndMutate> {
+    let mut range = ObNewRange::from_keys(row_keys.clone(), row_keys.clone());
+    range.set_inclusive_start();
+    range.set_inclusive_end();
+
+    let mut table_query = ObTableQuery::new();
+    table_query.add_key_range(range);
+    table_query.set_filter_string(condition.to_filter_string()?);
+
+    let mut mutations = ObTableBatchOperation::with_ops_num(1);
+    mutations.add_op((op_type, row_keys, columns, properties));
+
+    let mut query_and_mutate = ObTableQueryAndMutate::new(table_query, mutations);
+    query_and_mutate.set_check_and_execute(true);
+    query_and_mutate.set_check_not_exists(check_not_exists);
+    Ok(query_and_mutate)
+}
+
+/// Check the row of the row key and mutate it by `query_and_mutate`, which
+/// is shared by the clients. The insert and insert-up are applied if no row
+/// matches the condition, and the other mutations are applied if the row
+/// matches it.
+/// Returns whether the row is mutated.
+pub(crate) fn check_and_mutate<F>(
+    row_keys: Vec<Value>,
+    condition: &CheckCondition,
+    op_type: ObTableOperationType,
+    columns: Option<Vec<String>>,
+    properties: Option<Vec<Value>>,
+    query_and_mutate: F,
+) -> Result<bool>
+where
+    F: FnOnce(ObTableQueryAndMutate) -> Result<ObTableQueryAndMutateResult>,
+{
+    let check_not_exists = matches!(
+        op_type,
+        ObTableOperationType::Insert | ObTableOperationType::InsertOrUpdate
+    );
+    let request = new_check_and_mutate(
+        row_keys,
+        condition,
+        check_not_exists,
+        op_type,
+        columns,
+        properties,
+    )?;
+    Ok(query_and_mutate(request)?.affected_rows() > 0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_condition_to_filter_string() {
+        assert_eq!("", CheckCondition::Exists.to_filter_string().unwrap());
+        assert_eq!(
+            "TableCompareFilter(=, 'c2:v1')",
+            CheckCondition::Compare("c2".to_owned(), CompareOp::Eq, Value::from("v1"))
+                .to_filter_string()
+                .unwrap()
+        );
+        assert_eq!(
+            "TableCompareFilter(>=, 'c3:10')",
+            CheckCondition::Compare("c3".to_owned(), CompareOp::Ge, Value::from(10i64))
+                .to_filter_string()
+                .unwrap()
+        );
+        assert_eq!(
+            "TableCompareFilter(IS, 'c3:')",
+            CheckCondition::Compare("c3".to_owned(), CompareOp::IsNull, Value::from(10i64))
+                .to_filter_string()
+                .unwrap()
+        );
+        assert!(
+            CheckCondition::Compare("c2".to_owned(), CompareOp::Eq, Value::from("'"))
+                .to_filter_string()
+                .is_err()
+        );
+    }
+}
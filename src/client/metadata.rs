@@ -0,0 +1,458 @@
+/*-
+ * #%L
+ * OBKV Table Client Framework
+ * %%
+ * Copyright (C) 2021 OceanBase
+ * %%
+ * OBKV Table Client Framework is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *          http://license.coscl.org.cn/MulanPSL2
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ * #L%
+ */
+
+use std::{sync::Arc, time::Duration};
+
+pub use super::ocp::OcpModel;
+use super::{
+    ocp::{ObOcpModelManager, RsList},
+    schema::TableSchema,
+    ClientConfig,
+};
+pub use crate::location::{
+    ObPartitionEntry, ObPartitionInfo, ObServerAddr, ObVersion, TableEntry, TableEntryKey,
+};
+use crate::{
+    constant::ALL_DUMMY_TABLE,
+    error::Result,
+    location::{cache::TableEntryCacheFile, ObTableLocation},
+    util::current_time_millis,
+};
+
+/// The source of the metadata to route the requests: the root servers, the
+/// table entries, the partition info and the partition locations.
+///
+/// The servers to fetch from are chosen by the client, so the providers only
+/// need to know how to fetch the metadata from a given server. The provider
+/// is set by [`Builder::metadata_provider`](crate::Builder::metadata_provider)
+/// and the system tables are queried by default.
+pub trait MetadataProvider: Send + Sync {
+    /// Load the root servers of the cluster.
+    fn load_ocp_model(&self, from_cache_when_fail: bool) -> Result<OcpModel>;
+
+    /// Detect the version of the cluster by the root servers.
+    fn detect_ob_version(&self, rs_list: &[ObServerAddr]) -> Result<ObVersion>;
+
+    /// The version detected, which is before 4.x if not detected yet.
+    fn ob_version(&self) -> ObVersion;
+
+    /// Load the table entry with its partition info and partition locations.
+    fn load_table_entry(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+    ) -> Result<TableEntry>;
+
+    /// Load the partition info of the partitioned table.
+    fn load_partition_info(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+        table_entry: &TableEntry,
+    ) -> Result<ObPartitionInfo>;
+
+    /// Load the locations of the partitions of the table.
+    fn load_partition_location(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+        table_entry: &TableEntry,
+    ) -> Result<ObPartitionEntry>;
+
+    /// Load the schema of the table.
+    fn load_table_schema(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+        table_entry: &TableEntry,
+    ) -> Result<TableSchema>;
+
+    /// Release the resources held for the servers not in `valid_addrs`.
+    fn invalidate_servers(&self, _valid_addrs: &[ObServerAddr]) {}
+}
+
+/// The default provider, which loads the root servers from the OCP or the
+/// static rs list and the others from the system tables over MySQL.
+pub struct MysqlMetadataProvider {
+    location: Arc<ObTableLocation>,
+    ocp_manager: ObOcpModelManager,
+    rs_list: Option<RsList>,
+    param_url: String,
+    datasource_name: String,
+    config: ClientConfig,
+}
+
+impl MysqlMetadataProvider {
+    pub fn new(
+        location: Arc<ObTableLocation>,
+        param_url: String,
+        rs_list: Option<RsList>,
+        datasource_name: String,
+        config: ClientConfig,
+    ) -> Result<Self> {
+        let ocp_manager =
+            ObOcpModelManager::new(config.rslist_acquire_timeout, &config.ocp_model_cache_file)?;
+
+        Ok(Self {
+            location,
+            ocp_manager,
+            rs_list,
+            param_url,
+            datasource_name,
+            config,
+        })
+    }
+}
+
+impl MetadataProvider for MysqlMetadataProvider {
+    fn load_ocp_model(&self, from_cache_when_fail: bool) -> Result<OcpModel> {
+        match &self.rs_list {
+            Some(rs_list) => rs_list.load_ocp_model(),
+            None => self.ocp_manager.load_ocp_model(
+                &self.param_url,
+                &self.datasource_name,
+                self.config.rslist_acquire_try_times,
+                self.config.rslist_acquire_retry_interval,
+                from_cache_when_fail,
+            ),
+        }
+    }
+
+    fn detect_ob_version(&self, rs_list: &[ObServerAddr]) -> Result<ObVersion> {
+        self.location.detect_ob_version(
+            rs_list,
+            self.config.table_entry_acquire_connect_timeout,
+            self.config.table_entry_acquire_read_timeout,
+        )
+    }
+
+    fn ob_version(&self) -> ObVersion {
+        self.location.ob_version()
+    }
+
+    fn load_table_entry(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+    ) -> Result<TableEntry> {
+        self.location.get_table_entry_from_remote(
+            server_addr,
+            key,
+            self.config.table_entry_acquire_connect_timeout,
+            self.config.table_entry_acquire_read_timeout,
+        )
+    }
+
+    fn load_partition_info(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+        table_entry: &TableEntry,
+    ) -> Result<ObPartitionInfo> {
+        self.location.load_partition_info(
+            server_addr,
+            key,
+            table_entry,
+            self.config.table_entry_acquire_connect_timeout,
+            self.config.table_entry_acquire_read_timeout,
+        )
+    }
+
+    fn load_partition_location(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+        table_entry: &TableEntry,
+    ) -> Result<ObPartitionEntry> {
+        self.location.load_partition_location(
+            server_addr,
+            key,
+            table_entry,
+            self.config.table_entry_acquire_connect_timeout,
+            self.config.table_entry_acquire_read_timeout,
+        )
+    }
+
+    fn load_table_schema(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+        table_entry: &TableEntry,
+    ) -> Result<TableSchema> {
+        self.location.load_table_schema(
+            server_addr,
+            key,
+            table_entry,
+            self.config.table_entry_acquire_connect_timeout,
+            self.config.table_entry_acquire_read_timeout,
+        )
+    }
+
+    fn invalidate_servers(&self, valid_addrs: &[ObServerAddr]) {
+        self.location.invalidate_mysql_pools(valid_addrs)
+    }
+}
+
+/// The provider persisting the table entries loaded by the inner provider, the
+/// persisted entries are served until they're older than `max_stale`, and the
+/// locations of them are refreshed in the background as usual and persisted
+/// again.
+pub struct CachedMetadataProvider {
+    inner: Arc<dyn MetadataProvider>,
+    cache_file: TableEntryCacheFile,
+    max_stale: Duration,
+}
+
+impl CachedMetadataProvider {
+    pub fn new(inner: Arc<dyn MetadataProvider>, cache_path: &str, max_stale: Duration) -> Self {
+        Self {
+            inner,
+            cache_file: TableEntryCacheFile::new(cache_path),
+            max_stale,
+        }
+    }
+}
+
+impl MetadataProvider for CachedMetadataProvider {
+    fn load_ocp_model(&self, from_cache_when_fail: bool) -> Result<OcpModel> {
+        self.inner.load_ocp_model(from_cache_when_fail)
+    }
+
+    fn detect_ob_version(&self, rs_list: &[ObServerAddr]) -> Result<ObVersion> {
+        self.inner.detect_ob_version(rs_list)
+    }
+
+    fn ob_version(&self) -> ObVersion {
+        self.inner.ob_version()
+    }
+
+    fn load_table_entry(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+    ) -> Result<TableEntry> {
+        // the servers of the cluster are always loaded from the servers
+        if key.table_name() == ALL_DUMMY_TABLE {
+            return self.inner.load_table_entry(server_addr, key);
+        }
+
+        let ob_version = self.inner.ob_version();
+        if let Some(table_entry) = self.cache_file.get(key, ob_version, self.max_stale) {
+            debug!(
+                "CachedMetadataProvider::load_table_entry load from cache file, key:{:?}",
+                key
+            );
+            return Ok(table_entry);
+        }
+
+        let table_entry = self.inner.load_table_entry(server_addr, key)?;
+        if let Err(e) = self.cache_file.save(key, &table_entry, ob_version) {
+            error!(
+                "CachedMetadataProvider::load_table_entry fail to save table entry, key:{:?}, err:{}",
+                key, e
+            );
+        }
+        Ok(table_entry)
+    }
+
+    fn load_partition_info(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+        table_entry: &TableEntry,
+    ) -> Result<ObPartitionInfo> {
+        self.inner
+            .load_partition_info(server_addr, key, table_entry)
+    }
+
+    fn load_partition_location(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+        table_entry: &TableEntry,
+    ) -> Result<ObPartitionEntry> {
+        let partition_entry = self
+            .inner
+            .load_partition_location(server_addr, key, table_entry)?;
+        if key.table_name() == ALL_DUMMY_TABLE {
+            return Ok(partition_entry);
+        }
+
+        // persist the locations refreshed, which are served on the warm start
+        let mut refreshed = table_entry.clone();
+        refreshed.set_partition_entry(Some(partition_entry.clone()));
+        refreshed.set_refresh_time_mills(current_time_millis());
+        if let Err(e) = self
+            .cache_file
+            .save(key, &refreshed, self.inner.ob_version())
+        {
+            error!(
+                "CachedMetadataProvider::load_partition_location fail to save table entry, key:{:?}, err:{}",
+                key, e
+            );
+        }
+        Ok(partition_entry)
+    }
+
+    fn load_table_schema(
+        &self,
+        server_addr: &ObServerAddr,
+        key: &TableEntryKey,
+        table_entry: &TableEntry,
+    ) -> Result<TableSchema> {
+        self.inner.load_table_schema(server_addr, key, table_entry)
+    }
+
+    fn invalidate_servers(&self, valid_addrs: &[ObServerAddr]) {
+        self.inner.invalidate_servers(valid_addrs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::{
+        client::ocp::parse_rs_list,
+        error::{CommonErrCode, Error::Common as CommonErr},
+        location::new_hash_table_entry,
+    };
+
+    /// Serves the locations of a hash partitioned table led by one server.
+    struct FakeMetadataProvider {
+        leader: ObServerAddr,
+    }
+
+    impl MetadataProvider for FakeMetadataProvider {
+        fn load_ocp_model(&self, _from_cache_when_fail: bool) -> Result<OcpModel> {
+            Ok(OcpModel {
+                observer_addrs: vec![self.leader.clone()],
+                cluster_id: 1,
+            })
+        }
+
+        fn detect_ob_version(&self, _rs_list: &[ObServerAddr]) -> Result<ObVersion> {
+            Ok(self.ob_version())
+        }
+
+        fn ob_version(&self) -> ObVersion {
+            ObVersion::new(4, 2, 1, 0)
+        }
+
+        fn load_table_entry(
+            &self,
+            _server_addr: &ObServerAddr,
+            _key: &TableEntryKey,
+        ) -> Result<TableEntry> {
+            Ok(new_hash_table_entry(4, &self.leader))
+        }
+
+        fn load_partition_info(
+            &self,
+            _server_addr: &ObServerAddr,
+            _key: &TableEntryKey,
+            table_entry: &TableEntry,
+        ) -> Result<ObPartitionInfo> {
+            table_entry.partition_info().clone().ok_or_else(|| {
+                CommonErr(
+                    CommonErrCode::NotFound,
+                    "partition info not found".to_owned(),
+                )
+            })
+        }
+
+        fn load_partition_location(
+            &self,
+            _server_addr: &ObServerAddr,
+            _key: &TableEntryKey,
+            _table_entry: &TableEntry,
+        ) -> Result<ObPartitionEntry> {
+            let table_entry = new_hash_table_entry(4, &self.leader);
+            table_entry.partition_entry().clone().ok_or_else(|| {
+                CommonErr(
+                    CommonErrCode::NotFound,
+                    "partition entry not found".to_owned(),
+                )
+            })
+        }
+
+        fn load_table_schema(
+            &self,
+            _server_addr: &ObServerAddr,
+            _key: &TableEntryKey,
+            _table_entry: &TableEntry,
+        ) -> Result<TableSchema> {
+            Err(CommonErr(
+                CommonErrCode::NotFound,
+                "table schema not found".to_owned(),
+            ))
+        }
+    }
+
+    #[test]
+    fn test_persist_refreshed_locations() {
+        let dir = Builder::new().prefix("table_entry").tempfile().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let key = TableEntryKey::new("cluster", "tenant", "test", "test_hash_table");
+        let max_stale = Duration::from_secs(60);
+        let mut leader = ObServerAddr::new();
+        leader.address("127.0.0.1".to_owned());
+        leader.set_sql_port(2881);
+        leader.set_svr_port(2882);
+
+        let provider = CachedMetadataProvider::new(
+            Arc::new(FakeMetadataProvider {
+                leader: leader.clone(),
+            }),
+            path,
+            max_stale,
+        );
+        // the locations of the entry are refreshed in the background
+        let mut table_entry = new_hash_table_entry(4, &leader);
+        table_entry.set_partition_entry(None);
+        let partition_entry = provider
+            .load_partition_location(&leader, &key, &table_entry)
+            .expect("fail to load partition location");
+
+        // served on the warm start
+        let file = TableEntryCacheFile::new(path);
+        let cached = file
+            .get(&key, provider.ob_version(), max_stale)
+            .expect("fail to get table entry");
+        assert_eq!(&Some(partition_entry), cached.partition_entry());
+    }
+
+    #[test]
+    fn test_load_ocp_model_from_rs_list() {
+        let addrs = parse_rs_list(vec!["127.0.0.1:2881", "127.0.0.2:2881"]).unwrap();
+        let provider = MysqlMetadataProvider::new(
+            Arc::new(ObTableLocation::default()),
+            "".to_owned(),
+            Some(RsList::Addrs(addrs.clone())),
+            "".to_owned(),
+            ClientConfig::default(),
+        )
+        .expect("fail to create metadata provider");
+
+        let model = provider
+            .load_ocp_model(false)
+            .expect("fail to load ocp model");
+        assert_eq!(addrs, model.observer_addrs);
+        assert!(!provider.ob_version().is_4x());
+    }
+}
@@ -18,17 +18,26 @@
 use std::{collections::HashMap, time::Duration};
 
 use crate::{
-    error::Result,
-    rpc::protocol::{payloads::ObTableBatchOperation, DEFAULT_FLAG},
+    error::{CommonErrCode, Error::Common as CommonErr, Result},
+    rpc::protocol::{
+        payloads::{
+            ObTableBatchOperation, ObTableConsistencyLevel, ObTableEntity, ObTableOperationRequest,
+        },
+        DEFAULT_FLAG,
+    },
     serde_obkv::value::Value,
+    util::StdBoxFuture,
 };
 
+pub mod filter;
+pub mod metadata;
 mod metrics;
 mod ocp;
 pub mod query;
+pub mod schema;
 pub mod table;
 pub mod table_client;
-use self::table::ObTable;
+use self::{filter::CheckCondition, query::TableQuery, table::ObTable};
 
 #[derive(Clone, Debug)]
 pub enum TableOpResult {
@@ -36,6 +45,49 @@ pub enum TableOpResult {
     RetrieveRows(HashMap<String, Value>),
 }
 
+/// Options of a single operation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OperationOptions {
+    return_row_key: bool,
+    return_affected_entity: bool,
+    consistency_level: Option<ObTableConsistencyLevel>,
+}
+
+impl OperationOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the row key of the affected record.
+    pub fn return_row_key(mut self, return_row_key: bool) -> Self {
+        self.return_row_key = return_row_key;
+        self
+    }
+
+    /// Return the values of the affected record after the operation, e.g.
+    /// the counter after the increment.
+    pub fn return_affected_entity(mut self, return_affected_entity: bool) -> Self {
+        self.return_affected_entity = return_affected_entity;
+        self
+    }
+
+    /// Set the consistency level of the read, the weak read is served by the
+    /// followers if there are any. The default follows the client config.
+    pub fn consistency_level(mut self, consistency_level: ObTableConsistencyLevel) -> Self {
+        self.consistency_level = Some(consistency_level);
+        self
+    }
+
+    pub(crate) fn get_consistency_level(&self) -> Option<ObTableConsistencyLevel> {
+        self.consistency_level
+    }
+
+    pub(crate) fn apply(&self, payload: &mut ObTableOperationRequest) {
+        payload.set_return_row_key(self.return_row_key);
+        payload.set_return_affected_entity(self.return_affected_entity);
+    }
+}
+
 pub trait Table {
     /// Insert a record
     fn insert(
@@ -92,6 +144,28 @@ pub trait Table {
         properties: Vec<Value>,
     ) -> Result<i64>;
 
+    /// Append with options, the affected record is returned if it is
+    /// required by the options.
+    fn append_with_options(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+        options: OperationOptions,
+    ) -> Result<ObTableEntity>;
+
+    /// Increment with options, the affected record is returned if it is
+    /// required by the options.
+    fn increment_with_options(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+        options: OperationOptions,
+    ) -> Result<ObTableEntity>;
+
     /// Delete records by row keys.
     fn delete(&self, table_name: &str, row_keys: Vec<Value>) -> Result<i64>;
 
@@ -103,6 +177,74 @@ pub trait Table {
         columns: Vec<String>,
     ) -> Result<HashMap<String, Value>>;
 
+    /// Retrieve a record by row keys with options, e.g. the weak read.
+    fn get_with_options(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        options: OperationOptions,
+    ) -> Result<HashMap<String, Value>>;
+
+    /// Insert a record if no row matches the condition, e.g. the record does
+    /// not exist with [`CheckCondition::Exists`]. Returns whether the record
+    /// is inserted.
+    ///
+    /// NOTE: the insert fails if the record exists but does not match a
+    /// [`CheckCondition::Compare`] or [`CheckCondition::Filter`] condition,
+    /// see [`Table::check_and_insert_up`] to overwrite it instead.
+    fn check_and_insert(
+        &self,
+        _table_name: &str,
+        _row_keys: Vec<Value>,
+        _condition: CheckCondition,
+        _columns: Vec<String>,
+        _properties: Vec<Value>,
+    ) -> Result<bool> {
+        unsupported("check_and_insert")
+    }
+
+    /// Insert or update a record if no row matches the condition. Returns
+    /// whether the record is written.
+    ///
+    /// NOTE: this is an upsert rather than an insert, an existing record is
+    /// overwritten if it does not match a [`CheckCondition::Compare`] or
+    /// [`CheckCondition::Filter`] condition.
+    fn check_and_insert_up(
+        &self,
+        _table_name: &str,
+        _row_keys: Vec<Value>,
+        _condition: CheckCondition,
+        _columns: Vec<String>,
+        _properties: Vec<Value>,
+    ) -> Result<bool> {
+        unsupported("check_and_insert_up")
+    }
+
+    /// Update a record if it matches the condition. Returns whether the record
+    /// is updated.
+    fn check_and_update(
+        &self,
+        _table_name: &str,
+        _row_keys: Vec<Value>,
+        _condition: CheckCondition,
+        _columns: Vec<String>,
+        _properties: Vec<Value>,
+    ) -> Result<bool> {
+        unsupported("check_and_update")
+    }
+
+    /// Delete a record if it matches the condition. Returns whether the record
+    /// is deleted.
+    fn check_and_delete(
+        &self,
+        _table_name: &str,
+        _row_keys: Vec<Value>,
+        _condition: CheckCondition,
+    ) -> Result<bool> {
+        unsupported("check_and_delete")
+    }
+
     /// Create a batch operation
     fn batch_operation(&self, ops_num_hint: usize) -> ObTableBatchOperation;
     // Execute a batch operation
@@ -113,6 +255,106 @@ pub trait Table {
     ) -> Result<Vec<TableOpResult>>;
 }
 
+/// The error of the operations not implemented by a [`Table`].
+fn unsupported<T>(operation: &str) -> Result<T> {
+    Err(CommonErr(
+        CommonErrCode::NotSupported,
+        format!("Table::{} is not supported", operation),
+    ))
+}
+
+/// Asynchronous version of [`Table`], the returned futures resolve on the
+/// replies from the server so that no thread is held by in-flight requests.
+///
+/// The futures are std futures which need no reactor from the runtime, so
+/// they can be awaited on any std runtime such as tokio:
+///
+/// ```ignore
+/// let affected_rows = client.insert(table, row_keys, columns, properties).await?;
+/// ```
+pub trait AsyncTable {
+    type Query: TableQuery;
+
+    /// Insert a record
+    fn insert(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64>;
+
+    /// Update a record
+    fn update(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64>;
+
+    /// Insert or update a record, if the record exists, update it.
+    /// Otherwise insert a new one.
+    fn insert_or_update(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64>;
+
+    /// Replace a record.
+    fn replace(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64>;
+
+    /// Append
+    fn append(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64>;
+
+    /// Increment
+    fn increment(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64>;
+
+    /// Delete records by row keys.
+    fn delete(&self, table_name: &str, row_keys: Vec<Value>) -> StdBoxFuture<i64>;
+
+    /// Retrieve a record by row keys.
+    fn get(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+    ) -> StdBoxFuture<HashMap<String, Value>>;
+
+    /// Create a batch operation
+    fn batch_operation(&self, ops_num_hint: usize) -> ObTableBatchOperation;
+    // Execute a batch operation
+    fn execute_batch(
+        &self,
+        table_name: &str,
+        batch_op: ObTableBatchOperation,
+    ) -> StdBoxFuture<Vec<TableOpResult>>;
+
+    /// Create a query on the table, which should be executed by
+    /// [`TableQuery::execute_async`].
+    fn query(&self, table_name: &str) -> Self::Query;
+}
+
 /// ObTable client config
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ClientConfig {
@@ -123,6 +365,11 @@ pub struct ClientConfig {
     pub metadata_mysql_conn_pool_max_size: usize,
     pub metadata_refresh_interval: Duration,
     pub ocp_model_cache_file: String,
+    // the file to persist the table entries for the warm start, the table
+    // entries are not persisted if it is not set
+    pub table_entry_cache_file: Option<String>,
+    // the persisted table entries older than it are loaded from the servers
+    pub table_entry_cache_max_stale: Duration,
 
     pub rslist_acquire_timeout: Duration,
     pub rslist_acquire_try_times: usize,
@@ -154,7 +401,17 @@ pub struct ClientConfig {
     pub max_conns_per_server: usize,
     pub min_idle_conns_per_server: usize,
     pub conn_init_thread_num: usize,
+    pub conn_event_loop_num: usize,
     pub query_concurrency_limit: Option<usize>,
+    // the default consistency level of the reads, the weak reads are served
+    // by the followers
+    pub read_consistency_level: ObTableConsistencyLevel,
+    // the idc where the client is deployed, the weak reads and the servers
+    // are chosen by the locality to it if it is set
+    pub idc: Option<String>,
+    // check and coerce the values of the mutations against the schema of the
+    // table before sending, not supported in the ODP mode
+    pub validate_values: bool,
 
     pub log_level_flag: u16,
 }
@@ -168,6 +425,8 @@ impl Default for ClientConfig {
             metadata_mysql_conn_pool_max_size: 3,
             metadata_refresh_interval: Duration::from_secs(60),
             ocp_model_cache_file: "/tmp/ocp_model_cache.json".to_owned(),
+            table_entry_cache_file: None,
+            table_entry_cache_max_stale: Duration::from_secs(3600),
 
             rslist_acquire_timeout: Duration::from_secs(10),
             rslist_acquire_try_times: 3,
@@ -198,7 +457,11 @@ impl Default for ClientConfig {
             max_conns_per_server: 10,
             min_idle_conns_per_server: 5,
             conn_init_thread_num: 2,
+            conn_event_loop_num: 4,
             query_concurrency_limit: None,
+            read_consistency_level: ObTableConsistencyLevel::Strong,
+            idc: None,
+            validate_values: false,
 
             log_level_flag: DEFAULT_FLAG,
         }
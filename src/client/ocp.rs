@@ -16,7 +16,7 @@
  */
 
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io, thread, time,
 };
 
@@ -112,6 +112,79 @@ impl Default for OcpModel {
     }
 }
 
+/// The root servers configured statically instead of fetched from the OCP.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum RsList {
+    /// The root servers in the `ip:sql_port` format.
+    Addrs(Vec<ObServerAddr>),
+    /// A local file listing one root server in the `ip:sql_port` format per
+    /// line, it's read again on every loading.
+    File(String),
+}
+
+impl RsList {
+    pub fn load_ocp_model(&self) -> Result<OcpModel> {
+        let observer_addrs = match self {
+            RsList::Addrs(addrs) => addrs.clone(),
+            RsList::File(path) => {
+                let content = fs::read_to_string(path)?;
+                parse_rs_list(content.lines())?
+            }
+        };
+
+        if observer_addrs.is_empty() {
+            return Err(CommonErr(
+                CommonErrCode::OcpError,
+                "Empty rs list".to_owned(),
+            ));
+        }
+
+        let mut ret = OcpModel::new();
+        ret.observer_addrs = observer_addrs;
+        Ok(ret)
+    }
+}
+
+/// Parse the root servers in the `ip:sql_port` format, the blank lines and
+/// the lines starting with `#` are skipped.
+pub fn parse_rs_list<'a, I>(rs_list: I) -> Result<Vec<ObServerAddr>>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut addrs = vec![];
+    for rs in rs_list {
+        let rs = rs.trim();
+        if rs.is_empty() || rs.starts_with('#') {
+            continue;
+        }
+
+        let (ip, port) = match rs.rfind(':') {
+            Some(pos) => (&rs[..pos], &rs[pos + 1..]),
+            None => {
+                return Err(CommonErr(
+                    CommonErrCode::InvalidParam,
+                    format!("ocp::parse_rs_list invalid rs address:{}", rs),
+                ))
+            }
+        };
+        let sql_port = match port.parse::<i32>() {
+            Ok(port) if !ip.is_empty() => port,
+            _ => {
+                return Err(CommonErr(
+                    CommonErrCode::InvalidParam,
+                    format!("ocp::parse_rs_list invalid rs address:{}", rs),
+                ))
+            }
+        };
+
+        let mut addr = ObServerAddr::new();
+        addr.address(ip.to_owned());
+        addr.set_sql_port(sql_port);
+        addrs.push(addr);
+    }
+    Ok(addrs)
+}
+
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Clone)]
 struct OcpResponseDataRs {
     address: String,
@@ -289,7 +362,7 @@ impl ObOcpModelManager {
 
 #[cfg(test)]
 mod test {
-    use std::time::Duration;
+    use std::{io::Write, time::Duration};
 
     use tempfile::Builder;
 
@@ -337,4 +410,35 @@ mod test {
         let model = file.load().expect("Fail to load ocp model");
         assert_eq!(ocp_model, model);
     }
+
+    #[test]
+    fn test_parse_rs_list() {
+        let addrs = parse_rs_list(vec!["127.0.0.1:2881", " 127.0.0.2:2882 ", "", "# comment"])
+            .expect("Fail to parse rs list");
+        assert_eq!(2, addrs.len());
+        assert_eq!("127.0.0.1", addrs[0].ip());
+        assert_eq!(2881, addrs[0].sql_port());
+        assert_eq!("127.0.0.2", addrs[1].ip());
+        assert_eq!(2882, addrs[1].sql_port());
+
+        assert!(parse_rs_list(vec!["127.0.0.1"]).is_err());
+        assert!(parse_rs_list(vec!["127.0.0.1:port"]).is_err());
+        assert!(parse_rs_list(vec![":2881"]).is_err());
+    }
+
+    #[test]
+    fn test_load_rs_list_file() {
+        let mut file = Builder::new().prefix("rs_list").tempfile().unwrap();
+        writeln!(file, "# root servers\n127.0.0.1:2881\n127.0.0.2:2881").unwrap();
+        let rs_list = RsList::File(file.path().to_str().unwrap().to_owned());
+
+        let model = rs_list.load_ocp_model().expect("Fail to load ocp model");
+        assert_eq!(2, model.observer_addrs.len());
+        assert_eq!("127.0.0.2", model.observer_addrs[1].ip());
+
+        assert!(RsList::Addrs(vec![]).load_ocp_model().is_err());
+        assert!(RsList::File("/not/exist/rs_list".to_owned())
+            .load_ocp_model()
+            .is_err());
+    }
 }
@@ -16,27 +16,30 @@
  */
 
 use std::{
-    collections::{HashMap, VecDeque},
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
     fmt, mem,
     sync::Arc,
     time::Duration,
 };
 
+use futures::{future, stream::FuturesUnordered, Async, Future, Poll, Stream};
 use prometheus::*;
 
 /// Query API for ob table
 use super::ObTable;
 use crate::{
-    error::{CommonErrCode, Error::Common as CommonErr, Result},
+    error::{CommonErrCode, Error, Error::Common as CommonErr, Result},
     rpc::protocol::{
-        payloads::ObTableEntityType,
+        payloads::{ObTableConsistencyLevel, ObTableEntityType},
         query::{
-            ObHTableFilter, ObTableQuery, ObTableQueryRequest, ObTableQueryResult,
+            ObHTableFilter, ObScanOrder, ObTableQuery, ObTableQueryRequest, ObTableQueryResult,
             ObTableStreamRequest,
         },
         DEFAULT_FLAG,
     },
-    serde_obkv::value::Value,
+    serde_obkv::value::{CollationType, Value},
+    util::{self, permit::PermitGuard, BoxFuture, Compat, StdBoxFuture},
 };
 
 lazy_static! {
@@ -59,6 +62,9 @@ lazy_static! {
 // Zero timeout means no-wait request.
 const ZERO_TIMEOUT_MS: Duration = Duration::from_millis(0);
 
+/// The query result of a partition, together with the partition id and table.
+pub type PartitionQueryResult = ((i64, Arc<ObTable>), ObTableQueryResult);
+
 pub trait StreamQuerier {
     fn execute_query(
         &self,
@@ -73,9 +79,345 @@ pub trait StreamQuerier {
         part_id_and_table: (i64, Arc<ObTable>),
         payload: &mut ObTableStreamRequest,
     ) -> Result<i64>;
+
+    /// Acquire a permit for a request to be sent asynchronously, which is
+    /// held until the reply arrives. `None` is returned if the concurrency of
+    /// the queries is not limited.
+    fn acquire_permit(&self) -> Result<Option<PermitGuard>> {
+        Ok(None)
+    }
+
+    /// Asynchronous version of [`StreamQuerier::execute_query`], the result
+    /// is returned by the future instead of being cached into the stream
+    /// result.
+    fn execute_query_async(
+        &self,
+        part_id_and_table: (i64, Arc<ObTable>),
+        payload: ObTableQueryRequest,
+    ) -> BoxFuture<PartitionQueryResult>;
+
+    /// Asynchronous version of [`StreamQuerier::execute_stream`].
+    fn execute_stream_async(
+        &self,
+        part_id_and_table: (i64, Arc<ObTable>),
+        payload: ObTableStreamRequest,
+    ) -> BoxFuture<PartitionQueryResult>;
+}
+
+type PartitionQueryResultDeque = VecDeque<PartitionQueryResult>;
+
+/// How the rows of the partitions involved in a query are returned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueryResultMode {
+    /// Partition by partition, and the order of the partitions is undefined.
+    Sequential,
+    /// Merge the rows of the partitions by the row key, in the scan order of
+    /// the query. The row key columns must be selected.
+    ///
+    /// NOTE: the strings are compared as bytes, so the query fails on the row
+    /// key strings of the collations other than `utf8mb4_bin` and `binary`.
+    MergeSort,
+    /// Scan at most the given number of partitions at the same time, which
+    /// is also limited by the query concurrency limit of the client, and the
+    /// rows are returned in the order of arrival.
+    Parallel(usize),
+}
+
+impl Default for QueryResultMode {
+    fn default() -> Self {
+        QueryResultMode::Sequential
+    }
+}
+
+/// The cached rows of a partition in the merge sort.
+struct PartitionCursor {
+    part_id_and_table: (i64, Arc<ObTable>),
+    rows: VecDeque<Vec<Value>>,
+    // the result to continue the stream of the partition
+    last_result: Option<ObTableQueryResult>,
+}
+
+/// The head row key of a partition in the merge heap.
+struct MergeEntry {
+    row_key: Vec<Value>,
+    part_id: i64,
+    reverse: bool,
+}
+
+impl MergeEntry {
+    // The row keys are checked by `check_row_key` before entering the heap,
+    // so that the values are always comparable here.
+    fn cmp_row_key(&self, other: &Self) -> Ordering {
+        for (l, r) in self.row_key.iter().zip(other.row_key.iter()) {
+            match l.partial_cmp(r) {
+                Some(Ordering::Equal) | None => continue,
+                Some(ord) => return ord,
+            }
+        }
+        self.part_id.cmp(&other.part_id)
+    }
+}
+
+/// Check that the row key can be ordered the same way as the server does by
+/// comparing the values, and against the row key of another partition.
+fn check_row_key(row_key: &[Value], other: Option<&[Value]>) -> Result<()> {
+    for (i, v) in row_key.iter().enumerate() {
+        if let Value::String(_, meta) = v {
+            if !matches!(
+                meta.cs_type(),
+                CollationType::UTF8MB4Bin | CollationType::Binary
+            ) {
+                return Err(CommonErr(
+                    CommonErrCode::InvalidParam,
+                    format!(
+                        "Merge sort doesn't support the row key of collation {:?}, value:{:?}",
+                        meta.cs_type(),
+                        v
+                    ),
+                ));
+            }
+        }
+        if let Some(o) = other.and_then(|o| o.get(i)) {
+            if mem::discriminant(v) != mem::discriminant(o) || v.partial_cmp(o).is_none() {
+                return Err(CommonErr(
+                    CommonErrCode::InvalidParam,
+                    format!(
+                        "Merge sort encountered incomparable row key values, {:?} and {:?}",
+                        v, o
+                    ),
+                ));
+            }
+        } else if v.partial_cmp(v).is_none() {
+            return Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                format!("Merge sort encountered incomparable row key value {:?}", v),
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl PartialEq for MergeEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MergeEntry {}
+
+impl PartialOrd for MergeEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MergeEntry {
+    // `BinaryHeap` pops the greatest entry, so the order is inverted for the
+    // forward scan.
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.reverse {
+            self.cmp_row_key(other)
+        } else {
+            other.cmp_row_key(self)
+        }
+    }
 }
 
-type PartitionQueryResultDeque = VecDeque<((i64, Arc<ObTable>), ObTableQueryResult)>;
+/// The requests in flight of the parallel scan.
+struct ParallelFetcher {
+    parallelism: usize,
+    fetches: FuturesUnordered<BoxFuture<PartitionQueryResult>>,
+    // partition id => the stream session in flight
+    sessions: HashMap<i64, ((i64, Arc<ObTable>), u64)>,
+}
+
+impl ParallelFetcher {
+    fn new(parallelism: usize) -> Self {
+        Self {
+            parallelism: parallelism.max(1),
+            fetches: FuturesUnordered::new(),
+            sessions: HashMap::new(),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.fetches.len() >= self.parallelism
+    }
+}
+
+/// K-way merge of the partitions, each of which returns the rows ordered by
+/// the row key.
+struct MergeSorter {
+    row_key_columns: Vec<String>,
+    // the positions of the row key columns in the properties
+    row_key_indexes: Vec<usize>,
+    reverse: bool,
+    cursors: HashMap<i64, PartitionCursor>,
+    heap: BinaryHeap<MergeEntry>,
+    // the partitions whose cached rows are consumed while more rows remain
+    // on the server, which must be fetched before the merge goes on
+    starving: VecDeque<i64>,
+    cache_size: usize,
+    // the rows left to return, the limit of the query applies to each
+    // partition on the server, so the merged rows are limited here again
+    remaining: Option<usize>,
+}
+
+impl MergeSorter {
+    fn new(row_key_columns: Vec<String>, reverse: bool, limit: Option<usize>) -> Self {
+        Self {
+            row_key_columns,
+            row_key_indexes: vec![],
+            reverse,
+            cursors: HashMap::new(),
+            heap: BinaryHeap::new(),
+            starving: VecDeque::new(),
+            cache_size: 0,
+            remaining: limit,
+        }
+    }
+
+    /// Whether the limit of the rows is reached.
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+
+    fn set_properties(&mut self, properties: &[String]) -> Result<()> {
+        if !self.row_key_indexes.is_empty() || properties.is_empty() {
+            return Ok(());
+        }
+        let mut row_key_indexes = Vec::with_capacity(self.row_key_columns.len());
+        for column in &self.row_key_columns {
+            match properties.iter().position(|p| p == column) {
+                Some(idx) => row_key_indexes.push(idx),
+                None => {
+                    return Err(CommonErr(
+                        CommonErrCode::InvalidParam,
+                        format!(
+                            "MergeSorter::set_properties row key column not selected, column:{}, properties:{:?}",
+                            column, properties
+                        ),
+                    ))
+                }
+            }
+        }
+        self.row_key_indexes = row_key_indexes;
+        Ok(())
+    }
+
+    fn push_head(&mut self, part_id: i64) -> Result<()> {
+        if let Some(row) = self.cursors.get(&part_id).and_then(|c| c.rows.front()) {
+            let row_key: Vec<Value> = self
+                .row_key_indexes
+                .iter()
+                .map(|idx| row[*idx].clone())
+                .collect();
+            check_row_key(&row_key, self.heap.peek().map(|e| &e.row_key[..]))?;
+            self.heap.push(MergeEntry {
+                row_key,
+                part_id,
+                reverse: self.reverse,
+            });
+        }
+        Ok(())
+    }
+
+    /// Append the rows fetched from a partition.
+    fn push(
+        &mut self,
+        part_id_and_table: (i64, Arc<ObTable>),
+        rows: Vec<Vec<Value>>,
+        last_result: Option<ObTableQueryResult>,
+    ) -> Result<()> {
+        let part_id = part_id_and_table.0;
+        let cursor = self
+            .cursors
+            .entry(part_id)
+            .or_insert_with(|| PartitionCursor {
+                part_id_and_table,
+                rows: VecDeque::new(),
+                last_result: None,
+            });
+        let was_empty = cursor.rows.is_empty();
+        self.cache_size += rows.len();
+        cursor.rows.extend(rows);
+        cursor.last_result = last_result;
+
+        if !cursor.rows.is_empty() {
+            if was_empty {
+                self.push_head(part_id)?;
+            }
+        } else if cursor.last_result.is_some() {
+            self.starving.push_back(part_id);
+        } else {
+            self.cursors.remove(&part_id);
+        }
+        Ok(())
+    }
+
+    /// Whether the next row can be merged without fetching any partition.
+    fn is_ready(&self) -> bool {
+        !self.is_exhausted() && self.starving.is_empty() && !self.heap.is_empty()
+    }
+
+    fn pop(&mut self) -> Result<Option<Vec<Value>>> {
+        if self.is_exhausted() {
+            return Ok(None);
+        }
+        let part_id = match self.heap.pop() {
+            Some(entry) => entry.part_id,
+            None => return Ok(None),
+        };
+        let cursor = match self.cursors.get_mut(&part_id) {
+            Some(cursor) => cursor,
+            None => return Ok(None),
+        };
+        let row = cursor.rows.pop_front();
+        self.cache_size -= 1;
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+        }
+
+        if !cursor.rows.is_empty() {
+            self.push_head(part_id)?;
+        } else if cursor.last_result.is_some() {
+            self.starving.push_back(part_id);
+        } else {
+            self.cursors.remove(&part_id);
+        }
+        Ok(row)
+    }
+
+    /// Take the last result of a starving partition to fetch its next batch.
+    fn take_starving(&mut self) -> Option<PartitionQueryResult> {
+        if self.is_exhausted() {
+            return None;
+        }
+        while let Some(part_id) = self.starving.pop_front() {
+            if let Some(cursor) = self.cursors.get_mut(&part_id) {
+                if let Some(last_result) = cursor.last_result.take() {
+                    return Some((cursor.part_id_and_table.clone(), last_result));
+                }
+            }
+        }
+        None
+    }
+
+    /// Take all the last results, whose streams should be closed.
+    fn take_last_results(&mut self) -> Vec<PartitionQueryResult> {
+        self.starving.clear();
+        self.cursors
+            .values_mut()
+            .filter_map(|cursor| {
+                cursor
+                    .last_result
+                    .take()
+                    .map(|last_result| (cursor.part_id_and_table.clone(), last_result))
+            })
+            .collect()
+    }
+}
 
 pub struct QueryStreamResult {
     querier: Arc<dyn StreamQuerier + Send + Sync>,
@@ -91,6 +433,9 @@ pub struct QueryStreamResult {
     cache_properties: Vec<String>,
     cache_rows: VecDeque<Vec<Value>>,
     partition_last_result: PartitionQueryResultDeque,
+    merge_sorter: Option<MergeSorter>,
+    parallel_fetcher: Option<ParallelFetcher>,
+    consistency_level: ObTableConsistencyLevel,
     flag: u16,
 }
 
@@ -119,11 +464,18 @@ impl QueryStreamResult {
             cache_properties: vec![],
             cache_rows: VecDeque::new(),
             partition_last_result: VecDeque::new(),
+            merge_sorter: None,
+            parallel_fetcher: None,
+            consistency_level: ObTableConsistencyLevel::Strong,
             flag: DEFAULT_FLAG,
         }
     }
 
-    fn refer_to_new_partition(&mut self, (part_id, ob_table): (i64, Arc<ObTable>)) -> Result<i64> {
+    pub(crate) fn new_query_request(
+        &self,
+        part_id: i64,
+        ob_table: &ObTable,
+    ) -> ObTableQueryRequest {
         let mut req = ObTableQueryRequest::new(
             &self.table_name,
             part_id,
@@ -133,6 +485,23 @@ impl QueryStreamResult {
                 .unwrap_or_else(|| ob_table.operation_timeout()),
             self.flag,
         );
+        req.set_consistency_level(self.consistency_level);
+        req
+    }
+
+    fn new_stream_next_request(&self, session_id: u64, ob_table: &ObTable) -> ObTableStreamRequest {
+        let mut req = ObTableStreamRequest::new(
+            session_id,
+            self.operation_timeout
+                .unwrap_or_else(|| ob_table.operation_timeout()),
+            self.flag,
+        );
+        req.set_stream_next();
+        req
+    }
+
+    fn refer_to_new_partition(&mut self, (part_id, ob_table): (i64, Arc<ObTable>)) -> Result<i64> {
+        let mut req = self.new_query_request(part_id, &ob_table);
 
         let result = self
             .querier
@@ -151,13 +520,7 @@ impl QueryStreamResult {
         (part_id, ob_table): (i64, Arc<ObTable>),
         last_result: &ObTableQueryResult,
     ) -> Result<i64> {
-        let mut req = ObTableStreamRequest::new(
-            last_result.session_id(),
-            self.operation_timeout
-                .unwrap_or_else(|| ob_table.operation_timeout()),
-            self.flag,
-        );
-        req.set_stream_next();
+        let mut req = self.new_stream_next_request(last_result.session_id(), &ob_table);
         let result = self
             .querier
             .clone()
@@ -194,19 +557,45 @@ impl QueryStreamResult {
         self.flag = flag;
     }
 
+    pub fn set_consistency_level(&mut self, consistency_level: ObTableConsistencyLevel) {
+        self.consistency_level = consistency_level;
+    }
+
+    /// Merge the rows of the partitions by the row key, see
+    /// [`QueryResultMode::MergeSort`].
+    pub fn set_merge_sort(&mut self, row_key_columns: Vec<String>) {
+        let reverse = *self.table_query.get_scan_order() == ObScanOrder::Reverse;
+        let limit = self.table_query.get_limit();
+        let limit = (limit >= 0).then_some(limit as usize);
+        self.merge_sorter = Some(MergeSorter::new(row_key_columns, reverse, limit));
+    }
+
+    /// Scan the partitions in parallel, see [`QueryResultMode::Parallel`].
+    pub fn set_parallelism(&mut self, parallelism: usize) {
+        self.parallel_fetcher = Some(ParallelFetcher::new(parallelism));
+    }
+
     pub fn cache_stream_next(
         &mut self,
         part_id_and_table: (i64, Arc<ObTable>),
         mut query_result: ObTableQueryResult,
-    ) {
+    ) -> Result<()> {
         self.cache_properties = query_result.take_properties_names();
+        let rows = query_result.take_properties_rows();
+        let has_next = query_result.is_stream() && query_result.is_stream_next();
 
-        self.cache_rows.extend(query_result.take_properties_rows());
+        if let Some(sorter) = self.merge_sorter.as_mut() {
+            sorter.set_properties(&self.cache_properties)?;
+            return sorter.push(part_id_and_table, rows, has_next.then_some(query_result));
+        }
 
-        if query_result.is_stream() && query_result.is_stream_next() {
+        self.cache_rows.extend(rows);
+
+        if has_next {
             self.partition_last_result
                 .push_back((part_id_and_table, query_result));
         }
+        Ok(())
     }
 
     pub fn init(&mut self) -> Result<()> {
@@ -214,7 +603,11 @@ impl QueryStreamResult {
             return Ok(());
         }
 
-        if self.table_query.batch_size() == -1 {
+        // all the partitions are queried at first for the merge sort, and they
+        // are queried while polling for the parallel scan
+        if self.parallel_fetcher.is_none()
+            && (self.table_query.batch_size() == -1 || self.merge_sorter.is_some())
+        {
             let tuples = std::mem::replace(&mut self.expectant, HashMap::new());
 
             for (_, tuple) in tuples {
@@ -227,8 +620,33 @@ impl QueryStreamResult {
         Ok(())
     }
 
+    /// Initialize the stream with the first results of the partitions which
+    /// have been queried already, e.g. by the asynchronous api.
+    pub fn init_with_results(&mut self, results: Vec<PartitionQueryResult>) -> Result<()> {
+        self.initialized = true;
+        for (part_id_and_table, result) in results {
+            self.expectant.remove(&part_id_and_table.0);
+            if let Err(e) = self.cache_stream_next(part_id_and_table, result) {
+                self.close_eagerly("err");
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
     fn cache_size(&self) -> usize {
-        self.cache_rows.len()
+        match &self.merge_sorter {
+            None => self.cache_rows.len(),
+            Some(sorter) => sorter.cache_size,
+        }
+    }
+
+    /// Whether there are cached rows to return without fetching.
+    fn has_ready_row(&self) -> bool {
+        match &self.merge_sorter {
+            None => !self.cache_rows.is_empty(),
+            Some(sorter) => sorter.is_ready(),
+        }
     }
 
     pub fn close(&mut self) -> Result<()> {
@@ -237,6 +655,25 @@ impl QueryStreamResult {
         }
         self.closed = true;
 
+        if let Some(sorter) = self.merge_sorter.as_mut() {
+            let last_results = sorter.take_last_results();
+            self.partition_last_result.extend(last_results);
+        }
+
+        if let Some(fetcher) = self.parallel_fetcher.as_mut() {
+            fetcher.fetches = FuturesUnordered::new();
+            let sessions: Vec<_> = fetcher.sessions.drain().map(|(_, v)| v).collect();
+            for (part_id_and_table, session_id) in sessions {
+                if let Err(e) = self.close_stream_session(part_id_and_table, session_id) {
+                    debug!(
+                        "QueryStreamResult::close fail to close stream in flight, \
+                         session_id:{}, err: {}",
+                        session_id, e
+                    );
+                }
+            }
+        }
+
         let last_result_num = self.partition_last_result.len();
         let _timer = OBKV_CLIENT_STREAM_HISTOGRAM_VEC
             .with_label_values(&["close_stream"])
@@ -293,11 +730,19 @@ impl QueryStreamResult {
 
     fn close_last_stream_result(
         &mut self,
-        (part_id, ob_table): (i64, Arc<ObTable>),
+        part_id_and_table: (i64, Arc<ObTable>),
         last_result: ObTableQueryResult,
+    ) -> Result<i64> {
+        self.close_stream_session(part_id_and_table, last_result.session_id())
+    }
+
+    fn close_stream_session(
+        &mut self,
+        (part_id, ob_table): (i64, Arc<ObTable>),
+        session_id: u64,
     ) -> Result<i64> {
         let mut req = ObTableStreamRequest::new(
-            last_result.session_id(),
+            session_id,
             self.gen_close_stream_timeout(ob_table.operation_timeout()),
             self.flag,
         );
@@ -308,9 +753,240 @@ impl QueryStreamResult {
             .execute_stream(self, (part_id, ob_table), &mut req)
     }
 
+    /// Whether there is any batch left to fetch.
+    fn has_next_fetch(&self, new_partition: bool) -> bool {
+        match &self.merge_sorter {
+            Some(sorter) => !sorter.is_exhausted() && !sorter.starving.is_empty(),
+            None => {
+                !self.partition_last_result.is_empty()
+                    || (new_partition && !self.expectant.is_empty())
+            }
+        }
+    }
+
+    /// Send the request for the next batch without waiting for the reply,
+    /// the unvisited partitions are queried only if `new_partition` is set.
+    ///
+    /// Returns `None` if there is nothing left to fetch.
+    fn fetch_next_async(&mut self, new_partition: bool) -> Result<Option<PendingFetch>> {
+        if !self.has_next_fetch(new_partition) {
+            return Ok(None);
+        }
+
+        let permit = self.querier.acquire_permit()?;
+        Ok(self
+            .take_next_fetch(new_partition)
+            .map(|fetch| PendingFetch {
+                session: fetch.session,
+                result: Box::new(fetch.result.then(move |res| {
+                    drop(permit);
+                    res
+                })),
+            }))
+    }
+
+    fn take_next_fetch(&mut self, new_partition: bool) -> Option<PendingFetch> {
+        if let Some(sorter) = self.merge_sorter.as_mut() {
+            let (part_id_and_table, last_result) = sorter.take_starving()?;
+            let session_id = last_result.session_id();
+            let req = self.new_stream_next_request(session_id, &part_id_and_table.1);
+            return Some(PendingFetch {
+                session: Some((part_id_and_table.clone(), session_id)),
+                result: self.querier.execute_stream_async(part_id_and_table, req),
+            });
+        }
+
+        while let Some((part_id_and_table, last_result)) = self.partition_last_result.pop_front() {
+            if !(last_result.is_stream() && last_result.is_stream_next()) {
+                continue;
+            }
+            let session_id = last_result.session_id();
+            let req = self.new_stream_next_request(session_id, &part_id_and_table.1);
+            return Some(PendingFetch {
+                session: Some((part_id_and_table.clone(), session_id)),
+                result: self.querier.execute_stream_async(part_id_and_table, req),
+            });
+        }
+
+        if !new_partition {
+            return None;
+        }
+
+        let part_id = *self.expectant.keys().next()?;
+        let (part_id, ob_table) = self.expectant.remove(&part_id)?;
+        let req = self.new_query_request(part_id, &ob_table);
+        Some(PendingFetch {
+            session: None,
+            result: self.querier.execute_query_async((part_id, ob_table), req),
+        })
+    }
+
     fn next_row(&mut self) -> Result<Option<Vec<Value>>> {
         self.row_index += 1;
-        Ok(self.cache_rows.pop_front())
+        match self.merge_sorter.as_mut() {
+            None => Ok(self.cache_rows.pop_front()),
+            Some(sorter) => sorter.pop(),
+        }
+    }
+
+    /// Send the requests of the partitions until the parallelism or the
+    /// query concurrency limit is reached.
+    fn start_parallel_fetches(&mut self) -> Result<()> {
+        loop {
+            match self.parallel_fetcher.as_ref() {
+                Some(fetcher) if !fetcher.is_full() => (),
+                _ => return Ok(()),
+            }
+
+            let fetch = match self.fetch_next_async(true) {
+                Ok(Some(fetch)) => fetch,
+                Ok(None) => return Ok(()),
+                Err(e) => {
+                    let fetcher = self.parallel_fetcher.as_ref().unwrap();
+                    // try again after some of the requests in flight finish
+                    if e.common_err_code() == Some(CommonErrCode::PermitDenied)
+                        && !fetcher.fetches.is_empty()
+                    {
+                        return Ok(());
+                    }
+                    return Err(e);
+                }
+            };
+
+            let fetcher = self.parallel_fetcher.as_mut().unwrap();
+            if let Some(session) = fetch.session {
+                fetcher.sessions.insert((session.0).0, session);
+            }
+            fetcher.fetches.push(fetch.result);
+        }
+    }
+
+    fn poll_parallel(&mut self) -> Poll<Option<Vec<Value>>, Error> {
+        loop {
+            if self.eof {
+                return Ok(Async::Ready(None));
+            }
+
+            if self.closed {
+                return Err(CommonErr(
+                    CommonErrCode::AlreadyClosed,
+                    "Query was closed".to_owned(),
+                ));
+            }
+
+            // the next batches are fetched while the cached rows are consumed
+            if let Err(e) = self.start_parallel_fetches() {
+                self.close_eagerly("err");
+                return Err(e);
+            }
+
+            if !self.cache_rows.is_empty() {
+                return self.next_row().map(Async::Ready);
+            }
+
+            let fetcher = self.parallel_fetcher.as_mut().unwrap();
+            match fetcher.fetches.poll() {
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Ok(Async::Ready(Some((part_id_and_table, result)))) => {
+                    fetcher.sessions.remove(&part_id_and_table.0);
+                    if let Err(e) = self.cache_stream_next(part_id_and_table, result) {
+                        self.close_eagerly("err");
+                        return Err(e);
+                    }
+                }
+                Ok(Async::Ready(None)) => {
+                    self.eof = true;
+                    self.close_eagerly("eof");
+                    return Ok(Async::Ready(None));
+                }
+                Err(e) => {
+                    self.close_eagerly("err");
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    fn next_merged(&mut self) -> Result<Option<Vec<Value>>> {
+        // fetch the partitions whose cached rows are consumed at first
+        while let Some(sorter) = self.merge_sorter.as_mut() {
+            match sorter.take_starving() {
+                None => break,
+                Some((part_id_and_table, last_result)) => {
+                    self.refer_to_last_stream_result(part_id_and_table, &last_result)?;
+                }
+            }
+        }
+
+        if self.has_ready_row() {
+            self.next_row()
+        } else {
+            self.eof = true;
+            self.close_eagerly("eof");
+            Ok(None)
+        }
+    }
+
+    /// Close the sessions opened by the fetches in flight once their replies
+    /// arrive, including the ones of the partitions queried for the first
+    /// time whose session ids are unknown until then.
+    fn close_in_flight(&mut self, pending: Option<PendingFetch>) {
+        let mut fetches = match self.parallel_fetcher.as_mut() {
+            Some(fetcher) => {
+                fetcher.sessions.clear();
+                mem::replace(&mut fetcher.fetches, FuturesUnordered::new())
+            }
+            None => FuturesUnordered::new(),
+        };
+        if let Some(pending) = pending {
+            fetches.push(pending.result);
+        }
+        if fetches.is_empty() {
+            return;
+        }
+
+        let querier = self.querier.clone();
+        let flag = self.flag;
+        let close_all = fetches
+            .then(move |res| Self::close_fetched_session(&querier, flag, res))
+            .for_each(|_| Ok(()));
+        util::spawn_detached(Box::new(close_all));
+    }
+
+    /// Close the session of the fetched result if the stream has more data.
+    fn close_fetched_session(
+        querier: &Arc<dyn StreamQuerier + Send + Sync>,
+        flag: u16,
+        fetched: Result<PartitionQueryResult>,
+    ) -> BoxFuture<()> {
+        let (part_id_and_table, result) = match fetched {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                debug!(
+                    "QueryStreamResult::close_fetched_session fetch in flight failed, err: {}",
+                    e
+                );
+                return Box::new(future::ok(()));
+            }
+        };
+        if !(result.is_stream() && result.is_stream_next()) {
+            return Box::new(future::ok(()));
+        }
+
+        let session_id = result.session_id();
+        let mut req = ObTableStreamRequest::new(session_id, ZERO_TIMEOUT_MS, flag);
+        req.set_stream_last();
+        let close = querier.execute_stream_async(part_id_and_table, req);
+        Box::new(close.then(move |res| -> Result<()> {
+            if let Err(e) = res {
+                debug!(
+                    "QueryStreamResult::close_fetched_session fail to close stream, \
+                     session_id:{}, err: {}",
+                    session_id, e
+                );
+            }
+            Ok(())
+        }))
     }
 
     #[inline]
@@ -353,6 +1029,14 @@ impl QueryStreamResult {
             ));
         }
 
+        if self.merge_sorter.is_some() {
+            return self.next_merged();
+        }
+
+        if self.parallel_fetcher.is_some() {
+            return future::poll_fn(|| self.poll_parallel()).wait();
+        }
+
         //1. Found from cache.
         if !self.cache_rows.is_empty() {
             return self.next_row();
@@ -455,6 +1139,14 @@ impl QueryResultSet {
             QueryResultSet::Some(stream_result) => stream_result.close(),
         }
     }
+
+    /// Convert into an asynchronous [`QueryResultStream`].
+    pub fn into_stream(mut self) -> QueryResultStream {
+        match mem::take(&mut self) {
+            QueryResultSet::None => QueryResultStream::new(None),
+            QueryResultSet::Some(stream_result) => QueryResultStream::new(Some(stream_result)),
+        }
+    }
 }
 
 impl Iterator for QueryResultSet {
@@ -498,12 +1190,185 @@ impl Drop for QueryResultSet {
     }
 }
 
+/// The request in flight for the next batch of a partition.
+struct PendingFetch {
+    // partition and session id of the stream to fetch from, none for the first
+    // query of a partition
+    session: Option<((i64, Arc<ObTable>), u64)>,
+    result: BoxFuture<PartitionQueryResult>,
+}
+
+/// Asynchronous version of [`QueryResultSet`], which yields the rows as
+/// `Vec<Value>` in the order of [`QueryResultStream::properties`].
+///
+/// It's a `futures` 0.1 stream, and the rows are awaited on the std runtimes
+/// such as tokio by [`QueryResultStream::next_row`].
+///
+/// The next batch of the stream is prefetched while the cached rows are
+/// consumed, and the streams on the server are closed when it is dropped.
+/// The streams opened by the requests still in flight are closed once their
+/// replies arrive.
+pub struct QueryResultStream {
+    stream_result: Option<QueryStreamResult>,
+    pending: Option<PendingFetch>,
+    // the failure of prefetching, returned by the next poll
+    prefetch_error: Option<Error>,
+}
+
+impl fmt::Debug for QueryResultStream {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "QueryResultStream {{ stream_result: {:?}, pending: {} }}",
+            self.stream_result,
+            self.pending.is_some()
+        )
+    }
+}
+
+impl QueryResultStream {
+    fn new(stream_result: Option<QueryStreamResult>) -> Self {
+        Self {
+            stream_result,
+            pending: None,
+            prefetch_error: None,
+        }
+    }
+
+    /// The names of the columns of the rows.
+    pub fn properties(&self) -> Vec<String> {
+        match &self.stream_result {
+            None => vec![],
+            Some(stream_result) => stream_result.cache_properties(),
+        }
+    }
+
+    pub fn cache_size(&self) -> usize {
+        match &self.stream_result {
+            None => 0,
+            Some(stream_result) => stream_result.cache_size(),
+        }
+    }
+
+    /// Returns the next row, or None at the end of the stream.
+    pub async fn next_row(&mut self) -> Result<Option<Vec<Value>>> {
+        let mut stream = Compat::new(self);
+        std::future::poll_fn(|cx| stream.poll_next(cx))
+            .await
+            .transpose()
+    }
+}
+
+impl Stream for QueryResultStream {
+    type Error = Error;
+    type Item = Vec<Value>;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        let stream_result = match self.stream_result.as_mut() {
+            None => return Ok(Async::Ready(None)),
+            Some(stream_result) => stream_result,
+        };
+
+        if !stream_result.initialized {
+            return Err(CommonErr(
+                CommonErrCode::NotInitialized,
+                "Query is not initialized".to_owned(),
+            ));
+        }
+
+        if stream_result.parallel_fetcher.is_some() {
+            return stream_result.poll_parallel();
+        }
+
+        loop {
+            if let Some(e) = self.prefetch_error.take() {
+                stream_result.close_eagerly("err");
+                return Err(e);
+            }
+
+            if stream_result.eof {
+                return Ok(Async::Ready(None));
+            }
+
+            if stream_result.closed {
+                return Err(CommonErr(
+                    CommonErrCode::AlreadyClosed,
+                    "Query was closed".to_owned(),
+                ));
+            }
+
+            // prefetch the next batch, but the unvisited partitions are queried
+            // only after the cached rows are consumed
+            if self.pending.is_none() {
+                let new_partition = !stream_result.has_ready_row();
+                self.pending = match stream_result.fetch_next_async(new_partition) {
+                    Ok(pending) => pending,
+                    Err(e) => {
+                        stream_result.close_eagerly("err");
+                        return Err(e);
+                    }
+                };
+            }
+
+            if stream_result.has_ready_row() {
+                let row = stream_result.next_row();
+                if self.pending.is_none() {
+                    // the failure of prefetching is reported on the next poll
+                    match stream_result.fetch_next_async(false) {
+                        Ok(pending) => self.pending = pending,
+                        Err(e) => self.prefetch_error = Some(e),
+                    }
+                }
+                return row.map(Async::Ready);
+            }
+
+            let pending = match self.pending.as_mut() {
+                None => {
+                    stream_result.eof = true;
+                    stream_result.close_eagerly("eof");
+                    return Ok(Async::Ready(None));
+                }
+                Some(pending) => pending,
+            };
+
+            match pending.result.poll() {
+                Ok(Async::NotReady) => return Ok(Async::NotReady),
+                Ok(Async::Ready((part_id_and_table, result))) => {
+                    self.pending = None;
+                    if let Err(e) = stream_result.cache_stream_next(part_id_and_table, result) {
+                        stream_result.close_eagerly("err");
+                        return Err(e);
+                    }
+                }
+                Err(e) => {
+                    self.pending = None;
+                    stream_result.close_eagerly("err");
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for QueryResultStream {
+    fn drop(&mut self) {
+        // the other open streams are closed by the drop of the stream result
+        if let Some(stream_result) = self.stream_result.as_mut() {
+            stream_result.close_in_flight(self.pending.take());
+        }
+    }
+}
+
 /// Table Query Trait
 
 const PRIMARY_INDEX_NAME: &str = "PRIMARY";
 
 pub trait TableQuery {
     fn execute(&self) -> Result<QueryResultSet>;
+    /// Execute the query asynchronously, the future resolves once the first
+    /// batch of all the involved partitions has arrived. The result set can be
+    /// converted into a stream by [`QueryResultSet::into_stream`].
+    fn execute_async(&self) -> StdBoxFuture<QueryResultSet>;
     fn get_table_name(&self) -> String;
     fn set_entity_type(&mut self, entity_type: ObTableEntityType);
     fn entity_type(&self) -> ObTableEntityType;
@@ -529,6 +1394,67 @@ pub trait TableQuery {
     fn filter_string(self, filter_string: &str) -> Self;
     fn htable_filter(self, filter: ObHTableFilter) -> Self;
     fn batch_size(self, batch_size: i32) -> Self;
+    /// Set how the rows of multiple partitions are returned, the default is
+    /// [`QueryResultMode::Sequential`].
+    fn result_mode(self, mode: QueryResultMode) -> Self;
+    /// Set the consistency level of the query, the weak read is served by the
+    /// followers if there are any. The default follows the client config.
+    fn consistency_level(self, consistency_level: ObTableConsistencyLevel) -> Self;
     fn operation_timeout(self, timeout: Duration) -> Self;
     fn clear(&mut self);
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::serde_obkv::value::{CollationLevel, ObjMeta, ObjType};
+
+    fn string_value(s: &str, cs_type: CollationType) -> Value {
+        Value::String(
+            s.to_owned(),
+            ObjMeta::new(ObjType::Varchar, CollationLevel::Implict, cs_type, 10),
+        )
+    }
+
+    #[test]
+    fn test_check_row_key() {
+        let bin = vec![
+            string_value("a", CollationType::UTF8MB4Bin),
+            Value::from(1i64),
+        ];
+        assert!(check_row_key(&bin, None).is_ok());
+        let other = vec![
+            string_value("B", CollationType::UTF8MB4Bin),
+            Value::from(2i64),
+        ];
+        assert!(check_row_key(&bin, Some(&other[..])).is_ok());
+
+        let ci = vec![string_value("a", CollationType::UTF8MB4GeneralCi)];
+        assert!(check_row_key(&ci, None).is_err());
+
+        let mismatched = vec![Value::from(1i64), Value::from(1i64)];
+        assert!(check_row_key(&mismatched, Some(&other[..])).is_err());
+        assert!(check_row_key(&[Value::from(f64::NAN)], None).is_err());
+    }
+
+    #[test]
+    fn test_merge_sorter_properties() {
+        let mut sorter = MergeSorter::new(vec!["c1".to_owned(), "c2".to_owned()], false, None);
+        assert!(sorter
+            .set_properties(&["c2".to_owned(), "v".to_owned()])
+            .is_err());
+        assert!(sorter
+            .set_properties(&["c2".to_owned(), "c1".to_owned()])
+            .is_ok());
+        assert_eq!(vec![1, 0], sorter.row_key_indexes);
+    }
+
+    #[test]
+    fn test_merge_sorter_limit() {
+        let mut sorter = MergeSorter::new(vec!["c1".to_owned()], false, Some(0));
+        assert!(sorter.is_exhausted());
+        assert!(!sorter.is_ready());
+        assert!(sorter.pop().unwrap().is_none());
+        assert!(!MergeSorter::new(vec!["c1".to_owned()], false, None).is_exhausted());
+    }
+}
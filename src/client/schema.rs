@@ -0,0 +1,342 @@
+/*-
+ * #%L
+ * OBKV Table Client Framework
+ * %%
+ * Copyright (C) 2021 OceanBase
+ * %%
+ * OBKV Table Client Framework is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *          http://license.coscl.org.cn/MulanPSL2
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ * #L%
+ */
+
+//! The schema of the tables, see [`ObTableClient::describe_table`].
+//!
+//! [`ObTableClient::describe_table`]: super::table_client::ObTableClient::describe_table
+
+use std::mem;
+
+use crate::{
+    error::{CommonErrCode, Error, Error::Common as CommonErr, Result},
+    location::{part_func_type::PartFuncType, ObPartitionLevel},
+    serde_obkv::value::{decimal::Decimal, CollationType, ObjMeta, ObjType, Value},
+};
+
+/// The schema of the table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableSchema {
+    pub table_name: String,
+    pub table_id: i64,
+    /// The columns in the order of definition, the hidden columns excluded.
+    pub columns: Vec<ColumnSchema>,
+    /// The columns of the primary key in order.
+    pub row_key_columns: Vec<String>,
+    pub partition: PartitionSchema,
+}
+
+impl TableSchema {
+    /// Get the column by name, case-insensitively like the server.
+    pub fn column(&self, name: &str) -> Option<&ColumnSchema> {
+        self.columns
+            .iter()
+            .find(|column| column.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Check the row keys and the properties of the columns against the
+    /// schema, and coerce the values in place, see
+    /// [`ColumnSchema::coerce_value`].
+    pub fn check_values(
+        &self,
+        row_keys: &mut [Value],
+        columns: Option<&[String]>,
+        properties: Option<&mut [Value]>,
+    ) -> Result<()> {
+        // the row keys made of the hidden columns are left to the server
+        if row_keys.len() == self.row_key_columns.len() {
+            for (name, value) in self.row_key_columns.iter().zip(row_keys.iter_mut()) {
+                if let Some(column) = self.column(name) {
+                    *value = column.coerce_value(mem::take(value))?;
+                }
+            }
+        }
+
+        if let (Some(columns), Some(properties)) = (columns, properties) {
+            for (name, value) in columns.iter().zip(properties.iter_mut()) {
+                let column = self.column(name).ok_or_else(|| {
+                    CommonErr(
+                        CommonErrCode::InvalidParam,
+                        format!(
+                            "TableSchema::check_values column not found, table:{}, column:{}",
+                            self.table_name, name
+                        ),
+                    )
+                })?;
+                *value = column.coerce_value(mem::take(value))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The schema of the column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnSchema {
+    pub name: String,
+    /// None for the types the client doesn't support.
+    pub obj_type: Option<ObjType>,
+    /// None for the collations the client doesn't support.
+    pub collation_type: Option<CollationType>,
+    /// The max length of the string types.
+    pub data_length: i32,
+    pub nullable: bool,
+    /// The default value in the text form.
+    pub default_value: Option<String>,
+    /// The position in the primary key starting from 1.
+    pub row_key_position: Option<usize>,
+}
+
+impl ColumnSchema {
+    /// Check the value against the type and the length of the column, the
+    /// integers fitting in the column are converted to the type of the
+    /// column, and so are the floats to the doubles. The values of the types
+    /// unknown are returned as they are.
+    pub fn coerce_value(&self, value: Value) -> Result<Value> {
+        let obj_type = match &self.obj_type {
+            Some(obj_type) => obj_type.clone(),
+            None => return Ok(value),
+        };
+        if value.is_extend() {
+            return Ok(value);
+        }
+        if value.is_none() {
+            if self.nullable {
+                return Ok(value);
+            }
+            return Err(self.mismatch_error("not null", &value));
+        }
+
+        match obj_type {
+            ObjType::TinyInt
+            | ObjType::SmallInt
+            | ObjType::Int32
+            | ObjType::Int64
+            | ObjType::UTinyInt
+            | ObjType::USmallInt
+            | ObjType::UMediumInt
+            | ObjType::UInt32
+            | ObjType::UInt64 => self.coerce_integer(obj_type, value),
+            ObjType::Float | ObjType::UFloat => match value {
+                Value::Float(f, _) => Ok(Value::Float(f, ObjMeta::default_obj_meta(obj_type))),
+                _ => Err(self.mismatch_error("float", &value)),
+            },
+            ObjType::Double | ObjType::UDouble => match value {
+                Value::Float(f, _) => Ok(Value::Double(
+                    f64::from(f),
+                    ObjMeta::default_obj_meta(obj_type),
+                )),
+                Value::Double(f, _) => Ok(Value::Double(f, ObjMeta::default_obj_meta(obj_type))),
+                _ => Err(self.mismatch_error("double", &value)),
+            },
+            ObjType::Number | ObjType::UNumber => {
+                let decimal = match &value {
+                    Value::Decimal(d, _) => d.clone(),
+                    Value::Int8(v, _) => Decimal::from(i64::from(*v)),
+                    Value::UInt8(v, _) => Decimal::from(i64::from(*v)),
+                    Value::Int32(v, _) => Decimal::from(i64::from(*v)),
+                    Value::Int64(v, _) => Decimal::from(*v),
+                    Value::UInt32(v, _) => Decimal::from(i64::from(*v)),
+                    Value::UInt64(v, _) => Decimal::from(*v),
+                    _ => return Err(self.mismatch_error("decimal", &value)),
+                };
+                if obj_type == ObjType::UNumber && decimal.is_negative() {
+                    return Err(self.mismatch_error("unsigned decimal", &value));
+                }
+                Ok(Value::from(decimal))
+            }
+            ObjType::Varchar | ObjType::Char => {
+                let len = match &value {
+                    Value::String(s, _) => s.chars().count(),
+                    Value::Bytes(b, _) => b.len(),
+                    _ => return Err(self.mismatch_error("string", &value)),
+                };
+                self.check_length(len, value)
+            }
+            ObjType::TinyText | ObjType::Text | ObjType::MediumText | ObjType::LongText => {
+                let len = match &value {
+                    Value::String(s, _) => s.len(),
+                    Value::Bytes(b, _) => b.len(),
+                    _ => return Err(self.mismatch_error("text", &value)),
+                };
+                self.check_length(len, value)
+            }
+            _ => Ok(value),
+        }
+    }
+
+    fn coerce_integer(&self, obj_type: ObjType, value: Value) -> Result<Value> {
+        let v: i128 = match value {
+            Value::Bool(b, _) => i128::from(b),
+            Value::Int8(v, _) => i128::from(v),
+            Value::UInt8(v, _) => i128::from(v),
+            Value::Int32(v, _) => i128::from(v),
+            Value::Int64(v, _) => i128::from(v),
+            Value::UInt32(v, _) => i128::from(v),
+            Value::UInt64(v, _) => i128::from(v),
+            _ => return Err(self.mismatch_error("integer", &value)),
+        };
+        let (min, max) = match obj_type {
+            ObjType::TinyInt => (i128::from(i8::MIN), i128::from(i8::MAX)),
+            ObjType::SmallInt => (i128::from(i16::MIN), i128::from(i16::MAX)),
+            ObjType::Int32 => (i128::from(i32::MIN), i128::from(i32::MAX)),
+            ObjType::Int64 => (i128::from(i64::MIN), i128::from(i64::MAX)),
+            ObjType::UTinyInt => (0, i128::from(u8::MAX)),
+            ObjType::USmallInt => (0, i128::from(u16::MAX)),
+            ObjType::UMediumInt => (0, 0xFF_FFFF),
+            ObjType::UInt32 => (0, i128::from(u32::MAX)),
+            _ => (0, i128::from(u64::MAX)),
+        };
+        if v < min || v > max {
+            return Err(self.mismatch_error("integer in range", &value));
+        }
+
+        let meta = ObjMeta::default_obj_meta(obj_type.clone());
+        Ok(match obj_type {
+            ObjType::TinyInt => Value::Int8(v as i8, meta),
+            ObjType::SmallInt | ObjType::Int32 => Value::Int32(v as i32, meta),
+            ObjType::Int64 => Value::Int64(v as i64, meta),
+            ObjType::UTinyInt => Value::UInt8(v as u8, meta),
+            ObjType::USmallInt | ObjType::UMediumInt | ObjType::UInt32 => {
+                Value::UInt32(v as u32, meta)
+            }
+            _ => Value::UInt64(v as u64, meta),
+        })
+    }
+
+    fn check_length(&self, len: usize, value: Value) -> Result<Value> {
+        if self.data_length > 0 && len > self.data_length as usize {
+            return Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                format!(
+                    "ColumnSchema::check_length value too long, column:{}, max_length:{}, length:{}",
+                    self.name, self.data_length, len
+                ),
+            ));
+        }
+        Ok(value)
+    }
+
+    fn mismatch_error(&self, expected: &str, value: &Value) -> Error {
+        CommonErr(
+            CommonErrCode::InvalidParam,
+            format!(
+                "ColumnSchema::coerce_value value mismatched, column:{}, type:{:?}, expected:{}, value:{:?}",
+                self.name, self.obj_type, expected, value
+            ),
+        )
+    }
+}
+
+/// The partitioning of the table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartitionSchema {
+    pub level: ObPartitionLevel,
+    pub part_num: i64,
+    pub first_part: Option<PartitionDesc>,
+    pub sub_part: Option<PartitionDesc>,
+}
+
+/// The partitioning at one level.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartitionDesc {
+    pub func_type: PartFuncType,
+    pub expr: String,
+    pub part_num: i32,
+    /// The bounds of the range or list partitions, empty for the others.
+    pub bounds: Vec<PartitionBound>,
+}
+
+/// The bound of the range or list partition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartitionBound {
+    pub part_id: i64,
+    pub part_name: String,
+    /// The high bound of the range partition, or the values of the list
+    /// partition.
+    pub value: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn new_column(name: &str, obj_type: ObjType, data_length: i32) -> ColumnSchema {
+        ColumnSchema {
+            name: name.to_owned(),
+            obj_type: Some(obj_type),
+            collation_type: Some(CollationType::UTF8MB4GeneralCi),
+            data_length,
+            nullable: false,
+            default_value: None,
+            row_key_position: None,
+        }
+    }
+
+    #[test]
+    fn test_coerce_value() {
+        let column = new_column("c1", ObjType::Int64, 0);
+        assert_eq!(
+            Value::from(1i64),
+            column.coerce_value(Value::from(1)).unwrap()
+        );
+        assert!(column.coerce_value(Value::from("1")).is_err());
+        assert!(column.coerce_value(Value::default()).is_err());
+
+        let column = new_column("c1", ObjType::TinyInt, 0);
+        assert_eq!(
+            Value::from(1i8),
+            column.coerce_value(Value::from(1i64)).unwrap()
+        );
+        assert!(column.coerce_value(Value::from(128)).is_err());
+
+        let column = new_column("c1", ObjType::Varchar, 3);
+        assert!(column.coerce_value(Value::from("abc")).is_ok());
+        assert!(column.coerce_value(Value::from("abcd")).is_err());
+    }
+
+    #[test]
+    fn test_check_values() {
+        let mut row_key_column = new_column("c1", ObjType::Int64, 0);
+        row_key_column.row_key_position = Some(1);
+        let schema = TableSchema {
+            table_name: "t".to_owned(),
+            table_id: 1,
+            columns: vec![row_key_column, new_column("c2", ObjType::Double, 0)],
+            row_key_columns: vec!["c1".to_owned()],
+            partition: PartitionSchema {
+                level: ObPartitionLevel::Zero,
+                part_num: 1,
+                first_part: None,
+                sub_part: None,
+            },
+        };
+
+        let mut row_keys = vec![Value::from(1)];
+        let columns = vec!["c2".to_owned()];
+        let mut properties = vec![Value::from(1.5f32)];
+        schema
+            .check_values(&mut row_keys, Some(&columns), Some(&mut properties))
+            .unwrap();
+        assert_eq!(vec![Value::from(1i64)], row_keys);
+        assert_eq!(vec![Value::from(1.5f64)], properties);
+
+        let columns = vec!["c3".to_owned()];
+        assert!(schema
+            .check_values(&mut row_keys, Some(&columns), Some(&mut properties))
+            .is_err());
+    }
+}
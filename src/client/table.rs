@@ -17,9 +17,15 @@
 
 use std::{collections::HashMap, fmt::Formatter, sync::Arc, time::Duration};
 
+use futures::Future;
+
 use super::{
-    query::{QueryResultSet, QueryStreamResult, StreamQuerier, TableQuery},
-    ClientConfig, Table, TableOpResult,
+    filter::{self, CheckCondition},
+    query::{
+        PartitionQueryResult, QueryResultMode, QueryResultSet, QueryStreamResult, StreamQuerier,
+        TableQuery,
+    },
+    ClientConfig, OperationOptions, Table, TableOpResult,
 };
 use crate::{
     error::{CommonErrCode, Error::Common as CommonErr, Result},
@@ -28,7 +34,8 @@ use crate::{
             codes::ResultCodes,
             payloads::*,
             query::{
-                ObHTableFilter, ObNewRange, ObScanOrder, ObTableQuery, ObTableQueryRequest,
+                ObHTableFilter, ObNewRange, ObScanOrder, ObTableQuery, ObTableQueryAndMutate,
+                ObTableQueryAndMutateRequest, ObTableQueryAndMutateResult, ObTableQueryRequest,
                 ObTableQueryResult, ObTableStreamRequest,
             },
             ObPayload,
@@ -36,6 +43,7 @@ use crate::{
         proxy::Proxy,
     },
     serde_obkv::value::Value,
+    util::{self, BoxFuture, StdBoxFuture},
 };
 
 #[derive(Clone)]
@@ -48,6 +56,8 @@ pub struct ObTable {
     user_name: String,
     database: String,
     rpc_proxy: Proxy,
+    // route the requests by the tablet id on OceanBase 4.x
+    tablet_route: bool,
 }
 
 impl std::fmt::Debug for ObTable {
@@ -68,10 +78,39 @@ impl ObTable {
         payload: &mut T,
         result: &mut R,
     ) -> Result<()> {
+        payload.set_tablet_route(self.tablet_route);
         self.rpc_proxy.execute(payload, result)?;
         Ok(())
     }
 
+    /// Execute the payload asynchronously, the future resolves to the result
+    /// once the reply arrives.
+    pub fn execute_payload_async<T, R>(&self, mut payload: T) -> BoxFuture<R>
+    where
+        T: ObPayload + Send + 'static,
+        R: ObPayload + Default + Send + 'static,
+    {
+        payload.set_tablet_route(self.tablet_route);
+        self.rpc_proxy.execute_async(payload)
+    }
+
+    /// Execute a batch operation asynchronously, see [`Table::execute_batch`].
+    pub fn execute_batch_async(
+        &self,
+        batch_op: ObTableBatchOperation,
+    ) -> BoxFuture<Vec<TableOpResult>> {
+        let payload = ObTableBatchOperationRequest::new(
+            batch_op,
+            self.config.rpc_operation_timeout,
+            self.config.log_level_flag,
+        );
+
+        Box::new(
+            self.execute_payload_async::<_, ObTableBatchOperationResult>(payload)
+                .and_then(|result| -> Result<Vec<TableOpResult>> { result.into() }),
+        )
+    }
+
     pub fn query(&self, table_name: &str) -> impl TableQuery {
         ObTableQueryImpl::new(table_name, Arc::new(self.clone()))
     }
@@ -80,6 +119,28 @@ impl ObTable {
         self.config.rpc_operation_timeout
     }
 
+    /// Mutate the rows matched by the query and filter on the server side,
+    /// see [`ObTableQueryAndMutate`].
+    pub fn query_and_mutate(
+        &self,
+        table_name: &str,
+        query_and_mutate: ObTableQueryAndMutate,
+    ) -> Result<ObTableQueryAndMutateResult> {
+        query_and_mutate.verify()?;
+
+        let mut payload = ObTableQueryAndMutateRequest::new(
+            table_name,
+            0,
+            ObTableEntityType::Dynamic,
+            query_and_mutate,
+            self.config.rpc_operation_timeout,
+            self.config.log_level_flag,
+        );
+        let mut result = ObTableQueryAndMutateResult::new();
+        self.execute_payload(&mut payload, &mut result)?;
+        Ok(result)
+    }
+
     fn execute(
         &self,
         table_name: &str,
@@ -87,6 +148,25 @@ impl ObTable {
         row_keys: Vec<Value>,
         columns: Option<Vec<String>>,
         properties: Option<Vec<Value>>,
+    ) -> Result<ObTableOperationResult> {
+        self.execute_with_options(
+            table_name,
+            operation_type,
+            row_keys,
+            columns,
+            properties,
+            OperationOptions::default(),
+        )
+    }
+
+    fn execute_with_options(
+        &self,
+        table_name: &str,
+        operation_type: ObTableOperationType,
+        row_keys: Vec<Value>,
+        columns: Option<Vec<String>>,
+        properties: Option<Vec<Value>>,
+        options: OperationOptions,
     ) -> Result<ObTableOperationResult> {
         let mut payload = ObTableOperationRequest::new(
             table_name,
@@ -97,6 +177,14 @@ impl ObTable {
             self.config.rpc_operation_timeout,
             self.config.log_level_flag,
         );
+        options.apply(&mut payload);
+        if operation_type == ObTableOperationType::Get {
+            payload.set_consistency_level(
+                options
+                    .get_consistency_level()
+                    .unwrap_or(self.config.read_consistency_level),
+            );
+        }
         let mut result = ObTableOperationResult::new();
         self.execute_payload(&mut payload, &mut result)?;
         Ok(result)
@@ -113,6 +201,7 @@ pub struct Builder {
     password: String,
     database: String,
     rpc_proxy: Option<Proxy>,
+    tablet_route: bool,
 }
 
 impl Builder {
@@ -126,6 +215,7 @@ impl Builder {
             password: "".to_owned(),
             database: "".to_owned(),
             rpc_proxy: None,
+            tablet_route: false,
         }
     }
 
@@ -159,6 +249,13 @@ impl Builder {
         self
     }
 
+    /// Route the requests by the tablet id, which is required by the servers
+    /// of OceanBase 4.x.
+    pub fn tablet_route(mut self, tablet_route: bool) -> Self {
+        self.tablet_route = tablet_route;
+        self
+    }
+
     pub fn build(self) -> ObTable {
         assert!(self.rpc_proxy.is_some(), "missing necessary rpc proxy");
         ObTable {
@@ -169,6 +266,7 @@ impl Builder {
             user_name: self.user_name,
             database: self.database,
             rpc_proxy: self.rpc_proxy.unwrap(),
+            tablet_route: self.tablet_route,
         }
     }
 }
@@ -283,6 +381,46 @@ impl Table for ObTable {
             .affected_rows())
     }
 
+    fn append_with_options(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+        options: OperationOptions,
+    ) -> Result<ObTableEntity> {
+        Ok(self
+            .execute_with_options(
+                table_name,
+                ObTableOperationType::Append,
+                row_keys,
+                Some(columns),
+                Some(properties),
+                options,
+            )?
+            .take_entity())
+    }
+
+    fn increment_with_options(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+        options: OperationOptions,
+    ) -> Result<ObTableEntity> {
+        Ok(self
+            .execute_with_options(
+                table_name,
+                ObTableOperationType::Increment,
+                row_keys,
+                Some(columns),
+                Some(properties),
+                options,
+            )?
+            .take_entity())
+    }
+
 
     fn delete(&self, table_name: &str, row_keys: Vec<Value>) -> Result<i64> {
         Ok(self
@@ -308,6 +446,96 @@ impl Table for ObTable {
             .take_properties())
     }
 
+    fn get_with_options(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        options: OperationOptions,
+    ) -> Result<HashMap<String, Value>> {
+        Ok(self
+            .execute_with_options(
+                table_name,
+                ObTableOperationType::Get,
+                row_keys,
+                Some(columns),
+                None,
+                options,
+            )?
+            .take_entity()
+            .take_properties())
+    }
+
+    fn check_and_insert(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        condition: CheckCondition,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> Result<bool> {
+        filter::check_and_mutate(
+            row_keys,
+            &condition,
+            ObTableOperationType::Insert,
+            Some(columns),
+            Some(properties),
+            |q| self.query_and_mutate(table_name, q),
+        )
+    }
+
+    fn check_and_insert_up(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        condition: CheckCondition,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> Result<bool> {
+        filter::check_and_mutate(
+            row_keys,
+            &condition,
+            ObTableOperationType::InsertOrUpdate,
+            Some(columns),
+            Some(properties),
+            |q| self.query_and_mutate(table_name, q),
+        )
+    }
+
+    fn check_and_update(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        condition: CheckCondition,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> Result<bool> {
+        filter::check_and_mutate(
+            row_keys,
+            &condition,
+            ObTableOperationType::Update,
+            Some(columns),
+            Some(properties),
+            |q| self.query_and_mutate(table_name, q),
+        )
+    }
+
+    fn check_and_delete(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        condition: CheckCondition,
+    ) -> Result<bool> {
+        filter::check_and_mutate(
+            row_keys,
+            &condition,
+            ObTableOperationType::Del,
+            None,
+            None,
+            |q| self.query_and_mutate(table_name, q),
+        )
+    }
+
     fn batch_operation(&self, ops_num_hint: usize) -> ObTableBatchOperation {
         ObTableBatchOperation::with_ops_num(ops_num_hint)
     }
@@ -324,7 +552,7 @@ impl Table for ObTable {
         );
         let mut result = ObTableBatchOperationResult::new();
 
-        self.rpc_proxy.execute(&mut payload, &mut result)?;
+        self.execute_payload(&mut payload, &mut result)?;
 
         result.into()
     }
@@ -375,9 +603,9 @@ impl StreamQuerier for ObTableStreamQuerier {
         payload: &mut ObTableQueryRequest,
     ) -> Result<i64> {
         let mut result = ObTableQueryResult::new();
-        ob_table.rpc_proxy.execute(payload, &mut result)?;
+        ob_table.execute_payload(payload, &mut result)?;
         let row_count = result.row_count();
-        stream_result.cache_stream_next((part_id, ob_table), result);
+        stream_result.cache_stream_next((part_id, ob_table), result)?;
         Ok(row_count)
     }
 
@@ -389,18 +617,37 @@ impl StreamQuerier for ObTableStreamQuerier {
     ) -> Result<i64> {
         let mut result = ObTableQueryResult::new();
         let is_stream_next = payload.is_stream_next();
-        ob_table.rpc_proxy.execute(payload, &mut result)?;
+        ob_table.execute_payload(payload, &mut result)?;
         let row_count = result.row_count();
         if is_stream_next {
-            stream_result.cache_stream_next((part_id, ob_table), result);
+            stream_result.cache_stream_next((part_id, ob_table), result)?;
         }
         Ok(row_count)
     }
+
+    fn execute_query_async(
+        &self,
+        (part_id, ob_table): (i64, Arc<ObTable>),
+        payload: ObTableQueryRequest,
+    ) -> BoxFuture<PartitionQueryResult> {
+        let result = ob_table.execute_payload_async::<_, ObTableQueryResult>(payload);
+        Box::new(result.map(move |result| ((part_id, ob_table), result)))
+    }
+
+    fn execute_stream_async(
+        &self,
+        (part_id, ob_table): (i64, Arc<ObTable>),
+        payload: ObTableStreamRequest,
+    ) -> BoxFuture<PartitionQueryResult> {
+        let result = ob_table.execute_payload_async::<_, ObTableQueryResult>(payload);
+        Box::new(result.map(move |result| ((part_id, ob_table), result)))
+    }
 }
 
 pub struct ObTableQueryImpl {
     operation_timeout: Option<Duration>,
     entity_type: ObTableEntityType,
+    consistency_level: Option<ObTableConsistencyLevel>,
     table_name: String,
     table: Arc<ObTable>,
     table_query: ObTableQuery,
@@ -411,6 +658,7 @@ impl ObTableQueryImpl {
         Self {
             operation_timeout: None,
             entity_type: ObTableEntityType::Dynamic,
+            consistency_level: None,
             table_name: table_name.to_owned(),
             table,
             table_query: ObTableQuery::new(),
@@ -421,15 +669,8 @@ impl ObTableQueryImpl {
         //FIXME table query should set partition_id
         self.table_query = ObTableQuery::new();
     }
-}
-
-impl TableQuery for ObTableQueryImpl {
-    fn execute(&self) -> Result<QueryResultSet> {
-        let mut partition_table: HashMap<i64, (i64, Arc<ObTable>)> = HashMap::new();
-        partition_table.insert(0, (0, self.table.clone()));
-
-        self.table_query.verify()?;
 
+    fn new_stream_result(&self) -> QueryStreamResult {
         let mut stream_result = QueryStreamResult::new(
             Arc::new(ObTableStreamQuerier::new()),
             self.table_query.clone(),
@@ -437,14 +678,48 @@ impl TableQuery for ObTableQueryImpl {
 
         stream_result.set_entity_type(self.entity_type());
         stream_result.set_table_name(&self.table_name);
-        stream_result.set_expectant(partition_table);
         stream_result.set_operation_timeout(self.operation_timeout);
         stream_result.set_flag(self.table.config.log_level_flag);
+        stream_result.set_consistency_level(
+            self.consistency_level
+                .unwrap_or(self.table.config.read_consistency_level),
+        );
+        stream_result
+    }
+}
+
+impl TableQuery for ObTableQueryImpl {
+    fn execute(&self) -> Result<QueryResultSet> {
+        let mut partition_table: HashMap<i64, (i64, Arc<ObTable>)> = HashMap::new();
+        partition_table.insert(0, (0, self.table.clone()));
+
+        self.table_query.verify()?;
+
+        let mut stream_result = self.new_stream_result();
+        stream_result.set_expectant(partition_table);
         stream_result.init()?;
 
         Ok(QueryResultSet::from_stream_result(stream_result))
     }
 
+    fn execute_async(&self) -> StdBoxFuture<QueryResultSet> {
+        if let Err(e) = self.table_query.verify() {
+            return util::into_std(Box::new(futures::future::err(e)));
+        }
+
+        let mut stream_result = self.new_stream_result();
+        let payload = stream_result.new_query_request(0, &self.table);
+        let table = self.table.clone();
+        util::into_std(Box::new(
+            self.table
+                .execute_payload_async::<_, ObTableQueryResult>(payload)
+                .and_then(move |result| {
+                    stream_result.init_with_results(vec![((0, table), result)])?;
+                    Ok(QueryResultSet::from_stream_result(stream_result))
+                }),
+        ))
+    }
+
     fn get_table_name(&self) -> String {
         self.table_name.to_owned()
     }
@@ -588,6 +863,25 @@ impl TableQuery for ObTableQueryImpl {
         self
     }
 
+    /// Only one partition is queried by [`ObTable`], so the mode makes no
+    /// difference.
+    fn result_mode(self, _mode: QueryResultMode) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    /// The query is always sent to the server of [`ObTable`], so only the
+    /// consistency level of the request is changed.
+    fn consistency_level(mut self, consistency_level: ObTableConsistencyLevel) -> Self
+    where
+        Self: Sized,
+    {
+        self.consistency_level = Some(consistency_level);
+        self
+    }
+
     fn operation_timeout(mut self, timeout: Duration) -> Self
     where
         Self: Sized,
@@ -17,8 +17,8 @@
 
 use std::{
     borrow::Borrow,
-    collections::HashMap,
-    isize,
+    collections::{HashMap, HashSet},
+    fmt, isize,
     sync::{
         atomic::{AtomicBool, AtomicI64, AtomicIsize, AtomicUsize, Ordering},
         Arc, Mutex, RwLock,
@@ -27,46 +27,59 @@ use std::{
     time::Duration,
 };
 
-use futures::{future, Future};
+use crossbeam::{unbounded, Receiver, Sender};
+use futures::{
+    future::{self, Loop},
+    stream, Future, Stream,
+};
 use futures_cpupool::{Builder as CpuPoolBuilder, CpuPool};
 use prometheus::*;
 use rand::{seq::SliceRandom, thread_rng};
 use scheduled_thread_pool::ScheduledThreadPool;
 
 use super::{
+    filter::{self, CheckCondition},
+    metadata::{CachedMetadataProvider, MetadataProvider, MysqlMetadataProvider},
     metrics::OBKV_CLIENT_RETRY_COUNTER_VEC,
-    ocp::{ObOcpModelManager, OcpModel},
-    query::{QueryResultSet, QueryStreamResult, StreamQuerier, TableQuery},
+    ocp::{parse_rs_list, OcpModel, RsList},
+    query::{
+        PartitionQueryResult, QueryResultMode, QueryResultSet, QueryStreamResult, StreamQuerier,
+        TableQuery,
+    },
+    schema::TableSchema,
     table::{self, ObTable},
-    ClientConfig, Table, TableOpResult,
+    AsyncTable, ClientConfig, OperationOptions, Table, TableOpResult,
 };
 use crate::{
     error::{self, CommonErrCode, Error::Common as CommonErr, Result},
     location::{
         ob_part_constants::{MASK, PART_ID_SHIFT},
-        ObPartitionLevel, ObServerAddr, ObTableLocation, ReplicaLocation, TableEntry,
-        TableEntryKey,
+        ObPartitionLevel, ObServerAddr, ObServerLdc, ObTableLocation, ObVersion, ReplicaLocation,
+        TableEntry, TableEntryKey,
     },
     rpc::{
         conn_pool::{Builder as ConnPoolBuilder, ConnPool},
+        event_loop::EventLoopGroup,
         protocol::{
             payloads::{
-                ObTableBatchOperation, ObTableEntityType, ObTableOperationRequest,
-                ObTableOperationResult, ObTableOperationType,
+                ObTableBatchOperation, ObTableConsistencyLevel, ObTableEntity, ObTableEntityType,
+                ObTableOperationRequest, ObTableOperationResult, ObTableOperationType,
             },
             query::{
-                ObHTableFilter, ObNewRange, ObScanOrder, ObTableQuery, ObTableQueryRequest,
+                ObHTableFilter, ObNewRange, ObScanOrder, ObTableQuery, ObTableQueryAndMutate,
+                ObTableQueryAndMutateRequest, ObTableQueryAndMutateResult, ObTableQueryRequest,
                 ObTableQueryResult, ObTableStreamRequest,
             },
+            ObPayload,
         },
         proxy::Proxy,
         Builder as ConnBuilder,
     },
     serde_obkv::value::Value,
     util::{
-        assert_not_empty, current_time_millis, duration_to_millis, millis_to_secs,
+        self, assert_not_empty, current_time_millis, duration_to_millis, millis_to_secs,
         permit::{PermitGuard, Permits},
-        HandyRwLock,
+        BoxFuture, HandyRwLock, StdBoxFuture,
     },
     ResultCodes,
 };
@@ -89,36 +102,44 @@ lazy_static! {
 }
 
 const MAX_PRIORITY: isize = 50;
+// The partition id of the requests sent to ODP, which routes the requests by
+// the row keys itself.
+const ODP_PARTITION_ID: i64 = 0;
 
 pub struct ServerRoster {
     max_priority: AtomicIsize,
     roster: RwLock<Arc<Vec<ObServerAddr>>>,
+    ldc: RwLock<Arc<ObServerLdc>>,
 }
 
 impl ServerRoster {
-    fn new() -> Self {
+    fn new(idc: Option<String>) -> Self {
         ServerRoster {
             max_priority: AtomicIsize::new(0),
             roster: RwLock::new(Arc::new(vec![])),
+            ldc: RwLock::new(Arc::new(ObServerLdc::new(idc))),
         }
     }
 
+    /// Peek a random server among the nearest ones to the client.
     fn peek_random_server(&self) -> Option<ObServerAddr> {
         let roster = self.roster.rl();
-        if roster.is_empty() {
-            None
-        } else {
-            let mut rng = thread_rng();
-            roster.choose(&mut rng).cloned()
-        }
+        self.ldc.rl().choose(&roster).cloned()
     }
 
     pub fn get_members(&self) -> Arc<Vec<ObServerAddr>> {
         self.roster.rl().clone()
     }
 
+    pub fn ldc(&self) -> Arc<ObServerLdc> {
+        self.ldc.rl().clone()
+    }
+
     fn reset(&self, members: Vec<ObServerAddr>) {
         self.max_priority.store(0, Ordering::Release);
+        let mut ldc = (**self.ldc.rl()).clone();
+        ldc.resolve_region(&members);
+        (*self.ldc.wl()) = Arc::new(ldc);
         (*self.roster.wl()) = Arc::new(members);
     }
 
@@ -184,8 +205,8 @@ type Lock = Mutex<u8>;
 
 // ObTableClient inner implemetation.
 struct ObTableClientInner {
-    location: ObTableLocation,
-    ocp_manager: ObOcpModelManager,
+    location: Arc<ObTableLocation>,
+    metadata: Arc<dyn MetadataProvider>,
     config: ClientConfig,
     table_entry_refresh_continuous_failure_count: AtomicUsize,
     datasource_name: String,
@@ -205,11 +226,16 @@ struct ObTableClientInner {
     table_roster: RwLock<HashMap<ObServerAddr, Arc<ObTable>>>,
     server_roster: ServerRoster,
     running_mode: RunningMode,
+    // All the requests are sent to the ODP(obproxy) and routed by it if set,
+    // no location is fetched from the servers in this mode.
+    odp_addr: Option<ObServerAddr>,
     //TableName -> TableEntry
     table_locations: RwLock<HashMap<String, Arc<TableEntry>>>,
     table_mutexs: RwLock<HashMap<String, Arc<Lock>>>,
     //TableName -> rowKey element
     table_row_key_element: RwLock<HashMap<String, HashMap<String, i32>>>,
+    // the schemas loaded to validate the values
+    table_schemas: RwLock<HashMap<String, Arc<TableSchema>>>,
     connection_pools: RwLock<HashMap<ObServerAddr, Arc<ConnPool>>>,
 
     _retry_on_change_master: bool,
@@ -220,16 +246,24 @@ struct ObTableClientInner {
     last_refresh_metadata_ts: AtomicUsize,
 
     conn_init_thread_pool: Arc<ScheduledThreadPool>,
+    conn_event_loops: Arc<EventLoopGroup>,
 
     // table_name => thread pool
     table_batch_op_thread_pools: Arc<RwLock<HashMap<String, Arc<CpuPool>>>>,
     // query concurrency control
     query_permits: Option<Permits>,
+
+    // the workers refreshing the table entries in the background
+    refresh_workers: CpuPool,
+    // the subscribers of the changes of the locations
+    location_subscribers: RwLock<Vec<Sender<LocationEvent>>>,
 }
 
 impl ObTableClientInner {
+    #[allow(clippy::too_many_arguments)]
     fn internal_new(
         param_url: String,
+        rs_list: Option<RsList>,
         full_user_name: String,
         password: String,
         user_name: String,
@@ -237,20 +271,46 @@ impl ObTableClientInner {
         cluster_name: String,
         database: String,
         running_mode: RunningMode,
+        odp_addr: Option<ObServerAddr>,
+        metadata_provider: Option<Arc<dyn MetadataProvider>>,
         config: ClientConfig,
     ) -> Result<Self> {
         let conn_init_thread_num = config.conn_init_thread_num;
-        let ocp_manager =
-            ObOcpModelManager::new(config.rslist_acquire_timeout, &config.ocp_model_cache_file)?;
+        let conn_event_loops = EventLoopGroup::new("conn_io_{}", config.conn_event_loop_num)?;
+        let datasource_name = "".to_owned();
+        let location = Arc::new(ObTableLocation::new(config.clone()));
+        let mut metadata: Arc<dyn MetadataProvider> = match metadata_provider {
+            Some(provider) => provider,
+            None => Arc::new(MysqlMetadataProvider::new(
+                location.clone(),
+                param_url.clone(),
+                rs_list,
+                datasource_name.clone(),
+                config.clone(),
+            )?),
+        };
+        if let Some(cache_path) = &config.table_entry_cache_file {
+            metadata = Arc::new(CachedMetadataProvider::new(
+                metadata,
+                cache_path,
+                config.table_entry_cache_max_stale,
+            ));
+        }
 
         let query_permits = if let Some(max) = config.query_concurrency_limit {
             Some(Permits::new(max))
         } else {
             None
         };
+        let server_roster = ServerRoster::new(config.idc.clone());
+        let refresh_workers = CpuPoolBuilder::new()
+            .name_prefix("location-refresh-")
+            .pool_size(config.refresh_workers_num.max(1))
+            .create();
 
         Ok(Self {
-            ocp_manager,
+            location,
+            metadata,
             full_user_name,
             param_url,
             password,
@@ -258,22 +318,23 @@ impl ObTableClientInner {
             tenant_name,
             cluster_name,
             database,
-            datasource_name: "".to_owned(),
+            datasource_name,
             running_mode,
-            config: config.clone(),
+            odp_addr,
+            config,
 
-            location: ObTableLocation::new(config),
             initialized: AtomicBool::new(false),
             closed: AtomicBool::new(false),
             status_mutex: Mutex::new(0),
             table_entry_refresh_continuous_failure_count: AtomicUsize::new(0),
             ocp_model: RwLock::new(OcpModel::new()),
             table_roster: RwLock::new(HashMap::new()),
-            server_roster: ServerRoster::new(),
+            server_roster,
             table_locations: RwLock::new(HashMap::new()),
             connection_pools: RwLock::new(HashMap::new()),
             table_mutexs: RwLock::new(HashMap::new()),
             table_row_key_element: RwLock::new(HashMap::new()),
+            table_schemas: RwLock::new(HashMap::new()),
             table_continuous_failures: RwLock::new(HashMap::new()),
             _retry_on_change_master: true, //TODO it's useless right now.
             refresh_metadata_mutex: Mutex::new(0),
@@ -283,8 +344,11 @@ impl ObTableClientInner {
                 "conn_init_{}",
                 conn_init_thread_num,
             )),
+            conn_event_loops: Arc::new(conn_event_loops),
             table_batch_op_thread_pools: Arc::new(RwLock::new(HashMap::new())),
             query_permits,
+            refresh_workers,
+            location_subscribers: RwLock::new(Vec::new()),
         })
     }
 
@@ -311,6 +375,12 @@ impl ObTableClientInner {
     }
 
     fn on_table_op_failure(&self, table_name: &str, error: &error::Error) -> Result<()> {
+        // the location is maintained by the ODP
+        if self.is_odp_mode() {
+            return Ok(());
+        }
+        self.on_schema_error(table_name, error);
+
         if error.need_refresh_table() {
             debug!(
                 "ObTableClientInner::on_table_op_failure: found error requiring refresh, \
@@ -356,6 +426,71 @@ impl ObTableClientInner {
         }
     }
 
+    /// Load the metadata from a server chosen by the priorities in the server
+    /// roster, the priority of the server is degraded on failure.
+    fn load_with_priority<T>(&self, load: impl FnOnce(&ObServerAddr) -> Result<T>) -> Result<T> {
+        let server_roster = &self.server_roster;
+        let priority_timeout = self.config.server_address_priority_timeout;
+        let mut rs_list: Vec<ObServerAddr> = vec![];
+        let grade_time = current_time_millis() as usize;
+
+        let max_priority = server_roster.max_priority();
+
+        for addr in server_roster.get_members().iter() {
+            if addr.priority() == max_priority
+                || grade_time - addr.grant_priority_times()
+                    > duration_to_millis(&priority_timeout) as usize
+            {
+                rs_list.push(addr.to_owned());
+            }
+        }
+
+        if rs_list.is_empty() {
+            return Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                "Empty rs list".to_owned(),
+            ));
+        }
+
+        let addr = server_roster.ldc().choose(&rs_list).unwrap().to_owned();
+
+        match load(&addr) {
+            Ok(v) => {
+                if addr.priority() != 0 {
+                    let grant_priority_times = current_time_millis() as usize;
+                    addr.set_priority(0);
+                    addr.set_grant_priority_times(grant_priority_times);
+                    server_roster.upgrade_max_priority(0);
+                }
+
+                Ok(v)
+            }
+            Err(e) => {
+                let grant_priority_times = current_time_millis() as usize;
+                addr.set_grant_priority_times(grant_priority_times);
+                server_roster.downgrade_max_priority(addr.decrement_priority_and_get(1));
+                Err(e)
+            }
+        }
+    }
+
+    /// Load the table entry from a random server in the rs list.
+    fn load_table_entry_randomly(&self, key: &TableEntryKey) -> Result<TableEntry> {
+        let random_server = self
+            .ocp_model
+            .rl()
+            .observer_addrs
+            .choose(&mut thread_rng())
+            .cloned();
+        match random_server {
+            Some(addr) => self.metadata.load_table_entry(&addr, key),
+            None => Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                "Empty rs list".to_owned(),
+            )),
+        }
+    }
+
     fn refresh_table_entry(
         &self,
         table_name: &str,
@@ -372,33 +507,29 @@ impl ObTableClientInner {
             table_name,
         );
 
-        let server_roster = &self.server_roster;
-        let connect_timeout = self.config.table_entry_acquire_connect_timeout;
-        let read_timeout = self.config.table_entry_acquire_read_timeout;
-        let priority_timeout = self.config.server_address_priority_timeout;
-
         let result = if let Some(table_entry) = table_entry {
-            self.location.load_table_location_with_priority(
-                server_roster,
-                &table_entry_key,
-                table_entry.borrow(),
-                connect_timeout,
-                read_timeout,
-                priority_timeout,
-            )
+            let table_entry: &TableEntry = table_entry.borrow();
+            let part_entry = self.load_with_priority(|addr| {
+                self.metadata
+                    .load_partition_location(addr, &table_entry_key, table_entry)
+            })?;
+            //Clone a new table entry to return.
+            let mut table_entry = table_entry.clone();
+            //Update partiton entry and refresh_time
+            table_entry.set_partition_entry(Some(part_entry));
+            table_entry.set_refresh_time_mills(current_time_millis());
+            Ok(table_entry)
         } else {
-            let mut table_entry: TableEntry = self.location.load_table_entry_with_priority(
-                server_roster,
-                &table_entry_key,
-                connect_timeout,
-                read_timeout,
-                priority_timeout,
-            )?;
+            let mut table_entry: TableEntry = self.load_with_priority(|addr| {
+                self.metadata.load_table_entry(addr, &table_entry_key)
+            })?;
 
             if table_entry.is_partition_table() {
                 match self.running_mode {
+                    // the row key element added overrides the one discovered
                     RunningMode::Normal => match self.table_row_key_element.rl().get(table_name) {
                         Some(v) => table_entry.set_row_key_element(v.clone()),
+                        None if !table_entry.row_key_element().is_empty() => (),
                         None => {
                             return Err(CommonErr(
                                 CommonErrCode::NotFound,
@@ -432,12 +563,18 @@ impl ObTableClientInner {
         table_name: &str,
         row_key: &[Value],
         refresh: bool,
+        consistency_level: ObTableConsistencyLevel,
     ) -> Result<(i64, Arc<ObTable>)> {
+        if self.is_odp_mode() {
+            return Ok((ODP_PARTITION_ID, self.get_odp_table()?));
+        }
+
         let table_entry = self.get_or_refresh_table_entry(table_name, refresh)?;
         let part_id = self.get_partition(&table_entry, row_key)?;
-        self.get_or_create_table(table_name, &table_entry, part_id)
+        self.get_or_create_table(table_name, &table_entry, part_id, consistency_level)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn get_tables(
         &self,
         table_name: &str,
@@ -446,19 +583,30 @@ impl ObTableClientInner {
         end: &[Value],
         end_inclusive: bool,
         refresh: bool,
+        consistency_level: ObTableConsistencyLevel,
     ) -> Result<Vec<(i64, Arc<ObTable>)>> {
+        if self.is_odp_mode() {
+            return Ok(vec![(ODP_PARTITION_ID, self.get_odp_table()?)]);
+        }
+
         //1. get table entry info
         let table_entry = self.get_or_refresh_table_entry(table_name, refresh)?;
 
         //2. get replica locaton
-        let part_id_with_replicas: Vec<(i64, ReplicaLocation)> =
-            self.get_partition_leaders(&table_entry, start, start_inclusive, end, end_inclusive)?;
+        let part_id_with_replicas: Vec<(i64, ReplicaLocation)> = self.get_partition_replicas(
+            &table_entry,
+            start,
+            start_inclusive,
+            end,
+            end_inclusive,
+            consistency_level,
+        )?;
 
         let mut result: Vec<(i64, Arc<ObTable>)> = vec![];
 
         for (part_id, replica_location) in part_id_with_replicas {
             if let Some(table) = self.table_roster.rl().get(replica_location.addr()) {
-                result.push((part_id, table.clone()));
+                result.push((table_entry.route_id(part_id), table.clone()));
                 continue;
             }
             //Table not found, try to refresh it and retry get it again.
@@ -466,12 +614,13 @@ impl ObTableClientInner {
                   replica_location.addr());
             self.sync_refresh_metadata()?;
             let table_entry = self.get_or_refresh_table_entry(table_name, true)?;
+            let ldc = self.server_roster.ldc();
             match table_entry.get_partition_location_with_part_id(part_id) {
-                Some(location) => match location.leader() {
+                Some(location) => match location.get_replica(consistency_level, &ldc) {
                     Some(leader) => {
                         //Found leader of replication ,try to get table from table roster
                         if let Some(ob_table) = self.table_roster.rl().get(leader.addr()) {
-                            result.push((part_id, ob_table.clone()));
+                            result.push((location.route_id(part_id), ob_table.clone()));
                         } else {
                             return Err(CommonErr(
                                 CommonErrCode::NotFound,
@@ -508,14 +657,222 @@ impl ObTableClientInner {
         Ok(result)
     }
 
+    /// Get the tables of the partitions covered by the key ranges of the query.
+    fn get_query_tables(
+        &self,
+        table_name: &str,
+        table_query: &ObTableQuery,
+        consistency_level: ObTableConsistencyLevel,
+    ) -> Result<HashMap<i64, (i64, Arc<ObTable>)>> {
+        let mut partition_table: HashMap<i64, (i64, Arc<ObTable>)> = HashMap::new();
+
+        for range in table_query.get_key_ranges() {
+            let border_flag = range.get_border_flag();
+            let pairs = self.get_tables(
+                table_name,
+                range.get_start_key().keys(),
+                border_flag.is_inclusive_start(),
+                range.get_end_key().keys(),
+                border_flag.is_inclusive_end(),
+                false,
+                consistency_level,
+            )?;
+
+            for (part_id, ob_table) in pairs {
+                partition_table.insert(part_id, (part_id, ob_table));
+            }
+        }
+        Ok(partition_table)
+    }
+
+    /// Asynchronous version of `get_table`, the table entry not cached or the
+    /// connections not built yet are loaded on the refresh workers instead of
+    /// the caller's thread.
+    fn get_table_async(
+        self: &Arc<Self>,
+        table_name: &str,
+        row_key: &[Value],
+        consistency_level: ObTableConsistencyLevel,
+    ) -> BoxFuture<(i64, Arc<ObTable>)> {
+        if self.is_table_cached(table_name, row_key, consistency_level) {
+            return Box::new(future::result(self.get_table(
+                table_name,
+                row_key,
+                false,
+                consistency_level,
+            )));
+        }
+        let inner = self.clone();
+        let table_name = table_name.to_owned();
+        let row_key = row_key.to_vec();
+        Box::new(
+            self.refresh_workers
+                .spawn_fn(move || inner.get_table(&table_name, &row_key, false, consistency_level)),
+        )
+    }
+
+    /// Asynchronous version of `get_query_tables`, see `get_table_async`.
+    fn get_query_tables_async(
+        self: &Arc<Self>,
+        table_name: &str,
+        table_query: &ObTableQuery,
+        consistency_level: ObTableConsistencyLevel,
+    ) -> BoxFuture<HashMap<i64, (i64, Arc<ObTable>)>> {
+        let cached = table_query.get_key_ranges().iter().all(|range| {
+            let border_flag = range.get_border_flag();
+            self.are_tables_cached(
+                table_name,
+                range.get_start_key().keys(),
+                border_flag.is_inclusive_start(),
+                range.get_end_key().keys(),
+                border_flag.is_inclusive_end(),
+                consistency_level,
+            )
+        });
+        if cached {
+            return Box::new(future::result(self.get_query_tables(
+                table_name,
+                table_query,
+                consistency_level,
+            )));
+        }
+        let inner = self.clone();
+        let table_name = table_name.to_owned();
+        let table_query = table_query.clone();
+        Box::new(
+            self.refresh_workers.spawn_fn(move || {
+                inner.get_query_tables(&table_name, &table_query, consistency_level)
+            }),
+        )
+    }
+
+    /// Returns true if the table to the ODP is built.
+    fn is_odp_table_cached(&self) -> bool {
+        match &self.odp_addr {
+            Some(addr) => self.table_roster.rl().contains_key(addr),
+            None => true,
+        }
+    }
+
+    /// Returns true if the table to the replica of the partition is built, so
+    /// it's got without connecting to the server.
+    fn is_partition_table_cached(
+        &self,
+        table_entry: &Arc<TableEntry>,
+        part_id: i64,
+        consistency_level: ObTableConsistencyLevel,
+    ) -> bool {
+        match self.get_partition_replica(table_entry, part_id, consistency_level) {
+            Some((_, Some(replica))) => self.table_roster.rl().contains_key(replica.addr()),
+            // no replica to connect, the getting fails without blocking
+            _ => true,
+        }
+    }
+
+    /// Returns true if `get_table` gets the table from the caches, without
+    /// loading the table entry or connecting to the server.
+    fn is_table_cached(
+        &self,
+        table_name: &str,
+        row_key: &[Value],
+        consistency_level: ObTableConsistencyLevel,
+    ) -> bool {
+        if self.is_odp_mode() {
+            return self.is_odp_table_cached();
+        }
+        match self.get_table_entry_from_cache(table_name) {
+            Some(table_entry) => match self.get_partition(&table_entry, row_key) {
+                Ok(part_id) => {
+                    self.is_partition_table_cached(&table_entry, part_id, consistency_level)
+                }
+                Err(_) => true,
+            },
+            None => false,
+        }
+    }
+
+    /// Returns true if `get_tables` gets the tables from the caches, see
+    /// `is_table_cached`.
+    fn are_tables_cached(
+        &self,
+        table_name: &str,
+        start: &[Value],
+        start_inclusive: bool,
+        end: &[Value],
+        end_inclusive: bool,
+        consistency_level: ObTableConsistencyLevel,
+    ) -> bool {
+        if self.is_odp_mode() {
+            return self.is_odp_table_cached();
+        }
+        let table_entry = match self.get_table_entry_from_cache(table_name) {
+            Some(table_entry) => table_entry,
+            None => return false,
+        };
+        match self.get_partition_replicas(
+            &table_entry,
+            start,
+            start_inclusive,
+            end,
+            end_inclusive,
+            consistency_level,
+        ) {
+            Ok(replicas) => {
+                let table_roster = self.table_roster.rl();
+                replicas
+                    .iter()
+                    .all(|(_, replica)| table_roster.contains_key(replica.addr()))
+            }
+            Err(_) => true,
+        }
+    }
+
+    /// Returns true if the batch operation is split with the tables got from
+    /// the caches, see `is_table_cached`.
+    fn is_batch_cached(&self, table_name: &str, batch_op: &ObTableBatchOperation) -> bool {
+        if self.is_odp_mode() {
+            return self.is_odp_table_cached();
+        }
+        let table_entry = match self.get_table_entry_from_cache(table_name) {
+            Some(table_entry) => table_entry,
+            None => return false,
+        };
+        let read_consistency_level = batch_op
+            .consistency_level()
+            .unwrap_or(self.config.read_consistency_level);
+        batch_op
+            .get_raw_ops()
+            .iter()
+            .all(|(operation_type, row_keys, ..)| {
+                match self.get_partition(&table_entry, row_keys) {
+                    // the reads of a read only partition may be sent to the followers
+                    Ok(part_id) => {
+                        self.is_partition_table_cached(
+                            &table_entry,
+                            part_id,
+                            ObTableConsistencyLevel::Strong,
+                        ) && (*operation_type != ObTableOperationType::Get
+                            || self.is_partition_table_cached(
+                                &table_entry,
+                                part_id,
+                                read_consistency_level,
+                            ))
+                    }
+                    Err(_) => true,
+                }
+            })
+    }
+
     fn fill_partition_location_with_part_id(
         &self,
         result: &mut Vec<(i64, ReplicaLocation)>,
         table_entry: &TableEntry,
         part_id: i64,
+        consistency_level: ObTableConsistencyLevel,
     ) -> Result<()> {
+        let ldc = self.server_roster.ldc();
         match table_entry.get_partition_location_with_part_id(part_id) {
-            Some(location) => match location.leader() {
+            Some(location) => match location.get_replica(consistency_level, &ldc) {
                 Some(leader) => {
                     result.push((part_id, leader.clone()));
                 }
@@ -545,13 +902,14 @@ impl ObTableClientInner {
         Ok(())
     }
 
-    fn get_partition_leaders(
+    fn get_partition_replicas(
         &self,
         table_entry: &TableEntry,
         start: &[Value],
         start_inclusive: bool,
         end: &[Value],
         end_inclusive: bool,
+        consistency_level: ObTableConsistencyLevel,
     ) -> Result<Vec<(i64, ReplicaLocation)>> {
         let mut result: Vec<(i64, ReplicaLocation)> = vec![];
 
@@ -559,7 +917,12 @@ impl ObTableClientInner {
             || table_entry.is_partition_level(ObPartitionLevel::Zero)
         {
             //Level zero or not partitioned.
-            self.fill_partition_location_with_part_id(&mut result, table_entry, 0)?;
+            self.fill_partition_location_with_part_id(
+                &mut result,
+                table_entry,
+                0,
+                consistency_level,
+            )?;
             Ok(result)
         } else if table_entry.is_partition_level(ObPartitionLevel::One) {
             //Level one
@@ -573,6 +936,7 @@ impl ObTableClientInner {
                                 &mut result,
                                 table_entry,
                                 part_id,
+                                consistency_level,
                             )?;
                         }
                         Ok(result)
@@ -623,7 +987,8 @@ impl ObTableClientInner {
                 .tenant_name(&self.tenant_name)
                 .user_name(&self.user_name)
                 .database_name(&self.database)
-                .password(&self.password);
+                .password(&self.password)
+                .event_loop_group(self.conn_event_loops.clone());
 
             let pool = Arc::new(
                 ConnPoolBuilder::new()
@@ -648,6 +1013,22 @@ impl ObTableClientInner {
         self.add_ob_table_to_roster(addr, &mut table_roster)
     }
 
+    /// Get the table to the ODP, which is the only table in ODP mode.
+    fn get_odp_table(&self) -> Result<Arc<ObTable>> {
+        match &self.odp_addr {
+            Some(addr) => {
+                if let Some(table) = self.table_roster.rl().get(addr) {
+                    return Ok(table.clone());
+                }
+                self.add_ob_table(addr)
+            }
+            None => Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                "ObTableClientInner::get_odp_table not in odp mode".to_owned(),
+            )),
+        }
+    }
+
     fn add_ob_table_to_roster(
         &self,
         addr: &ObServerAddr,
@@ -672,6 +1053,7 @@ impl ObTableClientInner {
                 .password(&self.password)
                 .database(&self.database)
                 .rpc_proxy(rpc_proxy)
+                .tablet_route(self.ob_version().is_4x())
                 .build(),
         );
         table_roster.insert(addr.clone(), ob_table.clone());
@@ -683,8 +1065,9 @@ impl ObTableClientInner {
         table_name: &str,
         table_entry: &Arc<TableEntry>,
         part_id: i64,
+        consistency_level: ObTableConsistencyLevel,
     ) -> Result<(i64, Arc<ObTable>)> {
-        match self.get_partition_leader(table_entry, part_id) {
+        match self.get_partition_replica(table_entry, part_id, consistency_level) {
             Some((part_id, replica)) => match replica {
                 Some(r) => {
                     let addr = r.addr();
@@ -711,16 +1094,20 @@ impl ObTableClientInner {
         }
     }
 
-    fn get_partition_leader(
+    fn get_partition_replica(
         &self,
         table_entry: &Arc<TableEntry>,
         part_id: i64,
+        consistency_level: ObTableConsistencyLevel,
     ) -> Option<(i64, Option<ReplicaLocation>)> {
         Some((
-            part_id,
+            // the requests are routed by the tablet id on OceanBase 4.x
+            table_entry.route_id(part_id),
             match table_entry.partition_entry() {
                 Some(entry) => match entry.get_partition_location_with_part_id(part_id) {
-                    Some(v) => v.leader().to_owned(),
+                    Some(v) => v
+                        .get_replica(consistency_level, &self.server_roster.ldc())
+                        .cloned(),
                     None => None,
                 },
                 None => None,
@@ -974,8 +1361,13 @@ impl ObTableClientInner {
             match self.refresh_table_entry(table_name, table_entry) {
                 Ok(table_entry) => {
                     drop(table_locations); //release read lock
-                    let mut table_locations = self.table_locations.wl();
-                    table_locations.insert(table_name.to_owned(), table_entry.clone());
+                    let old_table_entry = self
+                        .table_locations
+                        .wl()
+                        .insert(table_name.to_owned(), table_entry.clone());
+                    if let Some(old_table_entry) = old_table_entry {
+                        self.on_table_entry_refreshed(table_name, &old_table_entry, &table_entry);
+                    }
                     trace!(
                         "ObTableClientInner::get_or_refresh_table_entry succeed in refreshing table \
                         entry for table:{}, retry_num:{}",
@@ -1011,6 +1403,33 @@ impl ObTableClientInner {
         self.refresh_table_entry(table_name, self.table_locations.rl().get(table_name))
     }
 
+    /// Get the row key columns of the table in order.
+    fn get_row_key_columns(&self, table_name: &str) -> Result<Vec<String>> {
+        let row_key_element = match self.running_mode {
+            RunningMode::Normal => match self.table_row_key_element.rl().get(table_name) {
+                Some(v) => v.clone(),
+                None => match self.get_table_entry_from_cache(table_name) {
+                    Some(table_entry) if !table_entry.row_key_element().is_empty() => {
+                        table_entry.row_key_element().clone()
+                    }
+                    _ => {
+                        return Err(CommonErr(
+                            CommonErrCode::NotFound,
+                            format!("Row key element not found, table_name:{}", table_name),
+                        ))
+                    }
+                },
+            },
+            RunningMode::HBase => {
+                return Ok(vec!["K".to_owned(), "Q".to_owned(), "T".to_owned()]);
+            }
+        };
+
+        let mut columns: Vec<(String, i32)> = row_key_element.into_iter().collect();
+        columns.sort_by_key(|(_, idx)| *idx);
+        Ok(columns.into_iter().map(|(column, _)| column).collect())
+    }
+
     fn add_row_key_element(&self, table_name: &str, columns: Vec<String>) {
         {
             let table_row_key_element = self.table_row_key_element.rl();
@@ -1029,6 +1448,186 @@ impl ObTableClientInner {
         }
     }
 
+    fn describe_table(&self, table_name: &str) -> Result<TableSchema> {
+        if self.is_odp_mode() {
+            return Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                "ObTableClientInner::describe_table not supported in odp mode".to_owned(),
+            ));
+        }
+        let table_entry = self.get_or_refresh_table_entry(table_name, false)?;
+        let table_entry_key = TableEntryKey::new(
+            &self.cluster_name,
+            &self.tenant_name,
+            &self.database,
+            table_name,
+        );
+        self.load_with_priority(|addr| {
+            self.metadata
+                .load_table_schema(addr, &table_entry_key, &table_entry)
+        })
+    }
+
+    fn get_or_load_table_schema(&self, table_name: &str) -> Result<Arc<TableSchema>> {
+        if let Some(schema) = self.table_schemas.rl().get(table_name) {
+            return Ok(schema.clone());
+        }
+        let schema = Arc::new(self.describe_table(table_name)?);
+        self.table_schemas
+            .wl()
+            .insert(table_name.to_owned(), schema.clone());
+        Ok(schema)
+    }
+
+    /// Asynchronous version of `get_or_load_table_schema`, the schema not
+    /// cached is loaded on the refresh workers instead of the caller's thread.
+    fn get_or_load_table_schema_async(
+        self: &Arc<Self>,
+        table_name: &str,
+    ) -> BoxFuture<Arc<TableSchema>> {
+        if let Some(schema) = self.table_schemas.rl().get(table_name) {
+            return Box::new(future::ok(schema.clone()));
+        }
+        let inner = self.clone();
+        let table_name = table_name.to_owned();
+        Box::new(
+            self.refresh_workers
+                .spawn_fn(move || inner.get_or_load_table_schema(&table_name)),
+        )
+    }
+
+    /// Drop the schema cached when the server reports it's out of date, and
+    /// it's loaded again by the next check.
+    fn on_schema_error(&self, table_name: &str, error: &error::Error) {
+        if error.is_schema_error() && self.table_schemas.wl().remove(table_name).is_some() {
+            debug!(
+                "ObTableClientInner::on_schema_error drop the schema of table:{}, err:{}",
+                table_name, error
+            );
+        }
+    }
+
+    /// Returns true when the values of the operation are checked against the
+    /// schema of the table.
+    fn need_check_values(&self, operation_type: ObTableOperationType) -> bool {
+        self.config.validate_values
+            && !self.is_odp_mode()
+            && ObTableClientInner::is_checked_operation(operation_type)
+    }
+
+    /// The mutations writing the values of the columns.
+    fn is_checked_operation(operation_type: ObTableOperationType) -> bool {
+        matches!(
+            operation_type,
+            ObTableOperationType::Insert
+                | ObTableOperationType::Update
+                | ObTableOperationType::InsertOrUpdate
+                | ObTableOperationType::Replace
+        )
+    }
+
+    /// Check and coerce the values of the mutation against the schema of the
+    /// table if the validation is enabled.
+    fn check_values(
+        &self,
+        table_name: &str,
+        operation_type: ObTableOperationType,
+        row_keys: &mut [Value],
+        columns: Option<&[String]>,
+        properties: Option<&mut [Value]>,
+    ) -> Result<()> {
+        if !self.need_check_values(operation_type) {
+            return Ok(());
+        }
+        let schema = self.get_or_load_table_schema(table_name)?;
+        schema.check_values(row_keys, columns, properties)
+    }
+
+    /// Asynchronous version of `check_values`, the future resolves to the
+    /// values coerced.
+    fn check_values_async(
+        self: &Arc<Self>,
+        table_name: &str,
+        operation_type: ObTableOperationType,
+        mut row_keys: Vec<Value>,
+        columns: Option<Vec<String>>,
+        mut properties: Option<Vec<Value>>,
+    ) -> BoxFuture<(Vec<Value>, Option<Vec<Value>>)> {
+        if !self.need_check_values(operation_type) {
+            return Box::new(future::ok((row_keys, properties)));
+        }
+        Box::new(self.get_or_load_table_schema_async(table_name).and_then(
+            move |schema| -> Result<(Vec<Value>, Option<Vec<Value>>)> {
+                schema.check_values(
+                    &mut row_keys,
+                    columns.as_deref(),
+                    properties.as_deref_mut(),
+                )?;
+                Ok((row_keys, properties))
+            },
+        ))
+    }
+
+    fn check_batch_values(
+        &self,
+        table_name: &str,
+        batch_op: &mut ObTableBatchOperation,
+    ) -> Result<()> {
+        if !self.config.validate_values || self.is_odp_mode() {
+            return Ok(());
+        }
+        let mut raw_ops = batch_op.take_raw_ops();
+        for (operation_type, row_keys, columns, properties) in &mut raw_ops {
+            self.check_values(
+                table_name,
+                *operation_type,
+                row_keys,
+                columns.as_deref(),
+                properties.as_deref_mut(),
+            )?;
+        }
+        for raw_op in raw_ops {
+            batch_op.add_op(raw_op);
+        }
+        Ok(())
+    }
+
+    /// Asynchronous version of `check_batch_values`, the future resolves to
+    /// the batch with the values coerced.
+    fn check_batch_values_async(
+        self: &Arc<Self>,
+        table_name: &str,
+        mut batch_op: ObTableBatchOperation,
+    ) -> BoxFuture<ObTableBatchOperation> {
+        let mut raw_ops = batch_op.take_raw_ops();
+        let need_check = raw_ops
+            .iter()
+            .any(|(operation_type, ..)| self.need_check_values(*operation_type));
+        if !need_check {
+            for raw_op in raw_ops {
+                batch_op.add_op(raw_op);
+            }
+            return Box::new(future::ok(batch_op));
+        }
+        Box::new(self.get_or_load_table_schema_async(table_name).and_then(
+            move |schema| -> Result<ObTableBatchOperation> {
+                for (operation_type, row_keys, columns, properties) in &mut raw_ops {
+                    if ObTableClientInner::is_checked_operation(*operation_type) {
+                        schema.check_values(
+                            row_keys,
+                            columns.as_deref(),
+                            properties.as_deref_mut(),
+                        )?;
+                    }
+                }
+                for raw_op in raw_ops {
+                    batch_op.add_op(raw_op);
+                }
+                Ok(batch_op)
+            },
+        ))
+    }
+
     fn get_or_create_batch_op_thread_pool(&self, table_name: &str) -> Arc<CpuPool> {
         let pools = self.table_batch_op_thread_pools.rl();
         if let Some(pool) = pools.get(table_name) {
@@ -1065,12 +1664,26 @@ impl ObTableClientInner {
             self.table_locations.wl().remove(table_name);
         }
         self.table_row_key_element.wl().remove(table_name);
+        self.table_schemas.wl().remove(table_name);
         self.table_continuous_failures.wl().remove(table_name);
         self.table_mutexs.wl().remove(table_name);
         self.table_batch_op_thread_pools.wl().remove(table_name);
     }
 
     fn execute_sql(&self, sql: &str) -> Result<()> {
+        if let Some(odp_addr) = &self.odp_addr {
+            // the cluster is specified in the user name to login the ODP
+            return self.location.execute_sql(
+                sql,
+                odp_addr,
+                &format!("{}#{}", self.tenant_name, self.cluster_name),
+                &self.user_name,
+                &self.password,
+                &self.database,
+                self.config.rpc_operation_timeout,
+            );
+        }
+
         if let Some(server_addr) = self.server_roster.peek_random_server() {
             self.location.execute_sql(
                 sql,
@@ -1114,6 +1727,10 @@ impl ObTableClientInner {
         self.running_mode.clone()
     }
 
+    fn is_odp_mode(&self) -> bool {
+        self.odp_addr.is_some()
+    }
+
     fn is_closed(&self) -> bool {
         self.closed.load(Ordering::Acquire)
     }
@@ -1122,7 +1739,9 @@ impl ObTableClientInner {
         self.initialized.load(Ordering::Acquire)
     }
 
-    fn refresh_all_table_entries(&self) {
+    /// Refresh the table entries of all the tables in use by the refresh
+    /// workers.
+    fn refresh_all_table_entries(self: &Arc<Self>) {
         let _timer = OBKV_CLIENT_HISTOGRAM_VEC
             .with_label_values(&["refresh_all_tables"])
             .start_timer();
@@ -1134,12 +1753,65 @@ impl ObTableClientInner {
             .map(|e| e.to_owned())
             .collect();
 
-        for table_name in tables {
-            if let Err(e) = self.get_or_refresh_table_entry(&table_name, true) {
-                error!("ObTableClientInner::refresh_all_table_entries fail to refresh table entry for table: {}, err: {}.",
-                                 table_name, e);
+        let refreshes = tables.into_iter().map(|table_name| {
+            let inner = self.clone();
+            self.refresh_workers.spawn_fn(move || {
+                if let Err(e) = inner.get_or_refresh_table_entry(&table_name, true) {
+                    error!("ObTableClientInner::refresh_all_table_entries fail to refresh table entry for table: {}, err: {}.",
+                                     table_name, e);
+                }
+                Ok::<(), error::Error>(())
+            })
+        });
+        // the failures are logged by the refreshes
+        let _ = future::join_all(refreshes).wait();
+    }
+
+    /// Publish the changes of the locations of the table to the subscribers,
+    /// and prepare the tables to the new servers.
+    fn on_table_entry_refreshed(
+        &self,
+        table_name: &str,
+        old_table_entry: &TableEntry,
+        new_table_entry: &TableEntry,
+    ) {
+        let events = LocationEvent::diff(table_name, old_table_entry, new_table_entry);
+        if events.is_empty() {
+            return;
+        }
+
+        for event in &events {
+            let addr = match event {
+                LocationEvent::LeaderChanged {
+                    leader: Some(addr), ..
+                } => addr,
+                LocationEvent::ServerAdded { addr, .. } => addr,
+                _ => continue,
+            };
+            if self.table_roster.rl().contains_key(addr) {
+                continue;
+            }
+            if let Err(e) = self.add_ob_table(addr) {
+                warn!(
+                    "ObTableClientInner::on_table_entry_refreshed fail to add table, addr:{:?}, err:{}",
+                    addr, e
+                );
             }
         }
+
+        if self.location_subscribers.rl().is_empty() {
+            return;
+        }
+        // the subscribers gone are removed
+        self.location_subscribers
+            .wl()
+            .retain(|subscriber| events.iter().all(|e| subscriber.send(e.clone()).is_ok()));
+    }
+
+    fn subscribe_location_events(&self) -> Receiver<LocationEvent> {
+        let (sender, receiver) = unbounded();
+        self.location_subscribers.wl().push(sender);
+        receiver
     }
 
     fn init(&self) -> Result<()> {
@@ -1154,19 +1826,67 @@ impl ObTableClientInner {
             return Ok(());
         }
         self.initialized.store(true, Ordering::Release);
+        if self.is_odp_mode() {
+            self.detect_odp_ob_version()?;
+            return self.get_odp_table().map(|_| ());
+        }
         self.init_metadata()
     }
 
-    fn close(&mut self) -> Result<()> {
-        if self.is_closed() {
-            warn!("ObTableClientInner::close already closed.");
-            return Ok(());
-        }
-
-        let _lock = self.status_mutex.lock();
-        if self.is_closed() {
-            warn!("ObTableClientInner::close already closed.");
-            return Ok(());
+    /// Detect the version of the cluster behind the ODP by the user, the
+    /// payloads sent to the ODP are encoded by the version.
+    fn detect_odp_ob_version(&self) -> Result<ObVersion> {
+        let odp_addr = match &self.odp_addr {
+            Some(addr) => addr,
+            None => {
+                return Err(CommonErr(
+                    CommonErrCode::InvalidParam,
+                    "ObTableClientInner::detect_odp_ob_version not in odp mode".to_owned(),
+                ))
+            }
+        };
+        // the cluster is specified in the user name to login the ODP
+        self.location
+            .detect_ob_version_with_user(
+                odp_addr,
+                &format!(
+                    "{}@{}#{}",
+                    self.user_name, self.tenant_name, self.cluster_name
+                ),
+                &self.password,
+                &self.database,
+                self.config.table_entry_acquire_connect_timeout,
+                self.config.table_entry_acquire_read_timeout,
+            )
+            .map_err(|e| {
+                error!(
+                    "ObTableClientInner::detect_odp_ob_version fail to detect ob version, odp:{:?}, err:{}",
+                    odp_addr, e
+                );
+                e
+            })
+    }
+
+    /// The version of the cluster, which is detected through the ODP in ODP
+    /// mode.
+    fn ob_version(&self) -> ObVersion {
+        if self.is_odp_mode() {
+            self.location.ob_version()
+        } else {
+            self.metadata.ob_version()
+        }
+    }
+
+    fn close(&mut self) -> Result<()> {
+        if self.is_closed() {
+            warn!("ObTableClientInner::close already closed.");
+            return Ok(());
+        }
+
+        let _lock = self.status_mutex.lock();
+        if self.is_closed() {
+            warn!("ObTableClientInner::close already closed.");
+            return Ok(());
         }
         self.closed.store(true, Ordering::Release);
 
@@ -1183,6 +1903,10 @@ impl ObTableClientInner {
     }
 
     fn sync_refresh_metadata(&self) -> Result<()> {
+        if self.is_odp_mode() {
+            return Ok(());
+        }
+
         let _timer = OBKV_CLIENT_HISTOGRAM_VEC
             .with_label_values(&["refresh_metadata"])
             .start_timer();
@@ -1201,30 +1925,17 @@ impl ObTableClientInner {
             return Ok(());
         }
 
-        let new_ocp_model = self.ocp_manager.load_ocp_model(
-            &self.param_url,
-            &self.datasource_name,
-            self.config.rslist_acquire_try_times,
-            self.config.rslist_acquire_retry_interval,
-            false,
-        )?;
+        let new_ocp_model = self.metadata.load_ocp_model(false)?;
 
-        self.location
-            .invalidate_mysql_pools(&new_ocp_model.observer_addrs);
+        self.metadata
+            .invalidate_servers(&new_ocp_model.observer_addrs);
 
         *self.ocp_model.wl() = new_ocp_model;
 
         let root_server_key =
             TableEntryKey::new_root_server_key(&self.cluster_name, &self.tenant_name);
 
-        let table_entry = {
-            self.location.load_table_entry_randomly(
-                &self.ocp_model.rl().observer_addrs,
-                &root_server_key,
-                self.config.table_entry_acquire_connect_timeout,
-                self.config.table_entry_acquire_read_timeout,
-            )?
-        };
+        let table_entry = self.load_table_entry_randomly(&root_server_key)?;
 
         let mut servers: Vec<ObServerAddr> = vec![];
 
@@ -1273,25 +1984,24 @@ impl ObTableClientInner {
 
     fn init_metadata(&self) -> Result<()> {
         let _lock = self.refresh_metadata_mutex.lock();
-        *self.ocp_model.wl() = self.ocp_manager.load_ocp_model(
-            &self.param_url,
-            &self.datasource_name,
-            self.config.rslist_acquire_try_times,
-            self.config.rslist_acquire_retry_interval,
-            true,
-        )?;
+        *self.ocp_model.wl() = self.metadata.load_ocp_model(true)?;
+
+        // the system views and the routing differ between the versions
+        let rs_list = self.ocp_model.rl().observer_addrs.clone();
+        // the requests are routed wrongly with the wrong version, so fail to
+        // init rather than assume one
+        if let Err(e) = self.metadata.detect_ob_version(&rs_list) {
+            error!(
+                "ObTableClientInner::init_metadata fail to detect ob version, err:{}",
+                e
+            );
+            return Err(e);
+        }
 
         let root_server_key =
             TableEntryKey::new_root_server_key(&self.cluster_name, &self.tenant_name);
 
-        let table_entry = {
-            self.location.load_table_entry_randomly(
-                &self.ocp_model.rl().observer_addrs,
-                &root_server_key,
-                self.config.table_entry_acquire_connect_timeout,
-                self.config.table_entry_acquire_read_timeout,
-            )?
-        };
+        let table_entry = self.load_table_entry_randomly(&root_server_key)?;
 
         let mut servers: Vec<ObServerAddr> = vec![];
 
@@ -1318,6 +2028,19 @@ impl ObTableClientInner {
         Ok(())
     }
 
+    /// The consistency level of the operation, only the reads are allowed to
+    /// be weak and they follow the client config by default.
+    fn consistency_level_of(
+        &self,
+        operation_type: ObTableOperationType,
+        requested: Option<ObTableConsistencyLevel>,
+    ) -> ObTableConsistencyLevel {
+        if operation_type != ObTableOperationType::Get {
+            return ObTableConsistencyLevel::Strong;
+        }
+        requested.unwrap_or(self.config.read_consistency_level)
+    }
+
     fn check_status(&self) -> Result<()> {
         if !self.is_initialized() {
             return Err(CommonErr(
@@ -1346,10 +2069,13 @@ impl ObTableClientInner {
         row_keys: Vec<Value>,
         columns: Option<Vec<String>>,
         properties: Option<Vec<Value>>,
+        options: OperationOptions,
     ) -> Result<ObTableOperationResult> {
         self.check_status()?;
 
-        let (part_id, table) = self.get_table(table_name, &row_keys, false)?;
+        let consistency_level =
+            self.consistency_level_of(operation_type, options.get_consistency_level());
+        let (part_id, table) = self.get_table(table_name, &row_keys, false, consistency_level)?;
 
         let _timer = OBKV_CLIENT_HISTOGRAM_VEC
             .with_label_values(&[operation_type.as_str()])
@@ -1365,6 +2091,8 @@ impl ObTableClientInner {
             self.config.log_level_flag,
         );
         payload.set_partition_id(part_id);
+        payload.set_consistency_level(consistency_level);
+        options.apply(&mut payload);
         let mut result = ObTableOperationResult::new();
         table.execute_payload(&mut payload, &mut result)?;
         Ok(result)
@@ -1378,6 +2106,32 @@ impl ObTableClientInner {
         columns: Option<Vec<String>>,
         properties: Option<Vec<Value>>,
     ) -> Result<ObTableOperationResult> {
+        self.execute_with_options(
+            table_name,
+            operation_type,
+            row_keys,
+            columns,
+            properties,
+            OperationOptions::default(),
+        )
+    }
+
+    fn execute_with_options(
+        &self,
+        table_name: &str,
+        operation_type: ObTableOperationType,
+        mut row_keys: Vec<Value>,
+        columns: Option<Vec<String>>,
+        mut properties: Option<Vec<Value>>,
+        options: OperationOptions,
+    ) -> Result<ObTableOperationResult> {
+        self.check_values(
+            table_name,
+            operation_type,
+            &mut row_keys,
+            columns.as_deref(),
+            properties.as_deref_mut(),
+        )?;
         let mut retry_num = 0;
         loop {
             retry_num += 1;
@@ -1387,24 +2141,9 @@ impl ObTableClientInner {
                 row_keys.clone(),
                 columns.clone(),
                 properties.clone(),
+                options,
             ) {
-                Ok(result) => {
-                    let error_no = result.header().errorno();
-                    let result_code = ResultCodes::from_i32(error_no);
-                    let result = if result_code == ResultCodes::OB_SUCCESS {
-                        self.reset_table_failure(table_name);
-                        Ok(result)
-                    } else {
-                        Err(CommonErr(
-                            CommonErrCode::ObException(result_code),
-                            format!(
-                                "OBKV server return exception, the msg is: {}.",
-                                result.header().message()
-                            ),
-                        ))
-                    };
-                    return result;
-                }
+                Ok(result) => return self.check_operation_result(table_name, result),
                 Err(e) => {
                     debug!(
                         "ObTableClientInner::execute fail to execute once, table_name:{}, \
@@ -1439,6 +2178,302 @@ impl ObTableClientInner {
             }
         }
     }
+
+    fn check_operation_result(
+        &self,
+        table_name: &str,
+        result: ObTableOperationResult,
+    ) -> Result<ObTableOperationResult> {
+        let error_no = result.header().errorno();
+        let result_code = ResultCodes::from_i32(error_no);
+        if result_code == ResultCodes::OB_SUCCESS {
+            self.reset_table_failure(table_name);
+            Ok(result)
+        } else {
+            let e = CommonErr(
+                CommonErrCode::ObException(result_code),
+                format!(
+                    "OBKV server return exception, the msg is: {}.",
+                    result.header().message()
+                ),
+            );
+            self.on_schema_error(table_name, &e);
+            Err(e)
+        }
+    }
+
+    /// Mutate the rows matched by the query on every partition it covers.
+    ///
+    /// NOTE: the request is not retried because it may be partially applied
+    /// when some of the partitions fail.
+    fn query_and_mutate(
+        &self,
+        table_name: &str,
+        query_and_mutate: ObTableQueryAndMutate,
+    ) -> Result<ObTableQueryAndMutateResult> {
+        self.check_status()?;
+        query_and_mutate.verify()?;
+
+        let _timer = OBKV_CLIENT_HISTOGRAM_VEC
+            .with_label_values(&["query_and_mutate"])
+            .start_timer();
+
+        let mut partition_table: HashMap<i64, Arc<ObTable>> = HashMap::new();
+        for range in query_and_mutate.table_query().get_key_ranges() {
+            let border_flag = range.get_border_flag();
+            let pairs = self.get_tables(
+                table_name,
+                range.get_start_key().keys(),
+                border_flag.is_inclusive_start(),
+                range.get_end_key().keys(),
+                border_flag.is_inclusive_end(),
+                false,
+                ObTableConsistencyLevel::Strong,
+            )?;
+            partition_table.extend(pairs);
+        }
+
+        // the check is done in a single partition by the server
+        if query_and_mutate.is_check_and_execute() && partition_table.len() > 1 {
+            return Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                format!(
+                    "Check and execute must be in a single partition, table_name:{}, \
+                     partition_num:{}",
+                    table_name,
+                    partition_table.len()
+                ),
+            ));
+        }
+
+        let _permit = self.acquire_query_permit()?;
+        let mut result = ObTableQueryAndMutateResult::new();
+        for (part_id, ob_table) in partition_table {
+            let mut payload = ObTableQueryAndMutateRequest::new(
+                table_name,
+                part_id,
+                ObTableEntityType::Dynamic,
+                query_and_mutate.clone(),
+                self.config.rpc_operation_timeout,
+                self.config.log_level_flag,
+            );
+            let mut part_result = ObTableQueryAndMutateResult::new();
+            match ob_table.execute_payload(&mut payload, &mut part_result) {
+                Ok(()) => self.reset_table_failure(table_name),
+                Err(e) => {
+                    if let Err(e) = self.on_table_op_failure(table_name, &e) {
+                        error!(
+                            "ObTableClientInner::query_and_mutate on_table_op_failure, \
+                             table_name:{}, part_id:{}, err:{}",
+                            table_name, part_id, e
+                        );
+                    }
+                    return Err(e);
+                }
+            }
+            result.merge(part_result);
+        }
+
+        Ok(result)
+    }
+
+    /// Asynchronous version of `on_table_op_failure`, which is run on the
+    /// refresh workers since the table entry may be refreshed. The future
+    /// resolves to the error of the operation and the result of handling it.
+    fn on_table_op_failure_async(
+        self: &Arc<Self>,
+        table_name: &str,
+        e: error::Error,
+    ) -> BoxFuture<(error::Error, Result<()>)> {
+        let inner = self.clone();
+        let table_name = table_name.to_owned();
+        Box::new(
+            self.refresh_workers
+                .spawn_fn(move || -> Result<(error::Error, Result<()>)> {
+                    let res = inner.on_table_op_failure(&table_name, &e);
+                    Ok((e, res))
+                }),
+        )
+    }
+
+    // Decide what to do after an asynchronous operation failed, returns the
+    // error if no retry is allowed.
+    fn on_async_op_failure<T: Send + 'static>(
+        self: &Arc<Self>,
+        table_name: &str,
+        tag: &'static str,
+        retry_num: usize,
+        e: error::Error,
+    ) -> BoxFuture<Loop<T, usize>> {
+        debug!(
+            "ObTableClientInner::on_async_op_failure fail to execute once, table_name:{}, \
+             op:{}, retry_num:{}, err:{}",
+            table_name, tag, retry_num, e
+        );
+        let inner = self.clone();
+        let table_name = table_name.to_owned();
+        Box::new(self.on_table_op_failure_async(&table_name, e).and_then(
+            move |(e, res)| -> BoxFuture<Loop<T, usize>> {
+                if let Err(fail_err) = res {
+                    error!(
+                        "ObTableClientInner::on_async_op_failure on_table_op_failure, \
+                         table_name:{}, op:{}, retry_num:{}, err:{}",
+                        table_name, tag, retry_num, fail_err
+                    );
+                    return Box::new(future::err(e));
+                }
+                if retry_num < inner.config.rpc_retry_limit && e.need_retry() {
+                    OBKV_CLIENT_RETRY_COUNTER_VEC
+                        .with_label_values(&[tag])
+                        .inc();
+
+                    if inner.config.rpc_retry_interval.as_secs() > 0 {
+                        return Box::new(
+                            util::delay(inner.config.rpc_retry_interval)
+                                .map(move |_| Loop::Continue(retry_num)),
+                        );
+                    }
+                    return Box::new(future::ok(Loop::Continue(retry_num)));
+                }
+                error!(
+                    "ObTableClientInner::on_async_op_failure retrying too many times, \
+                     table_name:{}, op:{}, retry_num:{}, err:{}",
+                    table_name, tag, retry_num, e
+                );
+                Box::new(future::err(e))
+            },
+        ))
+    }
+
+    fn execute_once_async(
+        self: &Arc<Self>,
+        table_name: &str,
+        operation_type: ObTableOperationType,
+        row_keys: Vec<Value>,
+        columns: Option<Vec<String>>,
+        properties: Option<Vec<Value>>,
+    ) -> BoxFuture<ObTableOperationResult> {
+        box_future_try!(self.check_status());
+
+        let consistency_level = self.consistency_level_of(operation_type, None);
+        let table = self.get_table_async(table_name, &row_keys, consistency_level);
+
+        let inner = self.clone();
+        let table_name = table_name.to_owned();
+        Box::new(table.and_then(move |(part_id, table)| {
+            let timer = OBKV_CLIENT_HISTOGRAM_VEC
+                .with_label_values(&[operation_type.as_str()])
+                .start_timer();
+
+            let mut payload = ObTableOperationRequest::new(
+                &table_name,
+                operation_type,
+                row_keys,
+                columns,
+                properties,
+                inner.config.rpc_operation_timeout,
+                inner.config.log_level_flag,
+            );
+            payload.set_partition_id(part_id);
+            payload.set_consistency_level(consistency_level);
+            table
+                .execute_payload_async::<_, ObTableOperationResult>(payload)
+                .then(move |res| {
+                    timer.observe_duration();
+                    res
+                })
+        }))
+    }
+
+    /// Asynchronous version of `execute`, the retries are scheduled on the
+    /// shared timer instead of sleeping.
+    fn execute_async(
+        self: Arc<Self>,
+        table_name: &str,
+        operation_type: ObTableOperationType,
+        row_keys: Vec<Value>,
+        columns: Option<Vec<String>>,
+        properties: Option<Vec<Value>>,
+    ) -> BoxFuture<ObTableOperationResult> {
+        let checked = self.check_values_async(
+            table_name,
+            operation_type,
+            row_keys,
+            columns.clone(),
+            properties,
+        );
+        let table_name = table_name.to_owned();
+        Box::new(checked.and_then(move |(row_keys, properties)| {
+            future::loop_fn(0, move |retry_num| {
+                let retry_num = retry_num + 1;
+                let inner = self.clone();
+                let table_name = table_name.clone();
+                self.execute_once_async(
+                    &table_name,
+                    operation_type,
+                    row_keys.clone(),
+                    columns.clone(),
+                    properties.clone(),
+                )
+                .then(
+                    move |res| -> BoxFuture<Loop<ObTableOperationResult, usize>> {
+                        match res {
+                            Ok(result) => Box::new(future::result(
+                                inner
+                                    .check_operation_result(&table_name, result)
+                                    .map(Loop::Break),
+                            )),
+                            Err(e) => inner.on_async_op_failure(
+                                &table_name,
+                                "execute_async",
+                                retry_num,
+                                e,
+                            ),
+                        }
+                    },
+                )
+            })
+        }))
+    }
+
+    /// Execute the query or stream request of a partition asynchronously, and
+    /// the permit is held until the reply arrives.
+    fn execute_query_async<T: ObPayload + Send + 'static>(
+        self: Arc<Self>,
+        table_name: &str,
+        (part_id, ob_table): (i64, Arc<ObTable>),
+        payload: T,
+        permit: Option<PermitGuard>,
+    ) -> BoxFuture<PartitionQueryResult> {
+        let table_name = table_name.to_owned();
+        Box::new(
+            ob_table
+                .execute_payload_async::<_, ObTableQueryResult>(payload)
+                .then(move |res| -> BoxFuture<PartitionQueryResult> {
+                    drop(permit);
+                    match res {
+                        Ok(result) => {
+                            self.reset_table_failure(&table_name);
+                            OBKV_CLIENT_HISTOGRAM_NUM_VEC
+                                .with_label_values(&["query_rows"])
+                                .observe(result.row_count() as f64);
+                            Box::new(future::ok(((part_id, ob_table), result)))
+                        }
+                        Err(e) => Box::new(self.on_table_op_failure_async(&table_name, e).and_then(
+                            |(e, res)| -> Result<PartitionQueryResult> {
+                                if let Err(fail_err) = res {
+                                    error!(
+                                        "ObTableClientInner::execute_query_async on_table_op_failure err: {}.",
+                                        fail_err
+                                    );
+                                }
+                                Err(e)
+                            },
+                        )),
+                    }
+                }),
+        )
+    }
 }
 
 impl Drop for ObTableClientInner {
@@ -1450,6 +2485,92 @@ impl Drop for ObTableClientInner {
     }
 }
 
+/// The change of the locations of a table.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LocationEvent {
+    /// The leader of the partition is changed.
+    LeaderChanged {
+        table_name: String,
+        part_id: i64,
+        leader: Option<ObServerAddr>,
+    },
+    /// The partition is newly located.
+    PartitionAdded { table_name: String, part_id: i64 },
+    /// The partition is not located any more.
+    PartitionRemoved { table_name: String, part_id: i64 },
+    /// The server newly hosts the replicas of the table.
+    ServerAdded {
+        table_name: String,
+        addr: ObServerAddr,
+    },
+    /// The server hosts no replica of the table any more.
+    ServerRemoved {
+        table_name: String,
+        addr: ObServerAddr,
+    },
+}
+
+impl LocationEvent {
+    /// Diff the locations of the table entries refreshed.
+    fn diff(table_name: &str, old: &TableEntry, new: &TableEntry) -> Vec<LocationEvent> {
+        let empty = HashMap::new();
+        let old_locations = match old.partition_entry() {
+            Some(entry) => entry.partition_locations(),
+            None => &empty,
+        };
+        let new_locations = match new.partition_entry() {
+            Some(entry) => entry.partition_locations(),
+            None => &empty,
+        };
+
+        let mut events = vec![];
+        let mut old_servers = HashSet::new();
+        let mut new_servers = HashSet::new();
+        for (part_id, location) in old_locations {
+            old_servers.extend(location.replicas().map(|r| r.addr().clone()));
+            if !new_locations.contains_key(part_id) {
+                events.push(LocationEvent::PartitionRemoved {
+                    table_name: table_name.to_owned(),
+                    part_id: *part_id,
+                });
+            }
+        }
+        for (part_id, location) in new_locations {
+            new_servers.extend(location.replicas().map(|r| r.addr().clone()));
+            let new_leader = location.leader().as_ref().map(|r| r.addr());
+            match old_locations.get(part_id) {
+                Some(old_location) => {
+                    if old_location.leader().as_ref().map(|r| r.addr()) != new_leader {
+                        events.push(LocationEvent::LeaderChanged {
+                            table_name: table_name.to_owned(),
+                            part_id: *part_id,
+                            leader: new_leader.cloned(),
+                        });
+                    }
+                }
+                None => events.push(LocationEvent::PartitionAdded {
+                    table_name: table_name.to_owned(),
+                    part_id: *part_id,
+                }),
+            }
+        }
+
+        for addr in new_servers.difference(&old_servers) {
+            events.push(LocationEvent::ServerAdded {
+                table_name: table_name.to_owned(),
+                addr: addr.clone(),
+            });
+        }
+        for addr in old_servers.difference(&new_servers) {
+            events.push(LocationEvent::ServerRemoved {
+                table_name: table_name.to_owned(),
+                addr: addr.clone(),
+            });
+        }
+        events
+    }
+}
+
 /// OBKV Table client
 #[derive(Clone)]
 pub struct ObTableClient {
@@ -1458,16 +2579,39 @@ pub struct ObTableClient {
 }
 
 impl ObTableClient {
-    /// Add row key element for table.
+    /// Add row key element for table, which overrides the one discovered from
+    /// the primary key of the table.
     pub fn add_row_key_element(&self, table_name: &str, columns: Vec<String>) {
         self.inner.add_row_key_element(table_name, columns);
     }
 
+    /// Describe the columns, the primary key and the partitioning of the
+    /// table.
+    pub fn describe_table(&self, table_name: &str) -> Result<TableSchema> {
+        self.inner.describe_table(table_name)
+    }
+
     /// Returns client's current running mode.
     pub fn running_mode(&self) -> RunningMode {
         self.inner.running_mode()
     }
 
+    /// Returns true when all the requests are sent to the ODP.
+    pub fn is_odp_mode(&self) -> bool {
+        self.inner.is_odp_mode()
+    }
+
+    /// Returns the version of the cluster detected on init.
+    pub fn ob_version(&self) -> ObVersion {
+        self.inner.ob_version()
+    }
+
+    /// Subscribe the changes of the locations of the tables in use, which are
+    /// found by the refreshing in the background.
+    pub fn subscribe_location_events(&self) -> Receiver<LocationEvent> {
+        self.inner.subscribe_location_events()
+    }
+
     /// Create a TableQuery instance for table.
     pub fn query(&self, table_name: &str) -> impl TableQuery {
         ObTableClientQueryImpl::new(table_name, self.inner.clone())
@@ -1502,6 +2646,9 @@ impl ObTableClient {
     /// Intialize the ob table client instance.
     pub fn init(&self) -> Result<()> {
         self.inner.init()?;
+        if self.inner.is_odp_mode() {
+            return Ok(());
+        }
         let inner = self.inner.clone();
         self.refresh_thread_pool.execute_with_fixed_delay(
             inner.config.table_entry_refresh_interval_base,
@@ -1520,20 +2667,32 @@ impl ObTableClient {
         row_key: &[Value],
         refresh: bool,
     ) -> Result<(i64, Arc<ObTable>)> {
-        self.inner.get_table(table_name, row_key, refresh)
+        self.inner.get_table(
+            table_name,
+            row_key,
+            refresh,
+            ObTableConsistencyLevel::Strong,
+        )
     }
 
-    fn execute_batch_once(
+    /// Mutate the rows matched by the query and filter on the server side,
+    /// which is also used to check and mutate atomically, see
+    /// [`ObTableQueryAndMutate`].
+    pub fn query_and_mutate(
         &self,
         table_name: &str,
-        batch_op: ObTableBatchOperation,
-    ) -> Result<Vec<TableOpResult>> {
-        self.inner.check_status()?;
-
-        let _timer = OBKV_CLIENT_HISTOGRAM_VEC
-            .with_label_values(&["execute_batch"])
-            .start_timer();
+        query_and_mutate: ObTableQueryAndMutate,
+    ) -> Result<ObTableQueryAndMutateResult> {
+        self.inner.query_and_mutate(table_name, query_and_mutate)
+    }
 
+    // Split the batch operation by partition, each of the returned operations is
+    // ready to be executed on the paired table.
+    fn split_batch_op(
+        &self,
+        table_name: &str,
+        batch_op: ObTableBatchOperation,
+    ) -> Result<Vec<(Arc<ObTable>, ObTableBatchOperation)>> {
         assert!(batch_op.is_raw());
         let mut batch_op = batch_op;
 
@@ -1541,6 +2700,27 @@ impl ObTableClient {
             .with_label_values(&["batch_ops"])
             .observe(batch_op.get_raw_ops().len() as f64);
 
+        let read_consistency_level = batch_op
+            .consistency_level()
+            .unwrap_or(self.inner.config.read_consistency_level);
+
+        // the batch is split by the ODP
+        if self.inner.is_odp_mode() {
+            let mut odp_batch_op = ObTableBatchOperation::new();
+            for op in batch_op.take_raw_ops() {
+                odp_batch_op.add_op(op);
+            }
+            if odp_batch_op.is_read_only() {
+                odp_batch_op.set_consistency_level(read_consistency_level);
+            } else {
+                odp_batch_op.set_consistency_level(ObTableConsistencyLevel::Strong);
+            }
+            odp_batch_op.set_partition_id(ODP_PARTITION_ID);
+            odp_batch_op.set_table_name(table_name.to_owned());
+            odp_batch_op.set_atomic_op(batch_op.is_atomic_op());
+            return Ok(vec![(self.inner.get_odp_table()?, odp_batch_op)]);
+        }
+
         let table_entry = self.inner.get_or_refresh_table_entry(table_name, false)?;
 
         let mut part_batch_ops = HashMap::with_capacity(1);
@@ -1551,28 +2731,13 @@ impl ObTableClient {
                 .or_insert_with(ObTableBatchOperation::new)
                 .add_op(op);
         }
-        if part_batch_ops.is_empty() {
-            return Ok(Vec::new());
-        }
 
         OBKV_CLIENT_HISTOGRAM_NUM_VEC
             .with_label_values(&["partitioned_batch_ops"])
             .observe(part_batch_ops.len() as f64);
 
-        // fast path: to process batch operations involving only one partition
-        if part_batch_ops.len() == 1 {
-            let (part_id, mut part_batch_op) = part_batch_ops.into_iter().next().unwrap();
-            part_batch_op.set_partition_id(part_id);
-            part_batch_op.set_table_name(table_name.to_owned());
-            part_batch_op.set_atomic_op(batch_op.is_atomic_op());
-            let (_, table) = self
-                .inner
-                .get_or_create_table(table_name, &table_entry, part_id)?;
-            return table.execute_batch(table_name, part_batch_op);
-        }
-
         // atomic now only support single partition
-        if (part_batch_ops.len() as u32) != 1 && batch_op.is_atomic_op() {
+        if part_batch_ops.len() > 1 && batch_op.is_atomic_op() {
             return Err(CommonErr(
                 CommonErrCode::ObException(ResultCodes::OB_INVALID_PARTITION),
                 format!(
@@ -1582,30 +2747,336 @@ impl ObTableClient {
             ));
         }
 
+        let mut table_batch_ops = Vec::with_capacity(part_batch_ops.len());
+        for (part_id, mut part_batch_op) in part_batch_ops {
+            // only the read only batch is allowed to be weak
+            let consistency_level = if part_batch_op.is_read_only() {
+                read_consistency_level
+            } else {
+                ObTableConsistencyLevel::Strong
+            };
+            let (route_id, table) = self.inner.get_or_create_table(
+                table_name,
+                &table_entry,
+                part_id,
+                consistency_level,
+            )?;
+            part_batch_op.set_consistency_level(consistency_level);
+            part_batch_op.set_partition_id(route_id);
+            part_batch_op.set_table_name(table_name.to_owned());
+            part_batch_op.set_atomic_op(batch_op.is_atomic_op());
+            table_batch_ops.push((table, part_batch_op));
+        }
+        Ok(table_batch_ops)
+    }
+
+    fn execute_batch_once(
+        &self,
+        table_name: &str,
+        batch_op: ObTableBatchOperation,
+    ) -> Result<Vec<TableOpResult>> {
+        self.inner.check_status()?;
+
+        let _timer = OBKV_CLIENT_HISTOGRAM_VEC
+            .with_label_values(&["execute_batch"])
+            .start_timer();
+
+        let mut table_batch_ops = self.split_batch_op(table_name, batch_op)?;
+        if table_batch_ops.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // fast path: to process batch operations involving only one partition
+        if table_batch_ops.len() == 1 {
+            let (table, part_batch_op) = table_batch_ops.pop().unwrap();
+            return table.execute_batch(table_name, part_batch_op);
+        }
+
         // slow path: have to process operations involving multiple partitions
         // concurrent send the batch ops by partition
         let pool = self.inner.get_or_create_batch_op_thread_pool(table_name);
 
         // prepare all the runners
-        let mut runners = Vec::with_capacity(part_batch_ops.len());
-        for (part_id, mut batch_op) in part_batch_ops {
-            let (_, table) = self
-                .inner
-                .get_or_create_table(table_name, &table_entry, part_id)?;
+        let mut runners = Vec::with_capacity(table_batch_ops.len());
+        for (table, batch_op) in table_batch_ops {
             let table_name = table_name.to_owned();
-            runners.push(move || {
-                batch_op.set_partition_id(part_id);
-                batch_op.set_table_name(table_name.clone());
-                table.execute_batch(&table_name, batch_op)
-            });
+            runners.push(move || table.execute_batch(&table_name, batch_op));
         }
 
         // join all runners into one future
         let put_all = future::join_all(runners.into_iter().map(|runner| pool.spawn_fn(runner)));
 
-        // wait for all futures done
-        let results = put_all.wait()?;
-        Ok(results.into_iter().flatten().collect())
+        // wait for all futures done
+        let results = put_all.wait()?;
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Asynchronous version of `split_batch_op`, the table entry not cached or
+    /// the connections not built yet are loaded on the refresh workers instead
+    /// of the caller's thread.
+    fn split_batch_op_async(
+        &self,
+        table_name: &str,
+        batch_op: ObTableBatchOperation,
+    ) -> BoxFuture<Vec<(Arc<ObTable>, ObTableBatchOperation)>> {
+        if self.inner.is_batch_cached(table_name, &batch_op) {
+            return Box::new(future::result(self.split_batch_op(table_name, batch_op)));
+        }
+        let client = self.clone();
+        let table_name = table_name.to_owned();
+        Box::new(
+            self.inner
+                .refresh_workers
+                .spawn_fn(move || client.split_batch_op(&table_name, batch_op)),
+        )
+    }
+
+    fn execute_batch_once_async(
+        &self,
+        table_name: &str,
+        batch_op: ObTableBatchOperation,
+    ) -> BoxFuture<Vec<TableOpResult>> {
+        box_future_try!(self.inner.check_status());
+
+        let timer = OBKV_CLIENT_HISTOGRAM_VEC
+            .with_label_values(&["execute_batch_async"])
+            .start_timer();
+
+        // all the partitions are sent concurrently without any extra thread
+        let put_all = self
+            .split_batch_op_async(table_name, batch_op)
+            .and_then(|table_batch_ops| {
+                future::join_all(
+                    table_batch_ops
+                        .into_iter()
+                        .map(|(table, batch_op)| table.execute_batch_async(batch_op)),
+                )
+            });
+
+        Box::new(put_all.then(move |res| {
+            timer.observe_duration();
+            res.map(|results| results.into_iter().flatten().collect())
+        }))
+    }
+
+    /// Asynchronous version of `execute_batch` with the same retry policy.
+    fn execute_batch_async_with_retry(
+        &self,
+        table_name: &str,
+        batch_op: ObTableBatchOperation,
+    ) -> BoxFuture<Vec<TableOpResult>> {
+        let checked = self.inner.check_batch_values_async(table_name, batch_op);
+        let client = self.clone();
+        let table_name = table_name.to_owned();
+        Box::new(checked.and_then(move |batch_op| {
+            future::loop_fn(0, move |retry_num| {
+                let retry_num = retry_num + 1;
+                let inner = client.inner.clone();
+                let table_name = table_name.clone();
+                client
+                    .execute_batch_once_async(&table_name, batch_op.clone())
+                    .then(move |res| -> BoxFuture<Loop<Vec<TableOpResult>, usize>> {
+                        match res {
+                            Ok(res) => {
+                                inner.reset_table_failure(&table_name);
+                                Box::new(future::ok(Loop::Break(res)))
+                            }
+                            Err(e) => inner.on_async_op_failure(
+                                &table_name,
+                                "execute_batch_async",
+                                retry_num,
+                                e,
+                            ),
+                        }
+                    })
+            })
+        }))
+    }
+}
+
+impl AsyncTable for ObTableClient {
+    type Query = ObTableClientQueryImpl;
+
+    #[inline]
+    fn insert(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64> {
+        util::into_std(Box::new(
+            self.inner
+                .clone()
+                .execute_async(
+                    table_name,
+                    ObTableOperationType::Insert,
+                    row_keys,
+                    Some(columns),
+                    Some(properties),
+                )
+                .map(|result| result.affected_rows()),
+        ))
+    }
+
+    #[inline]
+    fn update(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64> {
+        util::into_std(Box::new(
+            self.inner
+                .clone()
+                .execute_async(
+                    table_name,
+                    ObTableOperationType::Update,
+                    row_keys,
+                    Some(columns),
+                    Some(properties),
+                )
+                .map(|result| result.affected_rows()),
+        ))
+    }
+
+    #[inline]
+    fn insert_or_update(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64> {
+        util::into_std(Box::new(
+            self.inner
+                .clone()
+                .execute_async(
+                    table_name,
+                    ObTableOperationType::InsertOrUpdate,
+                    row_keys,
+                    Some(columns),
+                    Some(properties),
+                )
+                .map(|result| result.affected_rows()),
+        ))
+    }
+
+    #[inline]
+    fn replace(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64> {
+        util::into_std(Box::new(
+            self.inner
+                .clone()
+                .execute_async(
+                    table_name,
+                    ObTableOperationType::Replace,
+                    row_keys,
+                    Some(columns),
+                    Some(properties),
+                )
+                .map(|result| result.affected_rows()),
+        ))
+    }
+
+    #[inline]
+    fn append(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64> {
+        util::into_std(Box::new(
+            self.inner
+                .clone()
+                .execute_async(
+                    table_name,
+                    ObTableOperationType::Append,
+                    row_keys,
+                    Some(columns),
+                    Some(properties),
+                )
+                .map(|result| result.affected_rows()),
+        ))
+    }
+
+    #[inline]
+    fn increment(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> StdBoxFuture<i64> {
+        util::into_std(Box::new(
+            self.inner
+                .clone()
+                .execute_async(
+                    table_name,
+                    ObTableOperationType::Increment,
+                    row_keys,
+                    Some(columns),
+                    Some(properties),
+                )
+                .map(|result| result.affected_rows()),
+        ))
+    }
+
+    #[inline]
+    fn delete(&self, table_name: &str, row_keys: Vec<Value>) -> StdBoxFuture<i64> {
+        util::into_std(Box::new(
+            self.inner
+                .clone()
+                .execute_async(table_name, ObTableOperationType::Del, row_keys, None, None)
+                .map(|result| result.affected_rows()),
+        ))
+    }
+
+    #[inline]
+    fn get(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+    ) -> StdBoxFuture<HashMap<String, Value>> {
+        util::into_std(Box::new(
+            self.inner
+                .clone()
+                .execute_async(
+                    table_name,
+                    ObTableOperationType::Get,
+                    row_keys,
+                    Some(columns),
+                    None,
+                )
+                .map(|result| result.take_entity().take_properties()),
+        ))
+    }
+
+    #[inline]
+    fn batch_operation(&self, ops_num_hint: usize) -> ObTableBatchOperation {
+        ObTableBatchOperation::with_ops_num_raw(ops_num_hint)
+    }
+
+    #[inline]
+    fn execute_batch(
+        &self,
+        table_name: &str,
+        batch_op: ObTableBatchOperation,
+    ) -> StdBoxFuture<Vec<TableOpResult>> {
+        util::into_std(self.execute_batch_async_with_retry(table_name, batch_op))
+    }
+
+    #[inline]
+    fn query(&self, table_name: &str) -> ObTableClientQueryImpl {
+        ObTableClientQueryImpl::new(table_name, self.inner.clone())
     }
 }
 
@@ -1730,6 +3201,48 @@ impl Table for ObTableClient {
             .affected_rows())
     }
 
+    fn append_with_options(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+        options: OperationOptions,
+    ) -> Result<ObTableEntity> {
+        Ok(self
+            .inner
+            .execute_with_options(
+                table_name,
+                ObTableOperationType::Append,
+                row_keys,
+                Some(columns),
+                Some(properties),
+                options,
+            )?
+            .take_entity())
+    }
+
+    fn increment_with_options(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+        options: OperationOptions,
+    ) -> Result<ObTableEntity> {
+        Ok(self
+            .inner
+            .execute_with_options(
+                table_name,
+                ObTableOperationType::Increment,
+                row_keys,
+                Some(columns),
+                Some(properties),
+                options,
+            )?
+            .take_entity())
+    }
+
     #[inline]
     fn delete(&self, table_name: &str, row_keys: Vec<Value>) -> Result<i64> {
         Ok(self
@@ -1758,6 +3271,97 @@ impl Table for ObTableClient {
             .take_properties())
     }
 
+    fn get_with_options(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        columns: Vec<String>,
+        options: OperationOptions,
+    ) -> Result<HashMap<String, Value>> {
+        Ok(self
+            .inner
+            .execute_with_options(
+                table_name,
+                ObTableOperationType::Get,
+                row_keys,
+                Some(columns),
+                None,
+                options,
+            )?
+            .take_entity()
+            .take_properties())
+    }
+
+    fn check_and_insert(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        condition: CheckCondition,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> Result<bool> {
+        filter::check_and_mutate(
+            row_keys,
+            &condition,
+            ObTableOperationType::Insert,
+            Some(columns),
+            Some(properties),
+            |q| self.inner.query_and_mutate(table_name, q),
+        )
+    }
+
+    fn check_and_insert_up(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        condition: CheckCondition,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> Result<bool> {
+        filter::check_and_mutate(
+            row_keys,
+            &condition,
+            ObTableOperationType::InsertOrUpdate,
+            Some(columns),
+            Some(properties),
+            |q| self.inner.query_and_mutate(table_name, q),
+        )
+    }
+
+    fn check_and_update(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        condition: CheckCondition,
+        columns: Vec<String>,
+        properties: Vec<Value>,
+    ) -> Result<bool> {
+        filter::check_and_mutate(
+            row_keys,
+            &condition,
+            ObTableOperationType::Update,
+            Some(columns),
+            Some(properties),
+            |q| self.inner.query_and_mutate(table_name, q),
+        )
+    }
+
+    fn check_and_delete(
+        &self,
+        table_name: &str,
+        row_keys: Vec<Value>,
+        condition: CheckCondition,
+    ) -> Result<bool> {
+        filter::check_and_mutate(
+            row_keys,
+            &condition,
+            ObTableOperationType::Del,
+            None,
+            None,
+            |q| self.inner.query_and_mutate(table_name, q),
+        )
+    }
+
     #[inline]
     fn batch_operation(&self, ops_num_hint: usize) -> ObTableBatchOperation {
         ObTableBatchOperation::with_ops_num_raw(ops_num_hint)
@@ -1766,8 +3370,9 @@ impl Table for ObTableClient {
     fn execute_batch(
         &self,
         table_name: &str,
-        batch_op: ObTableBatchOperation,
+        mut batch_op: ObTableBatchOperation,
     ) -> Result<Vec<TableOpResult>> {
+        self.inner.check_batch_values(table_name, &mut batch_op)?;
         let mut retry_num = 0;
         loop {
             retry_num += 1;
@@ -1876,7 +3481,7 @@ impl StreamQuerier for ObTableClientStreamQuerier {
             .with_label_values(&["query_rows"])
             .observe(row_count as f64);
 
-        stream_result.cache_stream_next((part_id, ob_table), result);
+        stream_result.cache_stream_next((part_id, ob_table), result)?;
         Ok(row_count)
     }
 
@@ -1911,16 +3516,46 @@ impl StreamQuerier for ObTableClientStreamQuerier {
             .observe(row_count as f64);
 
         if is_stream_next {
-            stream_result.cache_stream_next((part_id, ob_table), result);
+            stream_result.cache_stream_next((part_id, ob_table), result)?;
         }
         Ok(row_count)
     }
+
+    fn acquire_permit(&self) -> Result<Option<PermitGuard>> {
+        self.client.acquire_query_permit()
+    }
+
+    fn execute_query_async(
+        &self,
+        part_id_and_table: (i64, Arc<ObTable>),
+        payload: ObTableQueryRequest,
+    ) -> BoxFuture<PartitionQueryResult> {
+        self.start_execute_ts
+            .store(current_time_millis(), Ordering::Relaxed);
+
+        self.client
+            .clone()
+            .execute_query_async(&self.table_name, part_id_and_table, payload, None)
+    }
+
+    fn execute_stream_async(
+        &self,
+        part_id_and_table: (i64, Arc<ObTable>),
+        payload: ObTableStreamRequest,
+    ) -> BoxFuture<PartitionQueryResult> {
+        self.client
+            .clone()
+            .execute_query_async(&self.table_name, part_id_and_table, payload, None)
+    }
 }
 
 /// TODO refactor with ObTableQueryImpl
-struct ObTableClientQueryImpl {
+#[derive(Clone)]
+pub struct ObTableClientQueryImpl {
     operation_timeout: Option<Duration>,
     entity_type: ObTableEntityType,
+    result_mode: QueryResultMode,
+    consistency_level: Option<ObTableConsistencyLevel>,
     table_name: String,
     client: Arc<ObTableClientInner>,
     table_query: ObTableQuery,
@@ -1931,6 +3566,8 @@ impl ObTableClientQueryImpl {
         Self {
             operation_timeout: None,
             entity_type: ObTableEntityType::Dynamic,
+            result_mode: QueryResultMode::default(),
+            consistency_level: None,
             table_name: table_name.to_owned(),
             client,
             table_query: ObTableQuery::new(),
@@ -1940,33 +3577,44 @@ impl ObTableClientQueryImpl {
     fn reset(&mut self) {
         self.table_query = ObTableQuery::new();
     }
-}
-
-impl TableQuery for ObTableClientQueryImpl {
-    fn execute(&self) -> Result<QueryResultSet> {
-        let _timer = OBKV_CLIENT_HISTOGRAM_VEC
-            .with_label_values(&["query_execute"])
-            .start_timer();
-
-        let mut partition_table: HashMap<i64, (i64, Arc<ObTable>)> = HashMap::new();
 
-        self.table_query.verify()?;
+    fn read_consistency_level(&self) -> ObTableConsistencyLevel {
+        self.consistency_level
+            .unwrap_or(self.client.config.read_consistency_level)
+    }
 
-        for range in self.table_query.get_key_ranges() {
-            let border_flag = range.get_border_flag();
-            let pairs = self.client.get_tables(
-                &self.table_name,
-                range.get_start_key().keys(),
-                border_flag.is_inclusive_start(),
-                range.get_end_key().keys(),
-                border_flag.is_inclusive_end(),
-                false,
-            )?;
+    fn get_partition_tables(&self) -> Result<HashMap<i64, (i64, Arc<ObTable>)>> {
+        self.client.get_query_tables(
+            &self.table_name,
+            &self.table_query,
+            self.read_consistency_level(),
+        )
+    }
 
-            for (part_id, ob_table) in pairs {
-                partition_table.insert(part_id, (part_id, ob_table));
+    fn new_stream_result(
+        &self,
+        partition_table: HashMap<i64, (i64, Arc<ObTable>)>,
+    ) -> Result<QueryStreamResult> {
+        let merge_sort =
+            self.result_mode == QueryResultMode::MergeSort && partition_table.len() > 1;
+        let row_key_columns = if merge_sort {
+            let row_key_columns = self.client.get_row_key_columns(&self.table_name)?;
+            let select_columns = self.table_query.get_select_columns();
+            for column in &row_key_columns {
+                if !select_columns.contains(column) {
+                    return Err(CommonErr(
+                        CommonErrCode::InvalidParam,
+                        format!(
+                            "Row key column {} must be selected for merge sort, table_name:{}",
+                            column, self.table_name
+                        ),
+                    ));
+                }
             }
-        }
+            Some(row_key_columns)
+        } else {
+            None
+        };
 
         let mut stream_result = QueryStreamResult::new(
             Arc::new(ObTableClientStreamQuerier::new(
@@ -1981,11 +3629,104 @@ impl TableQuery for ObTableClientQueryImpl {
         stream_result.set_expectant(partition_table);
         stream_result.set_operation_timeout(self.operation_timeout);
         stream_result.set_flag(self.client.config.log_level_flag);
+        stream_result.set_consistency_level(self.read_consistency_level());
+        if let Some(row_key_columns) = row_key_columns {
+            stream_result.set_merge_sort(row_key_columns);
+        }
+        if let QueryResultMode::Parallel(parallelism) = self.result_mode {
+            stream_result.set_parallelism(parallelism);
+        }
+        Ok(stream_result)
+    }
+
+    /// Query the first batch of the partitions, the partitions are queried
+    /// while the stream is polled in the parallel mode.
+    fn query_partitions_async(
+        &self,
+        partition_table: HashMap<i64, (i64, Arc<ObTable>)>,
+        timer: HistogramTimer,
+    ) -> BoxFuture<QueryResultSet> {
+        let mut stream_result = box_future_try!(self.new_stream_result(partition_table.clone()));
+
+        if let QueryResultMode::Parallel(_) = self.result_mode {
+            box_future_try!(stream_result.init());
+            timer.observe_duration();
+            return Box::new(future::ok(QueryResultSet::from_stream_result(
+                stream_result,
+            )));
+        }
+
+        let requests: Vec<_> = partition_table
+            .values()
+            .map(|(part_id, ob_table)| {
+                let payload = stream_result.new_query_request(*part_id, ob_table);
+                ((*part_id, ob_table.clone()), payload)
+            })
+            .collect();
+        // every request in flight holds a permit, so no more partitions than the
+        // limit are queried at the same time
+        let concurrency = self
+            .client
+            .config
+            .query_concurrency_limit
+            .unwrap_or(requests.len())
+            .max(1);
+        let client = self.client.clone();
+        let table_name = self.table_name.clone();
+        let queries = stream::iter_ok(requests)
+            .map(move |(part_id_and_table, payload)| {
+                let client = client.clone();
+                let table_name = table_name.clone();
+                future::lazy(move || -> BoxFuture<PartitionQueryResult> {
+                    let permit = box_future_try!(client.acquire_query_permit());
+                    client.execute_query_async(&table_name, part_id_and_table, payload, permit)
+                })
+            })
+            .buffer_unordered(concurrency)
+            .collect();
+
+        Box::new(queries.and_then(move |results| {
+            timer.observe_duration();
+            stream_result.init_with_results(results)?;
+            Ok(QueryResultSet::from_stream_result(stream_result))
+        }))
+    }
+}
+
+impl TableQuery for ObTableClientQueryImpl {
+    fn execute(&self) -> Result<QueryResultSet> {
+        let _timer = OBKV_CLIENT_HISTOGRAM_VEC
+            .with_label_values(&["query_execute"])
+            .start_timer();
+
+        self.table_query.verify()?;
+
+        let mut stream_result = self.new_stream_result(self.get_partition_tables()?)?;
         stream_result.init()?;
 
         Ok(QueryResultSet::from_stream_result(stream_result))
     }
 
+    fn execute_async(&self) -> StdBoxFuture<QueryResultSet> {
+        let timer = OBKV_CLIENT_HISTOGRAM_VEC
+            .with_label_values(&["query_execute_async"])
+            .start_timer();
+
+        if let Err(e) = self.table_query.verify() {
+            return util::into_std(Box::new(future::err(e)));
+        }
+
+        let query = self.clone();
+        let partition_table = self.client.get_query_tables_async(
+            &self.table_name,
+            &self.table_query,
+            self.read_consistency_level(),
+        );
+        util::into_std(Box::new(partition_table.and_then(move |partition_table| {
+            query.query_partitions_async(partition_table, timer)
+        })))
+    }
+
     #[inline]
     fn get_table_name(&self) -> String {
         self.table_name.to_owned()
@@ -2139,6 +3880,24 @@ impl TableQuery for ObTableClientQueryImpl {
         self
     }
 
+    #[inline]
+    fn result_mode(mut self, mode: QueryResultMode) -> Self
+    where
+        Self: Sized,
+    {
+        self.result_mode = mode;
+        self
+    }
+
+    #[inline]
+    fn consistency_level(mut self, consistency_level: ObTableConsistencyLevel) -> Self
+    where
+        Self: Sized,
+    {
+        self.consistency_level = Some(consistency_level);
+        self
+    }
+
     #[inline]
     fn operation_timeout(mut self, timeout: Duration) -> Self
     where
@@ -2159,15 +3918,36 @@ impl TableQuery for ObTableClientQueryImpl {
 pub struct Builder {
     full_user_name: String,
     param_url: String,
+    rs_list: Option<RsList>,
     password: String,
     user_name: String,
     tenant_name: String,
     cluster_name: String,
     database: String,
     running_mode: RunningMode,
+    odp_addr: Option<ObServerAddr>,
+    metadata_provider: Option<MetadataProviderRef>,
     config: ClientConfig,
 }
 
+/// The metadata provider set to the builder, which is compared by identity.
+#[derive(Clone)]
+struct MetadataProviderRef(Arc<dyn MetadataProvider>);
+
+impl fmt::Debug for MetadataProviderRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MetadataProvider")
+    }
+}
+
+impl PartialEq for MetadataProviderRef {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::as_ptr(&self.0) as *const u8 == Arc::as_ptr(&other.0) as *const u8
+    }
+}
+
+impl Eq for MetadataProviderRef {}
+
 const USER_NAME_SEPERATORS: &[&str] = &[":", "-", "."];
 const DATABASE_PARAM_KEY: &str = "database";
 
@@ -2176,12 +3956,15 @@ impl Builder {
         Self {
             full_user_name: "".to_owned(),
             param_url: "".to_owned(),
+            rs_list: None,
             password: "".to_owned(),
             user_name: "".to_owned(),
             tenant_name: "".to_owned(),
             cluster_name: "".to_owned(),
             database: "".to_owned(),
             running_mode: RunningMode::Normal,
+            odp_addr: None,
+            metadata_provider: None,
             config: ClientConfig::default(),
         }
     }
@@ -2302,6 +4085,25 @@ impl Builder {
         self
     }
 
+    /// Set the root servers in the `ip:sql_port` format, which replaces the
+    /// param url to bootstrap without the OCP.
+    pub fn rs_list(mut self, rs_list: &[&str]) -> Self {
+        let addrs = parse_rs_list(rs_list.iter().copied()).expect("Invalid rs list");
+        assert!(!addrs.is_empty(), "Blank rs list");
+
+        self.rs_list = Some(RsList::Addrs(addrs));
+        self
+    }
+
+    /// Set the file listing one root server in the `ip:sql_port` format per
+    /// line, which replaces the param url to bootstrap without the OCP.
+    pub fn rs_list_file(mut self, path: &str) -> Self {
+        assert_not_empty(path, "Blank rs list file");
+
+        self.rs_list = Some(RsList::File(path.to_owned()));
+        self
+    }
+
     pub fn sys_user_name(mut self, name: &str) -> Self {
         self.config.sys_user_name = name.to_owned();
         self
@@ -2312,13 +4114,51 @@ impl Builder {
         self
     }
 
+    pub fn idc(mut self, idc: &str) -> Self {
+        self.config.idc = Some(idc.to_owned());
+        self
+    }
+
+    /// Send all the requests to the ODP(obproxy) and let it route them, the
+    /// param url and the sys user are not required in this mode, and the
+    /// version of the cluster is detected through the ODP by the user.
+    pub fn odp_addr(mut self, ip: &str, sql_port: i32, rpc_port: i32) -> Self {
+        assert_not_empty(ip, "Blank odp ip");
+
+        let mut addr = ObServerAddr::new();
+        addr.address(ip.to_owned());
+        addr.set_sql_port(sql_port);
+        addr.set_svr_port(rpc_port);
+        self.odp_addr = Some(addr);
+        self
+    }
+
+    /// Load the metadata to route the requests by the provider instead of
+    /// the system tables of the cluster, the param url is not required then.
+    pub fn metadata_provider(mut self, provider: Arc<dyn MetadataProvider>) -> Self {
+        self.metadata_provider = Some(MetadataProviderRef(provider));
+        self
+    }
+
+    /// Set the database, which is parsed from the param url by default.
+    pub fn database(mut self, database: &str) -> Self {
+        assert_not_empty(database, "Blank database");
+
+        self.database = database.to_owned();
+        self
+    }
+
     pub fn build(self) -> Result<ObTableClient> {
-        assert_not_empty(&self.param_url, "Blank param url");
+        if self.odp_addr.is_none() && self.rs_list.is_none() && self.metadata_provider.is_none() {
+            assert_not_empty(&self.param_url, "Blank param url");
+        }
         assert_not_empty(&self.full_user_name, "Blank full user name");
+        assert_not_empty(&self.database, "Blank database");
 
         Ok(ObTableClient {
             inner: Arc::new(ObTableClientInner::internal_new(
                 self.param_url,
+                self.rs_list,
                 self.full_user_name,
                 self.password,
                 self.user_name,
@@ -2326,6 +4166,8 @@ impl Builder {
                 self.cluster_name,
                 self.database,
                 self.running_mode,
+                self.odp_addr,
+                self.metadata_provider.map(|provider| provider.0),
                 self.config,
             )?),
             refresh_thread_pool: Arc::new(ScheduledThreadPool::with_name(
@@ -2341,3 +4183,260 @@ impl Default for Builder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        client::{
+            ocp::OcpModel,
+            schema::{PartitionSchema, TableSchema},
+        },
+        location::{new_hash_table_entry, ObPartitionEntry, ObPartitionInfo},
+    };
+
+    /// Serves a hash partitioned table led by one server without the cluster.
+    struct FakeMetadataProvider {
+        leader: ObServerAddr,
+    }
+
+    impl MetadataProvider for FakeMetadataProvider {
+        fn load_ocp_model(&self, _from_cache_when_fail: bool) -> Result<OcpModel> {
+            Ok(OcpModel {
+                observer_addrs: vec![self.leader.clone()],
+                cluster_id: 1,
+            })
+        }
+
+        fn detect_ob_version(&self, _rs_list: &[ObServerAddr]) -> Result<ObVersion> {
+            Ok(self.ob_version())
+        }
+
+        fn ob_version(&self) -> ObVersion {
+            ObVersion::new(4, 2, 1, 0)
+        }
+
+        fn load_table_entry(
+            &self,
+            _server_addr: &ObServerAddr,
+            _key: &TableEntryKey,
+        ) -> Result<TableEntry> {
+            Ok(new_hash_table_entry(4, &self.leader))
+        }
+
+        fn load_partition_info(
+            &self,
+            _server_addr: &ObServerAddr,
+            _key: &TableEntryKey,
+            table_entry: &TableEntry,
+        ) -> Result<ObPartitionInfo> {
+            table_entry.partition_info().clone().ok_or_else(|| {
+                CommonErr(
+                    CommonErrCode::NotFound,
+                    "partition info not found".to_owned(),
+                )
+            })
+        }
+
+        fn load_partition_location(
+            &self,
+            _server_addr: &ObServerAddr,
+            _key: &TableEntryKey,
+            table_entry: &TableEntry,
+        ) -> Result<ObPartitionEntry> {
+            table_entry.partition_entry().clone().ok_or_else(|| {
+                CommonErr(
+                    CommonErrCode::NotFound,
+                    "partition entry not found".to_owned(),
+                )
+            })
+        }
+
+        fn load_table_schema(
+            &self,
+            _server_addr: &ObServerAddr,
+            key: &TableEntryKey,
+            _table_entry: &TableEntry,
+        ) -> Result<TableSchema> {
+            Ok(TableSchema {
+                table_name: key.table_name().to_owned(),
+                table_id: 500_002,
+                columns: vec![],
+                row_key_columns: vec!["c1".to_owned()],
+                partition: PartitionSchema {
+                    level: ObPartitionLevel::One,
+                    part_num: 4,
+                    first_part: None,
+                    sub_part: None,
+                },
+            })
+        }
+    }
+
+    fn new_addr(ip: &str) -> ObServerAddr {
+        let mut addr = ObServerAddr::new();
+        addr.address(ip.to_owned());
+        addr.set_sql_port(2881);
+        addr.set_svr_port(2882);
+        addr
+    }
+
+    fn new_client(leader: &ObServerAddr) -> ObTableClient {
+        Builder::new()
+            .full_user_name("root@test#cluster")
+            .database("test")
+            .metadata_provider(Arc::new(FakeMetadataProvider {
+                leader: leader.clone(),
+            }))
+            .build()
+            .expect("fail to build client")
+    }
+
+    #[test]
+    fn test_route_by_metadata_provider() {
+        let leader = new_addr("127.0.0.1");
+        let client = new_client(&leader);
+        assert!(client.inner.ob_version().is_4x());
+        client.inner.server_roster.reset(vec![leader.clone()]);
+
+        let table_entry = client
+            .inner
+            .get_or_refresh_table_entry("test_hash_table", false)
+            .expect("fail to load table entry");
+        // 5 % 4
+        let part_id = client
+            .inner
+            .get_partition(&table_entry, &[Value::from(5i64)])
+            .expect("fail to get partition");
+        assert_eq!(1, part_id);
+        let (route_id, replica) = client
+            .inner
+            .get_partition_replica(&table_entry, part_id, ObTableConsistencyLevel::Strong)
+            .unwrap();
+        assert_eq!(200_002, route_id);
+        assert_eq!(Some(&leader), replica.as_ref().map(|r| r.addr()));
+    }
+
+    #[test]
+    fn test_diff_leader_changed() {
+        let (old_leader, new_leader) = (new_addr("127.0.0.1"), new_addr("127.0.0.2"));
+        let old = new_hash_table_entry(2, &old_leader);
+        let new = new_hash_table_entry(2, &new_leader);
+
+        let events = LocationEvent::diff("t", &old, &new);
+        assert_eq!(4, events.len());
+        for part_id in 0..2 {
+            assert!(events.contains(&LocationEvent::LeaderChanged {
+                table_name: "t".to_owned(),
+                part_id,
+                leader: Some(new_leader.clone()),
+            }));
+        }
+        assert!(events.contains(&LocationEvent::ServerAdded {
+            table_name: "t".to_owned(),
+            addr: new_leader,
+        }));
+        assert!(events.contains(&LocationEvent::ServerRemoved {
+            table_name: "t".to_owned(),
+            addr: old_leader,
+        }));
+
+        // nothing changed
+        assert!(LocationEvent::diff("t", &old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_diff_partition_added_and_removed() {
+        let leader = new_addr("127.0.0.1");
+        let old = new_hash_table_entry(2, &leader);
+        let new = new_hash_table_entry(3, &leader);
+
+        assert_eq!(
+            vec![LocationEvent::PartitionAdded {
+                table_name: "t".to_owned(),
+                part_id: 2,
+            }],
+            LocationEvent::diff("t", &old, &new)
+        );
+        assert_eq!(
+            vec![LocationEvent::PartitionRemoved {
+                table_name: "t".to_owned(),
+                part_id: 2,
+            }],
+            LocationEvent::diff("t", &new, &old)
+        );
+    }
+
+    #[test]
+    fn test_diff_server_added_and_removed() {
+        let leader = new_addr("127.0.0.1");
+        let located = new_hash_table_entry(2, &leader);
+        let mut unlocated = located.clone();
+        unlocated.set_partition_entry(None);
+
+        let events = LocationEvent::diff("t", &unlocated, &located);
+        assert_eq!(3, events.len());
+        assert!(events.contains(&LocationEvent::ServerAdded {
+            table_name: "t".to_owned(),
+            addr: leader.clone(),
+        }));
+
+        let events = LocationEvent::diff("t", &located, &unlocated);
+        assert_eq!(3, events.len());
+        assert!(events.contains(&LocationEvent::ServerRemoved {
+            table_name: "t".to_owned(),
+            addr: leader,
+        }));
+    }
+
+    #[test]
+    fn test_remove_disconnected_subscriber() {
+        let leader = new_addr("127.0.0.1");
+        let client = new_client(&leader);
+        let receiver = client.subscribe_location_events();
+        drop(client.subscribe_location_events());
+        assert_eq!(2, client.inner.location_subscribers.rl().len());
+
+        let old = new_hash_table_entry(2, &leader);
+        let new = new_hash_table_entry(3, &leader);
+        client.inner.on_table_entry_refreshed("t", &old, &new);
+        assert_eq!(1, client.inner.location_subscribers.rl().len());
+        assert_eq!(
+            LocationEvent::PartitionAdded {
+                table_name: "t".to_owned(),
+                part_id: 2,
+            },
+            receiver.try_recv().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_load_table_schema_async() {
+        let leader = new_addr("127.0.0.1");
+        let client = new_client(&leader);
+        client.inner.server_roster.reset(vec![leader]);
+        let table_name = "test_hash_table";
+
+        let schema = client
+            .inner
+            .get_or_load_table_schema_async(table_name)
+            .wait()
+            .expect("fail to load table schema");
+        assert_eq!(vec!["c1".to_owned()], schema.row_key_columns);
+        assert!(client.inner.table_schemas.rl().contains_key(table_name));
+
+        // only dropped on the schema errors
+        let not_master = CommonErr(
+            CommonErrCode::ObException(ResultCodes::OB_NOT_MASTER),
+            "not master".to_owned(),
+        );
+        client.inner.on_schema_error(table_name, &not_master);
+        assert!(client.inner.table_schemas.rl().contains_key(table_name));
+        let column_not_found = CommonErr(
+            CommonErrCode::ObException(ResultCodes::OB_ERR_COLUMN_NOT_FOUND),
+            "column not found".to_owned(),
+        );
+        client.inner.on_schema_error(table_name, &column_not_found);
+        assert!(!client.inner.table_schemas.rl().contains_key(table_name));
+    }
+}
@@ -117,6 +117,8 @@ pub enum CommonErrCode {
     Lock,
     PermitDenied,
     ConvertFailed,
+    Backpressure,
+    NotSupported,
 }
 
 impl Error {
@@ -170,6 +172,15 @@ impl Error {
         }
         false
     }
+
+    /// Returns true when the server reports the schema of the table or its
+    /// columns differ from the ones the client knows.
+    pub fn is_schema_error(&self) -> bool {
+        match self.ob_result_code() {
+            Some(code) => code.is_schema_error(),
+            None => false,
+        }
+    }
 }
 
 pub type Result<T> = ::std::result::Result<T, Error>;
@@ -185,5 +196,16 @@ mod test {
             "test_err".to_owned(),
         );
         assert!(err.need_refresh_table());
+        assert!(!err.is_schema_error());
+    }
+
+    #[test]
+    fn schema_error() {
+        let err = Error::Common(
+            CommonErrCode::ObException(ResultCodes::OB_ERR_COLUMN_NOT_FOUND),
+            "test_err".to_owned(),
+        );
+        assert!(err.is_schema_error());
+        assert!(!err.need_refresh_table());
     }
 }
@@ -55,11 +55,16 @@ pub mod serde_obkv;
 mod util;
 pub use self::{
     client::{
-        query::{QueryResultSet, TableQuery},
+        filter::{CheckCondition, CompareOp},
+        metadata::MetadataProvider,
+        query::{QueryResultMode, QueryResultSet, QueryResultStream, TableQuery},
+        schema::{ColumnSchema, PartitionBound, PartitionDesc, PartitionSchema, TableSchema},
         table::ObTable,
-        table_client::{Builder, ObTableClient, RunningMode},
-        ClientConfig, Table, TableOpResult,
+        table_client::{Builder, LocationEvent, ObTableClient, RunningMode},
+        AsyncTable, ClientConfig, OperationOptions, Table, TableOpResult,
     },
+    location::{part_func_type::PartFuncType, ObPartitionLevel, ObServerAddr, ObVersion},
     rpc::protocol::{codes::ResultCodes, payloads, query},
-    serde_obkv::value::{ObjType, Value},
+    serde_obkv::value::{decimal::Decimal, ObjType, Value},
+    util::{BoxFuture, StdBoxFuture},
 };
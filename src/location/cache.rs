@@ -0,0 +1,285 @@
+/*-
+ * #%L
+ * OBKV Table Client Framework
+ * %%
+ * Copyright (C) 2021 OceanBase
+ * %%
+ * OBKV Table Client Framework is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *          http://license.coscl.org.cn/MulanPSL2
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ * #L%
+ */
+
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use super::{
+    util::{LocationUtil, ObPartitionRows},
+    ObPartitionEntry, ObVersion, TableEntry, TableEntryKey, TableLocation,
+};
+use crate::{
+    error::Result,
+    util::{current_time_millis, duration_to_millis},
+};
+
+const SAVE_SLOW_THRESHOLD_MS: i64 = 100;
+
+/// The persisted form of the table entry, the partition info is rebuilt from
+/// the rows it was built from.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct TableEntrySnapshot {
+    // the entries of the other versions are not valid any more
+    ob_version: String,
+    table_id: i64,
+    tablet_id: i64,
+    partition_num: i64,
+    refresh_time_mills: i64,
+    table_location: TableLocation,
+    partition_entry: Option<ObPartitionEntry>,
+    partition_rows: Option<ObPartitionRows>,
+}
+
+impl TableEntrySnapshot {
+    fn new(table_entry: &TableEntry, ob_version: ObVersion) -> Self {
+        Self {
+            ob_version: ob_version.to_string(),
+            table_id: table_entry.table_id,
+            tablet_id: table_entry.tablet_id,
+            partition_num: table_entry.partition_num,
+            refresh_time_mills: table_entry.refresh_time_mills(),
+            table_location: table_entry.table_location.clone(),
+            partition_entry: table_entry.partition_entry.clone(),
+            partition_rows: table_entry
+                .partition_info
+                .as_ref()
+                .map(|info| info.rows.clone()),
+        }
+    }
+
+    fn to_table_entry(&self) -> Result<TableEntry> {
+        let mut table_entry = TableEntry {
+            table_id: self.table_id,
+            tablet_id: self.tablet_id,
+            partition_num: self.partition_num,
+            refresh_time_mills: Arc::new(AtomicUsize::new(self.refresh_time_mills as usize)),
+            partition_info: None,
+            table_location: self.table_location.clone(),
+            partition_entry: self.partition_entry.clone(),
+            row_key_element: HashMap::new(),
+        };
+        if let Some(rows) = &self.partition_rows {
+            LocationUtil::build_partition_info_with_rows(&mut table_entry, rows.clone())?;
+        }
+        Ok(table_entry)
+    }
+}
+
+/// The local file persisting the table entries, which are served on the warm
+/// start instead of querying the servers.
+pub struct TableEntryCacheFile {
+    path: String,
+    // cluster:tenant:database:table -> TableEntrySnapshot
+    entries: Mutex<HashMap<String, TableEntrySnapshot>>,
+}
+
+impl TableEntryCacheFile {
+    pub fn new(path: &str) -> TableEntryCacheFile {
+        let file = TableEntryCacheFile {
+            path: path.to_owned(),
+            entries: Mutex::new(HashMap::new()),
+        };
+        if let Err(e) = file.load() {
+            // there is no file on the first start
+            if Path::new(path).exists() {
+                warn!(
+                    "TableEntryCacheFile::new fail to load cached table entries, path:{}, err: {}",
+                    path, e
+                );
+            } else {
+                debug!(
+                    "TableEntryCacheFile::new no cached table entries, path:{}, err: {}",
+                    path, e
+                );
+            }
+        }
+        file
+    }
+
+    fn cache_key(key: &TableEntryKey) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            key.cluster_name, key.tenant_name, key.database_name, key.table_name
+        )
+    }
+
+    fn load(&self) -> Result<()> {
+        let file = File::open(&self.path)?;
+        let reader = io::BufReader::new(file);
+        let entries: HashMap<String, TableEntrySnapshot> = serde_json::from_reader(reader)?;
+        *self.entries.lock().unwrap() = entries;
+
+        Ok(())
+    }
+
+    /// Get the table entry persisted, the entries older than `max_stale` or
+    /// of the other versions are ignored.
+    pub fn get(
+        &self,
+        key: &TableEntryKey,
+        ob_version: ObVersion,
+        max_stale: Duration,
+    ) -> Option<TableEntry> {
+        let snapshot = self
+            .entries
+            .lock()
+            .unwrap()
+            .get(&Self::cache_key(key))
+            .cloned()?;
+
+        if snapshot.ob_version != ob_version.to_string()
+            || current_time_millis() - snapshot.refresh_time_mills > duration_to_millis(&max_stale)
+        {
+            debug!(
+                "TableEntryCacheFile::get ignore the stale table entry, key:{:?}, version:{}, \
+                 refresh_time_mills:{}",
+                key, snapshot.ob_version, snapshot.refresh_time_mills
+            );
+            return None;
+        }
+
+        match snapshot.to_table_entry() {
+            Ok(table_entry) => Some(table_entry),
+            Err(e) => {
+                warn!(
+                    "TableEntryCacheFile::get fail to rebuild table entry, key:{:?}, err:{}",
+                    key, e
+                );
+                None
+            }
+        }
+    }
+
+    pub fn save(
+        &self,
+        key: &TableEntryKey,
+        table_entry: &TableEntry,
+        ob_version: ObVersion,
+    ) -> Result<()> {
+        let snapshot = TableEntrySnapshot::new(table_entry, ob_version);
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(Self::cache_key(key), snapshot);
+
+        let start = current_time_millis();
+
+        // write to a temp file and rename it, so the file is never left half
+        // written
+        let tmp_path = format!("{}.tmp", self.path);
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&tmp_path)?;
+        let mut writer = io::BufWriter::new(file);
+        serde_json::to_writer(&mut writer, &*entries)?;
+        writer.flush()?;
+        fs::rename(&tmp_path, &self.path)?;
+
+        let cost = current_time_millis() - start;
+        if cost > SAVE_SLOW_THRESHOLD_MS {
+            warn!("TableEntryCacheFile::save cost {} ms.", cost);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::location::{
+        ObServerAddr, ObServerInfo, ObServerRole, ObServerStatus, ReplicaLocation,
+    };
+
+    fn new_table_entry(refresh_time_mills: i64) -> TableEntry {
+        let mut addr = ObServerAddr::new();
+        addr.address("127.0.0.1".to_owned());
+        addr.set_sql_port(2881);
+        addr.set_svr_port(2882);
+
+        TableEntry {
+            table_id: 1099511677777,
+            tablet_id: -1,
+            partition_num: 1,
+            refresh_time_mills: Arc::new(AtomicUsize::new(refresh_time_mills as usize)),
+            partition_info: None,
+            table_location: TableLocation {
+                replica_locations: vec![ReplicaLocation {
+                    addr,
+                    info: ObServerInfo {
+                        stop_time: 0,
+                        status: ObServerStatus::Active,
+                    },
+                    role: ObServerRole::Leader,
+                }],
+            },
+            partition_entry: None,
+            row_key_element: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_table_entry_cache_file() {
+        let dir = Builder::new().prefix("table_entry").tempfile().unwrap();
+        let path = dir.path().to_str().unwrap();
+        let key = TableEntryKey::new("cluster", "tenant", "test", "test_varchar_table");
+        let version = ObVersion::new(4, 2, 1, 0);
+        let max_stale = Duration::from_secs(60);
+
+        let file = TableEntryCacheFile::new(path);
+        assert!(file.get(&key, version, max_stale).is_none());
+
+        let table_entry = new_table_entry(current_time_millis());
+        file.save(&key, &table_entry, version)
+            .expect("fail to save table entry");
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+
+        // load again
+        let file = TableEntryCacheFile::new(path);
+        let cached = file
+            .get(&key, version, max_stale)
+            .expect("fail to get table entry");
+        assert_eq!(table_entry.table_id, cached.table_id);
+        assert_eq!(
+            table_entry.refresh_time_mills(),
+            cached.refresh_time_mills()
+        );
+        assert_eq!(table_entry.table_location, cached.table_location);
+
+        // the entries of the other versions are ignored
+        assert!(file
+            .get(&key, ObVersion::new(3, 2, 4, 0), max_stale)
+            .is_none());
+
+        // the stale entries are ignored
+        let stale_entry = new_table_entry(current_time_millis() - 120_000);
+        file.save(&key, &stale_entry, version)
+            .expect("fail to save table entry");
+        assert!(file.get(&key, version, max_stale).is_none());
+    }
+}
@@ -17,6 +17,7 @@
 
 use std::{
     collections::{HashMap, HashSet},
+    fmt,
     hash::{Hash, Hasher},
     str::FromStr,
     sync::{
@@ -31,19 +32,25 @@ use rand::{seq::SliceRandom, thread_rng};
 
 use self::ob_part_desc::{ObHashPartDesc, ObKeyPartDesc, ObPartDesc, ObRangePartDesc};
 use crate::{
-    client::{table_client::ServerRoster, ClientConfig},
+    client::{schema::TableSchema, ClientConfig},
     constant::*,
-    error::{CommonErrCode, Error::Common as CommonErr, Result},
-    rpc::protocol::{codes::ResultCodes, partition::ob_column::ObColumn},
+    error::{CommonErrCode, Error, Error::Common as CommonErr, Result},
+    rpc::protocol::{
+        codes::ResultCodes, partition::ob_column::ObColumn, payloads::ObTableConsistencyLevel,
+    },
     util as u,
     util::HandyRwLock,
 };
 
+pub mod cache;
 pub mod ob_part_constants;
 mod ob_part_desc;
-mod part_func_type;
+pub mod part_func_type;
 mod util;
 
+#[cfg(test)]
+pub(crate) use self::util::new_hash_table_entry;
+
 pub const OB_INVALID_ID: i64 = -1;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -51,6 +58,12 @@ pub struct ObServerAddr {
     ip: String,
     sql_port: i32,
     svr_port: i32,
+    #[serde(default)]
+    zone: String,
+    #[serde(default)]
+    idc: String,
+    #[serde(default)]
+    region: String,
     #[serde(skip)]
     priority: Arc<AtomicIsize>,
     #[serde(skip)]
@@ -87,6 +100,9 @@ impl ObServerAddr {
             ip: "".to_owned(),
             sql_port: 0,
             svr_port: 0,
+            zone: "".to_owned(),
+            idc: "".to_owned(),
+            region: "".to_owned(),
             priority: Arc::new(AtomicIsize::new(0)),
             grant_priority_times: Arc::new(AtomicUsize::new(0)),
         }
@@ -116,6 +132,26 @@ impl ObServerAddr {
         self.svr_port = port
     }
 
+    pub fn zone(&self) -> &str {
+        &self.zone
+    }
+
+    pub fn idc(&self) -> &str {
+        &self.idc
+    }
+
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Set the zone of the server and the idc/region the zone belongs to,
+    /// the idc and region are empty if they are not configured.
+    pub fn set_ldc(&mut self, zone: String, idc: Option<String>, region: Option<String>) {
+        self.zone = zone;
+        self.idc = idc.unwrap_or_default();
+        self.region = region.unwrap_or_default();
+    }
+
     pub fn grant_priority_times(&self) -> usize {
         self.grant_priority_times.load(Ordering::Acquire)
     }
@@ -144,7 +180,148 @@ impl ObServerAddr {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// The locality of a server relative to the client, the nearer the smaller.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ObServerLdcType {
+    SameIdc,
+    SameRegion,
+    Remote,
+}
+
+/// The logical data center of the client.
+///
+/// The region is resolved from the servers located in the idc of the client,
+/// and all the servers are remote if the idc of the client is not configured.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ObServerLdc {
+    idc: Option<String>,
+    region: Option<String>,
+}
+
+impl ObServerLdc {
+    pub fn new(idc: Option<String>) -> Self {
+        Self { idc, region: None }
+    }
+
+    pub fn idc(&self) -> Option<&str> {
+        self.idc.as_deref()
+    }
+
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// Resolve the region of the client by the servers in the same idc, the
+    /// region resolved before is kept if there is no such server.
+    pub fn resolve_region(&mut self, servers: &[ObServerAddr]) {
+        let idc = match &self.idc {
+            Some(idc) => idc,
+            None => return,
+        };
+        match servers
+            .iter()
+            .find(|addr| addr.idc().eq_ignore_ascii_case(idc) && !addr.region().is_empty())
+        {
+            Some(addr) => self.region = Some(addr.region().to_owned()),
+            None => warn!(
+                "ObServerLdc::resolve_region no server found in idc, idc:{}, region:{:?}",
+                idc, self.region
+            ),
+        }
+    }
+
+    pub fn ldc_type(&self, addr: &ObServerAddr) -> ObServerLdcType {
+        match (&self.idc, &self.region) {
+            (Some(idc), _) if addr.idc().eq_ignore_ascii_case(idc) => ObServerLdcType::SameIdc,
+            (_, Some(region)) if addr.region().eq_ignore_ascii_case(region) => {
+                ObServerLdcType::SameRegion
+            }
+            _ => ObServerLdcType::Remote,
+        }
+    }
+
+    /// Choose a random server among the nearest ones.
+    pub fn choose<'a>(&self, servers: &'a [ObServerAddr]) -> Option<&'a ObServerAddr> {
+        choose_nearest(servers.iter().collect(), |addr| self.ldc_type(addr))
+    }
+}
+
+/// Choose a random candidate among the ones with the minimum key.
+fn choose_nearest<'a, T, K: Ord>(candidates: Vec<&'a T>, key: impl Fn(&T) -> K) -> Option<&'a T> {
+    let nearest = candidates.iter().map(|c| key(c)).min()?;
+    let candidates: Vec<&T> = candidates
+        .into_iter()
+        .filter(|c| key(c) == nearest)
+        .collect();
+    candidates.choose(&mut thread_rng()).copied()
+}
+
+/// The version of the OceanBase cluster, e.g. `3.1.2` or `4.1.0.0`.
+///
+/// OceanBase 4.x routes the requests by the tablet and the log stream and
+/// provides different system views from the former versions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ObVersion {
+    major: u32,
+    minor: u32,
+    patch: u32,
+    build: u32,
+}
+
+impl ObVersion {
+    pub fn new(major: u32, minor: u32, patch: u32, build: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            build,
+        }
+    }
+
+    pub fn major(&self) -> u32 {
+        self.major
+    }
+
+    pub fn is_4x(&self) -> bool {
+        self.major >= 4
+    }
+}
+
+impl FromStr for ObVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut numbers = [0u32; 4];
+        let parts: Vec<&str> = s.trim().split('.').collect();
+        if parts.len() > numbers.len() {
+            return Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                format!("ObVersion::from_str invalid version:{}", s),
+            ));
+        }
+        for (i, part) in parts.into_iter().enumerate() {
+            numbers[i] = part.parse().map_err(|e| {
+                CommonErr(
+                    CommonErrCode::InvalidParam,
+                    format!("ObVersion::from_str invalid version:{}, err:{}", s, e),
+                )
+            })?;
+        }
+        Ok(Self::new(numbers[0], numbers[1], numbers[2], numbers[3]))
+    }
+}
+
+impl fmt::Display for ObVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major, self.minor, self.patch, self.build
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ObServerStatus {
     Active,
     Inactive,
@@ -179,14 +356,14 @@ impl ObPartitionLevel {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ObServerRole {
     InvalidRole,
     Leader,
     Follower,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ObServerInfo {
     stop_time: i64,
     status: ObServerStatus,
@@ -217,6 +394,8 @@ pub struct TableEntryKey {
 #[derive(Debug, Clone)]
 pub struct TableEntry {
     table_id: i64,
+    // the tablet of the non-partitioned table, only valid on OceanBase 4.x
+    tablet_id: i64,
     partition_num: i64,
     refresh_time_mills: Arc<AtomicUsize>,
     partition_info: Option<ObPartitionInfo>,
@@ -241,6 +420,10 @@ impl TableEntryKey {
         }
     }
 
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
     pub fn new_root_server_key(cluster_name: &str, tenant_name: &str) -> Self {
         Self::new(
             cluster_name,
@@ -259,6 +442,13 @@ pub struct ObPartitionInfo {
     part_columns: Vec<Box<dyn ObColumn>>,
     row_key_element: HashMap<String, i32>,
     part_name_id_map: HashMap<String, i64>,
+    // the tablet ids of the first level partitions, only on OceanBase 4.x
+    part_tablet_ids: Vec<i64>,
+    // the physical ids of the sub partitions to their tablet ids, only on
+    // OceanBase 4.x
+    sub_part_tablet_ids: HashMap<i64, i64>,
+    // the rows it's built from, which are persisted to rebuild it
+    rows: util::ObPartitionRows,
 }
 
 impl Default for ObPartitionInfo {
@@ -276,6 +466,9 @@ impl ObPartitionInfo {
             part_columns: Vec::new(),
             row_key_element: HashMap::<String, i32>::new(),
             part_name_id_map: HashMap::<String, i64>::new(),
+            part_tablet_ids: Vec::new(),
+            sub_part_tablet_ids: HashMap::new(),
+            rows: util::ObPartitionRows::default(),
         }
     }
 
@@ -302,6 +495,31 @@ impl ObPartitionInfo {
         &self.sub_part_desc
     }
 
+    /// The tablet of the partition on OceanBase 4.x, the first level
+    /// partitions are numbered by their order, and the sub partitions are
+    /// identified by the physical ids like `(part << 32) | sub_part | MASK`.
+    pub fn part_tablet_id(&self, part_id: i64) -> Option<i64> {
+        if self.level == ObPartitionLevel::Two {
+            return self.sub_part_tablet_ids.get(&part_id).copied();
+        }
+        if part_id < 0 {
+            return None;
+        }
+        self.part_tablet_ids.get(part_id as usize).copied()
+    }
+
+    /// The ids of the partitions which have tablets, the sub partitions for
+    /// the two level partitioned table.
+    pub fn tablet_part_ids(&self) -> Vec<i64> {
+        if self.level == ObPartitionLevel::Two {
+            let mut part_ids: Vec<i64> = self.sub_part_tablet_ids.keys().copied().collect();
+            part_ids.sort_unstable();
+            part_ids
+        } else {
+            (0..self.part_tablet_ids.len() as i64).collect()
+        }
+    }
+
     pub fn prepare(&mut self) -> Result<()> {
         match self.level {
             ObPartitionLevel::One => match &mut self.first_part_desc {
@@ -354,7 +572,7 @@ impl ObPartitionInfo {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TableLocation {
     replica_locations: Vec<ReplicaLocation>,
 }
@@ -365,24 +583,88 @@ impl TableLocation {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ObPartitionEntry {
     parititon_location: HashMap<i64 /* partition number */, ObPartitionLocation>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ObPartitionLocation {
     leader: Option<ReplicaLocation>,
     followers: Vec<ReplicaLocation>,
+    // the tablet and log stream of the partition, only valid on OceanBase 4.x.
+    // The requests only carry the tablet, the server resolves the log stream
+    // of it, and the log stream is only needed by the LS batch requests.
+    tablet_id: i64,
+    ls_id: i64,
 }
 
 impl ObPartitionLocation {
+    pub fn tablet_id(&self) -> i64 {
+        self.tablet_id
+    }
+
+    pub fn ls_id(&self) -> i64 {
+        self.ls_id
+    }
+
+    /// The id to route the requests of the partition to, which is the tablet
+    /// id on OceanBase 4.x and the partition id otherwise.
+    pub fn route_id(&self, part_id: i64) -> i64 {
+        if self.tablet_id != OB_INVALID_ID {
+            self.tablet_id
+        } else {
+            part_id
+        }
+    }
+
     pub fn leader(&self) -> &Option<ReplicaLocation> {
         &self.leader
     }
+
+    pub fn followers(&self) -> &[ReplicaLocation] {
+        &self.followers
+    }
+
+    /// All the replicas of the partition, the leader comes first.
+    pub fn replicas(&self) -> impl Iterator<Item = &ReplicaLocation> {
+        self.leader.iter().chain(self.followers.iter())
+    }
+
+    /// Get the replica to serve the requests of the consistency level, the
+    /// nearest replica to the client is chosen for the weak read and the
+    /// followers are preferred to the leader in the same locality.
+    pub fn get_replica(
+        &self,
+        consistency_level: ObTableConsistencyLevel,
+        ldc: &ObServerLdc,
+    ) -> Option<&ReplicaLocation> {
+        if consistency_level == ObTableConsistencyLevel::Eventual {
+            let replicas: Vec<&ReplicaLocation> = self
+                .followers
+                .iter()
+                .chain(self.leader.iter())
+                .filter(|replica| replica.info().is_active())
+                .collect();
+            let replica = choose_nearest(replicas, |replica| {
+                (
+                    ldc.ldc_type(replica.addr()),
+                    replica.role == ObServerRole::Leader,
+                )
+            });
+            if replica.is_some() {
+                return replica;
+            }
+        }
+        self.leader.as_ref()
+    }
 }
 
 impl ObPartitionEntry {
+    pub fn partition_locations(&self) -> &HashMap<i64, ObPartitionLocation> {
+        &self.parititon_location
+    }
+
     pub fn get_partition_location_with_part_id(
         &self,
         part_id: i64,
@@ -391,7 +673,7 @@ impl ObPartitionEntry {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ReplicaLocation {
     addr: ObServerAddr,
     info: ObServerInfo,
@@ -438,6 +720,12 @@ impl TableEntry {
         }
     }
 
+    /// The row key columns to their positions, which are discovered from the
+    /// primary key of the partitioned table unless set.
+    pub fn row_key_element(&self) -> &HashMap<String, i32> {
+        &self.row_key_element
+    }
+
     pub fn is_partition_table(&self) -> bool {
         self.partition_num > 1
     }
@@ -446,6 +734,10 @@ impl TableEntry {
         &self.partition_entry
     }
 
+    pub fn set_partition_entry(&mut self, partition_entry: Option<ObPartitionEntry>) {
+        self.partition_entry = partition_entry;
+    }
+
     pub fn partition_info(&self) -> &Option<ObPartitionInfo> {
         &self.partition_info
     }
@@ -454,6 +746,10 @@ impl TableEntry {
         &self.table_location
     }
 
+    pub fn tablet_id(&self) -> i64 {
+        self.tablet_id
+    }
+
     pub fn refresh_time_mills(&self) -> i64 {
         self.refresh_time_mills.load(Ordering::Acquire) as i64
     }
@@ -480,6 +776,15 @@ impl TableEntry {
         }
     }
 
+    /// The id to route the requests of the partition to, see
+    /// [`ObPartitionLocation::route_id`].
+    pub fn route_id(&self, part_id: i64) -> i64 {
+        match self.get_partition_location_with_part_id(part_id) {
+            Some(location) => location.route_id(part_id),
+            None => part_id,
+        }
+    }
+
     pub fn prepare(&mut self) -> Result<()> {
         if self.is_partition_table() {
             return match &mut self.partition_info {
@@ -501,6 +806,7 @@ pub struct ObTableLocation {
     config: ClientConfig,
     /// {ServerAddr} -> {User/DB} -> {Pool}
     mysql_pools: RwLock<HashMap<ObServerAddr, HashMap<String, Arc<my::Pool>>>>,
+    ob_version: RwLock<ObVersion>,
 }
 
 impl Default for ObTableLocation {
@@ -514,7 +820,104 @@ impl ObTableLocation {
         ObTableLocation {
             config,
             mysql_pools: RwLock::new(HashMap::new()),
+            ob_version: RwLock::new(ObVersion::default()),
+        }
+    }
+
+    pub fn ob_version(&self) -> ObVersion {
+        *self.ob_version.rl()
+    }
+
+    /// Detect the version of the cluster by a random server in the rs list,
+    /// the metadata is fetched from the system views of the version detected.
+    pub fn detect_ob_version(
+        &self,
+        rs_list: &[ObServerAddr],
+        connect_timeout: Duration,
+        sock_timeout: Duration,
+    ) -> Result<ObVersion> {
+        if rs_list.is_empty() {
+            return Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                "Empty rs list".to_owned(),
+            ));
         }
+
+        let mut rng = thread_rng();
+        let random_server = rs_list.choose(&mut rng).unwrap();
+
+        self.detect_ob_version_with_user(
+            random_server,
+            &self.config.sys_user_name,
+            &self.config.sys_password,
+            "oceanbase",
+            connect_timeout,
+            sock_timeout,
+        )
+    }
+
+    /// Detect the version of the cluster by the server or the ODP with the
+    /// user, which needs no privileges on the system views.
+    pub fn detect_ob_version_with_user(
+        &self,
+        server_addr: &ObServerAddr,
+        username: &str,
+        password: &str,
+        database: &str,
+        connect_timeout: Duration,
+        sock_timeout: Duration,
+    ) -> Result<ObVersion> {
+        let pool = self.get_or_create_mysql_pool(
+            username,
+            password,
+            database,
+            server_addr,
+            Some(connect_timeout),
+            Some(sock_timeout),
+        )?;
+        let mut conn = pool.try_get_conn(u::duration_to_millis(&connect_timeout) as u32)?;
+
+        let sql = "SELECT /*+READ_CONSISTENCY(WEAK)*/ OB_VERSION() AS CLUSTER_VERSION";
+        let mut version = None;
+        for result in conn.query(sql)? {
+            let row = result.map_err(|e| {
+                error!(
+                    "ObTableLocation::detect_ob_version: fail to execute sql, addr:{:?}, err:{}",
+                    server_addr, e
+                );
+                e
+            })?;
+            let cluster_version: String = match my::from_row_opt(row) {
+                Ok(v) => v,
+                Err(e) => {
+                    error!("ObTableLocation::detect_ob_version: fail to do mysql row conversion, err:{}", e);
+                    return Err(CommonErr(
+                        CommonErrCode::ConvertFailed,
+                        format!("mysql row conversion err:{}", e),
+                    ));
+                }
+            };
+            version = Some(ObVersion::from_str(&cluster_version)?);
+        }
+        let version = match version {
+            Some(v) => v,
+            None => {
+                return Err(CommonErr(
+                    CommonErrCode::NotFound,
+                    format!(
+                        "ObTableLocation::detect_ob_version version not found, addr:{:?}",
+                        server_addr
+                    ),
+                ))
+            }
+        };
+
+        info!(
+            "ObTableLocation::detect_ob_version detect version:{}, addr:{:?}",
+            version, server_addr
+        );
+        *self.ob_version.wl() = version;
+        Ok(version)
     }
 
     fn get_pool_from_cache(
@@ -659,166 +1062,86 @@ impl ObTableLocation {
         Ok(())
     }
 
-    pub fn load_table_entry_randomly(
-        &self,
-        rs_list: &[ObServerAddr],
-        key: &TableEntryKey,
-        connect_timeout: Duration,
-        sock_timeout: Duration,
-    ) -> Result<TableEntry> {
-        if rs_list.is_empty() {
-            return Err(CommonErr(
-                CommonErrCode::InvalidParam,
-                "Empty rs list".to_owned(),
-            ));
-        }
-
-        let mut rng = thread_rng();
-        let random_server = rs_list.choose(&mut rng).unwrap();
-
-        self.get_table_entry_from_remote(&random_server, key, connect_timeout, sock_timeout)
-    }
-
-    /// refresh table entry with callback and priority
-    fn refresh_table_entry_with_priority(
+    fn get_sys_conn(
         &self,
-        server_roster: &ServerRoster,
-        key: &TableEntryKey,
+        server_addr: &ObServerAddr,
         connect_timeout: Duration,
         sock_timeout: Duration,
-        priority_timeout: Duration,
-        callback: impl FnOnce(
-            &ObTableLocation,
-            &ObServerAddr,
-            &TableEntryKey,
-            Duration,
-            Duration,
-        ) -> Result<TableEntry>,
-    ) -> Result<TableEntry> {
-        let mut rs_list: Vec<ObServerAddr> = vec![];
-        let grade_time = u::current_time_millis() as usize;
-
-        let max_priority = server_roster.max_priority();
-
-        for addr in server_roster.get_members().iter() {
-            if addr.priority() == max_priority
-                || grade_time - addr.grant_priority_times()
-                    > u::duration_to_millis(&priority_timeout) as usize
-            {
-                rs_list.push(addr.to_owned());
-            }
-        }
-
-        if rs_list.is_empty() {
-            return Err(CommonErr(
-                CommonErrCode::InvalidParam,
-                "Empty rs list".to_owned(),
-            ));
-        }
-
-        let mut rng = thread_rng();
-        let addr = rs_list.choose(&mut rng).unwrap();
-
-        match callback(self, &addr, key, connect_timeout, sock_timeout) {
-            Ok(table_entry) => {
-                if addr.priority() != 0 {
-                    let grant_priority_times = u::current_time_millis() as usize;
-                    addr.set_priority(0);
-                    addr.set_grant_priority_times(grant_priority_times);
-                    server_roster.upgrade_max_priority(0);
-                }
+    ) -> Result<my::PooledConn> {
+        let pool = self.get_or_create_mysql_pool(
+            &self.config.sys_user_name,
+            &self.config.sys_password,
+            "oceanbase",
+            server_addr,
+            Some(connect_timeout),
+            Some(sock_timeout),
+        )?;
 
-                Ok(table_entry)
-            }
-            Err(e) => {
-                let grant_priority_times = u::current_time_millis() as usize;
-                addr.set_grant_priority_times(grant_priority_times);
-                server_roster.downgrade_max_priority(addr.decrement_priority_and_get(1));
-                Err(e)
-            }
-        }
+        Ok(pool.try_get_conn(u::duration_to_millis(&connect_timeout) as u32)?)
     }
 
-    /// Load table entry location from remote with priority
-    pub fn load_table_location_with_priority(
+    /// Load the partition info of the partitioned table from the server.
+    pub fn load_partition_info(
         &self,
-        server_roster: &ServerRoster,
+        server_addr: &ObServerAddr,
         key: &TableEntryKey,
         table_entry: &TableEntry,
         connect_timeout: Duration,
         sock_timeout: Duration,
-        priority_timeout: Duration,
-    ) -> Result<TableEntry> {
-        let callback = |location: &ObTableLocation,
-                        server_addr: &ObServerAddr,
-                        key: &TableEntryKey,
-                        connect_timeout: Duration,
-                        sock_timeout: Duration|
-         -> Result<TableEntry> {
-            location.load_table_location(
-                server_addr,
-                key,
-                table_entry,
-                connect_timeout,
-                sock_timeout,
+    ) -> Result<ObPartitionInfo> {
+        let mut conn = self.get_sys_conn(server_addr, connect_timeout, sock_timeout)?;
+        let mut table_entry = table_entry.clone();
+        self.fetch_partition_info(&mut conn, key, &mut table_entry)?;
+        table_entry.partition_info.ok_or_else(|| {
+            CommonErr(
+                CommonErrCode::PartitionError,
+                format!(
+                    "ObTableLocation::load_partition_info not a partition table, key:{:?}",
+                    key
+                ),
             )
-        };
-        self.refresh_table_entry_with_priority(
-            server_roster,
-            key,
-            connect_timeout,
-            sock_timeout,
-            priority_timeout,
-            callback,
-        )
+        })
     }
 
-    /// Load table entry from remote with priority
-    pub fn load_table_entry_with_priority(
+    /// Load the locations of the partitions of the table from the server.
+    pub fn load_partition_location(
         &self,
-        server_roster: &ServerRoster,
+        server_addr: &ObServerAddr,
         key: &TableEntryKey,
+        table_entry: &TableEntry,
         connect_timeout: Duration,
         sock_timeout: Duration,
-        priority_timeout: Duration,
-    ) -> Result<TableEntry> {
-        self.refresh_table_entry_with_priority(
-            server_roster,
-            key,
-            connect_timeout,
-            sock_timeout,
-            priority_timeout,
-            ObTableLocation::get_table_entry_from_remote,
-        )
+    ) -> Result<ObPartitionEntry> {
+        let mut conn = self.get_sys_conn(server_addr, connect_timeout, sock_timeout)?;
+        self.get_table_location_from_remote(&mut conn, key, table_entry)
     }
 
-    fn load_table_location(
+    /// Load the schema of the table from the server.
+    pub fn load_table_schema(
         &self,
         server_addr: &ObServerAddr,
         key: &TableEntryKey,
         table_entry: &TableEntry,
         connect_timeout: Duration,
         sock_timeout: Duration,
-    ) -> Result<TableEntry> {
-        let pool = self.get_or_create_mysql_pool(
-            &self.config.sys_user_name,
-            &self.config.sys_password,
-            "oceanbase",
-            server_addr,
-            Some(connect_timeout),
-            Some(sock_timeout),
-        )?;
-
-        let mut conn = pool.try_get_conn(u::duration_to_millis(&connect_timeout) as u32)?;
-
-        let part_entry = self.get_table_location_from_remote(&mut conn, &key, table_entry)?;
-        //Clone a new table entry to return.
-        let mut table_entry = table_entry.clone();
-        //Update partiton entry and refresh_time
-        table_entry.partition_entry = Some(part_entry);
-        table_entry.set_refresh_time_mills(u::current_time_millis() as i64);
-
-        Ok(table_entry)
+    ) -> Result<TableSchema> {
+        let mut conn = self.get_sys_conn(server_addr, connect_timeout, sock_timeout)?;
+        let column_rows =
+            util::LocationUtil::fetch_column_rows(&mut conn, table_entry, key, self.ob_version())?;
+        if column_rows.is_empty() {
+            return Err(CommonErr(
+                CommonErrCode::NotFound,
+                format!(
+                    "ObTableLocation::load_table_schema columns not found, key:{:?}",
+                    key
+                ),
+            ));
+        }
+        Ok(util::LocationUtil::build_table_schema(
+            &key.table_name,
+            table_entry,
+            &column_rows,
+        ))
     }
 
     pub fn get_table_entry_from_remote(
@@ -828,34 +1151,35 @@ impl ObTableLocation {
         connect_timeout: Duration,
         sock_timeout: Duration,
     ) -> Result<TableEntry> {
-        let pool = self.get_or_create_mysql_pool(
-            &self.config.sys_user_name,
-            &self.config.sys_password,
-            "oceanbase",
-            server_addr,
-            Some(connect_timeout),
-            Some(sock_timeout),
-        )?;
-
         let mut table_id = OB_INVALID_ID;
+        let mut tablet_id = OB_INVALID_ID;
         let mut partition_num = OB_INVALID_ID;
-        let mut conn = pool.try_get_conn(u::duration_to_millis(&connect_timeout) as u32)?;
+        let mut conn = self.get_sys_conn(server_addr, connect_timeout, sock_timeout)?;
+        // OceanBase 4.x locates the tablets instead of the partitions
+        let is_4x = self.ob_version().is_4x();
+        let id_column = if is_4x { "tablet_id" } else { "partition_id" };
         let sql = match key.table_name.as_ref() {
-        ALL_DUMMY_TABLE => format!("SELECT /*+READ_CONSISTENCY(WEAK)*/ A.partition_id as partition_id, A.svr_ip as svr_ip, A.sql_port as sql_port,
+        ALL_DUMMY_TABLE => format!("SELECT /*+READ_CONSISTENCY(WEAK)*/ A.{id} as {id}, A.svr_ip as svr_ip, A.sql_port as sql_port,
                                     A.table_id as table_id, A.role as role, A.part_num as part_num, B.svr_port as svr_port,
-                                    B.status as status, B.stop_time as stop_time FROM oceanbase.__all_virtual_proxy_schema A inner join oceanbase.__all_server
-                                    B on A.svr_ip = B.svr_ip and A.sql_port = B.inner_port WHERE tenant_name = '{}' and database_name='{}'
-                                    and table_name ='{}'",
+                                    B.status as status, B.stop_time as stop_time, B.zone as zone, C.info as idc, D.info as region
+                                    FROM oceanbase.__all_virtual_proxy_schema A inner join oceanbase.__all_server B on A.svr_ip = B.svr_ip and A.sql_port = B.inner_port
+                                    left join oceanbase.__all_zone C on B.zone = C.zone and C.name = 'idc'
+                                    left join oceanbase.__all_zone D on B.zone = D.zone and D.name = 'region'
+                                    WHERE tenant_name = '{}' and database_name='{}' and table_name ='{}'",
                                    &key.tenant_name,
                                    &key.database_name,
-                                   &key.table_name),
-        _ => format!("SELECT /*+READ_CONSISTENCY(WEAK)*/ A.partition_id as partition_id, A.svr_ip as svr_ip, A.sql_port as sql_port, A.table_id as table_id,
+                                   &key.table_name,
+                                   id = id_column),
+        _ => format!("SELECT /*+READ_CONSISTENCY(WEAK)*/ A.{id} as {id}, A.svr_ip as svr_ip, A.sql_port as sql_port, A.table_id as table_id,
                       A.role as role, A.part_num as part_num, B.svr_port as svr_port, B.status as status, B.stop_time as
-                      stop_time FROM oceanbase.__all_virtual_proxy_schema A inner join oceanbase.__all_server B on A.svr_ip = B.svr_ip and
-                      A.sql_port = B.inner_port WHERE tenant_name = '{}' and database_name='{}' and table_name = '{}' and partition_id = 0",
+                      stop_time, B.zone as zone, C.info as idc, D.info as region FROM oceanbase.__all_virtual_proxy_schema A inner join oceanbase.__all_server B
+                      on A.svr_ip = B.svr_ip and A.sql_port = B.inner_port left join oceanbase.__all_zone C on B.zone = C.zone and C.name = 'idc'
+                      left join oceanbase.__all_zone D on B.zone = D.zone and D.name = 'region'
+                      WHERE tenant_name = '{}' and database_name='{}' and table_name = '{}' and {id} = 0",
                      &key.tenant_name,
                      &key.database_name,
-                     &key.table_name),
+                     &key.table_name,
+                     id = id_column),
         };
         let mut replica_locations = Vec::new();
         for result in conn.query(sql)? {
@@ -866,19 +1190,32 @@ impl ObTableLocation {
                 );
                 e
             })?;
-            let (id, svr_ip, sql_port, tbl_id, role, part_num, svr_port, status, stop_time) =
-                match my::from_row_opt(row) {
-                    Ok(tuple) => tuple,
-                    Err(e) => {
-                        error!("ObTableLocation::get_table_entry_from_remote: fail to do mysql row conversion, err:{}", e);
-                        return Err(CommonErr(
-                            CommonErrCode::ConvertFailed,
-                            format!("mysql row conversion err:{}", e),
-                        ));
-                    }
-                };
-            // just for id type infer
-            let _: i64 = id;
+            let (
+                id,
+                svr_ip,
+                sql_port,
+                tbl_id,
+                role,
+                part_num,
+                svr_port,
+                status,
+                stop_time,
+                zone,
+                idc,
+                region,
+            ) = match my::from_row_opt(row) {
+                Ok(tuple) => tuple,
+                Err(e) => {
+                    error!("ObTableLocation::get_table_entry_from_remote: fail to do mysql row conversion, err:{}", e);
+                    return Err(CommonErr(
+                        CommonErrCode::ConvertFailed,
+                        format!("mysql row conversion err:{}", e),
+                    ));
+                }
+            };
+            if is_4x {
+                tablet_id = id;
+            }
             table_id = tbl_id;
             partition_num = part_num;
             let role: ObServerRole = ObServerRole::from_int(role);
@@ -889,6 +1226,7 @@ impl ObTableLocation {
             observer_addr.address(svr_ip);
             observer_addr.set_sql_port(sql_port);
             observer_addr.set_svr_port(svr_port);
+            observer_addr.set_ldc(zone, idc, region);
 
             let observer_info = ObServerInfo { stop_time, status };
             if !observer_info.is_active() {
@@ -916,6 +1254,7 @@ impl ObTableLocation {
         let table_location = TableLocation { replica_locations };
         let mut table_entry = TableEntry {
             table_id,
+            tablet_id,
             partition_num,
             refresh_time_mills: Arc::new(AtomicUsize::new(0)),
             partition_info: None,
@@ -924,59 +1263,45 @@ impl ObTableLocation {
             row_key_element: HashMap::new(),
         };
 
+        // the partition info is fetched before the location because the
+        // partitions are located by their tablets on OceanBase 4.x
+        if table_entry.is_partition_table() {
+            self.fetch_partition_info(&mut conn, key, &mut table_entry)?;
+        }
+
         let part_entry = self.get_table_location_from_remote(&mut conn, &key, &table_entry)?;
         table_entry.partition_entry = Some(part_entry);
         table_entry.set_refresh_time_mills(u::current_time_millis() as i64);
 
-        if table_entry.is_partition_table() {
-            // fetch partition info
-            match util::LocationUtil::fetch_partition_info(&mut conn, &table_entry) {
-                Ok(v) => table_entry.partition_info = Some(v),
+        Ok(table_entry)
+    }
+
+    fn fetch_partition_info(
+        &self,
+        conn: &mut my::PooledConn,
+        key: &TableEntryKey,
+        table_entry: &mut TableEntry,
+    ) -> Result<()> {
+        let ob_version = self.ob_version();
+        let rows =
+            match util::LocationUtil::fetch_partition_rows(conn, table_entry, key, ob_version) {
+                Ok(v) => v,
                 Err(e) => {
-                    table_entry.partition_info = None;
                     error!(
-                        "Location::get_table_entry_from_remote fetch_partition_info error:{:?}",
-                        e
+                        "ObTableLocation::fetch_partition_info fail to fetch partition info, \
+                     key:{:?}, err:{:?}",
+                        key, e
                     );
                     return Err(CommonErr(
                         CommonErrCode::PartitionError,
                         format!(
-                            "location::get_table_entry_from_remote fetch_partition_info error:{:?}",
-                            e
-                        ),
+                        "ObTableLocation::fetch_partition_info fail to fetch partition info:{:?}",
+                        e
+                    ),
                     ));
                 }
-            }
-            if let Some(v) = table_entry.partition_info.clone() {
-                // fetch first range part
-                if let Some(first_part_desc) = &v.first_part_desc {
-                    let ob_part_func_type = first_part_desc.get_part_func_type();
-                    if ob_part_func_type.is_range_part() || ob_part_func_type.is_list_part() {
-                        util::LocationUtil::fetch_first_part(
-                            &mut conn,
-                            &mut table_entry,
-                            ob_part_func_type,
-                        )?;
-                    }
-                }
-                // fetch sub range part
-                if let Some(sub_part_desc) = &v.sub_part_desc {
-                    let ob_part_func_type = sub_part_desc.get_part_func_type();
-                    if ob_part_func_type.is_range_part() || ob_part_func_type.is_list_part() {
-                        util::LocationUtil::fetch_sub_part(
-                            &mut conn,
-                            &mut table_entry,
-                            ob_part_func_type,
-                        )?;
-                    }
-                }
-            }
-            if let Some(v) = &mut table_entry.partition_info {
-                v.part_name_id_map = util::LocationUtil::build_part_name_id_map(v);
-            }
-        }
-
-        Ok(table_entry)
+            };
+        util::LocationUtil::build_partition_info_with_rows(table_entry, rows)
     }
 
     pub fn get_table_location_from_remote(
@@ -986,22 +1311,80 @@ impl ObTableLocation {
         table_entry: &TableEntry,
     ) -> Result<ObPartitionEntry> {
         let partition_num = table_entry.partition_num;
-        let mut part_str = String::with_capacity((partition_num * 2) as usize);
-        for i in 0..partition_num {
-            if i > 0 {
+        // OceanBase 4.x locates the tablets of the partitions and the log
+        // streams they belong to
+        let is_4x = self.ob_version().is_4x();
+        // the sub partitions are located on OceanBase 4.x, whose ids are not
+        // numbered from 0
+        let part_ids: Vec<i64> = match &table_entry.partition_info {
+            Some(info)
+                if is_4x
+                    && table_entry.is_partition_table()
+                    && info.level() == ObPartitionLevel::Two =>
+            {
+                info.tablet_part_ids()
+            }
+            _ => (0..partition_num).collect(),
+        };
+        let mut tablet_part_ids = HashMap::with_capacity(part_ids.len());
+        let mut part_str = String::with_capacity(part_ids.len() * 2);
+        for (n, &i) in part_ids.iter().enumerate() {
+            if n > 0 {
                 part_str.push(',');
             }
-            part_str.push_str(&format!("{}", i));
+            let id = if is_4x {
+                let tablet_id = match &table_entry.partition_info {
+                    Some(info) if table_entry.is_partition_table() => info.part_tablet_id(i),
+                    _ => Some(table_entry.tablet_id),
+                };
+                match tablet_id {
+                    Some(tablet_id) => {
+                        tablet_part_ids.insert(tablet_id, i);
+                        tablet_id
+                    }
+                    None => {
+                        error!("Location::get_table_location_from_remote: tablet of partition num={} is not exists, table={:?}",
+                               i, table_entry);
+                        return Err(CommonErr(
+                            CommonErrCode::PartitionError,
+                            format!("Location::get_table_location_from_remote: tablet of partition num={} is not exists, table={:?}",
+                                    i, table_entry),
+                        ));
+                    }
+                }
+            } else {
+                i
+            };
+            part_str.push_str(&format!("{}", id));
         }
 
-        let sql = format!("SELECT /*+READ_CONSISTENCY(WEAK)*/ A.partition_id as partition_id, A.svr_ip as svr_ip, A.sql_port as sql_port,
-                       A.role as role, B.svr_port as svr_port, B.status as status, B.stop_time as stop_time
+        let sql = if is_4x {
+            format!("SELECT /*+READ_CONSISTENCY(WEAK)*/ A.tablet_id as tablet_id, A.svr_ip as svr_ip, A.sql_port as sql_port,
+                       A.role as role, B.svr_port as svr_port, B.status as status, B.stop_time as stop_time, B.zone as zone, C.info as idc, D.info as region,
+                       F.ls_id as ls_id
                        FROM oceanbase.__all_virtual_proxy_schema A inner join oceanbase.__all_server B on A.svr_ip = B.svr_ip and A.sql_port = B.inner_port
+                       left join oceanbase.__all_zone C on B.zone = C.zone and C.name = 'idc'
+                       left join oceanbase.__all_zone D on B.zone = D.zone and D.name = 'region'
+                       left join oceanbase.DBA_OB_TENANTS E on E.tenant_name = A.tenant_name
+                       left join oceanbase.CDB_OB_TABLET_TO_LS F on F.tenant_id = E.tenant_id and F.tablet_id = A.tablet_id
+                       WHERE A.tenant_name = '{}' and A.database_name='{}' and A.table_name = '{}' and A.tablet_id in ({})",
+                    &key.tenant_name,
+                    &key.database_name,
+                    &key.table_name,
+                    &part_str)
+        } else {
+            format!("SELECT /*+READ_CONSISTENCY(WEAK)*/ A.partition_id as partition_id, A.svr_ip as svr_ip, A.sql_port as sql_port,
+                       A.role as role, B.svr_port as svr_port, B.status as status, B.stop_time as stop_time, B.zone as zone, C.info as idc, D.info as region,
+                       NULL as ls_id
+                       FROM oceanbase.__all_virtual_proxy_schema A inner join oceanbase.__all_server B on A.svr_ip = B.svr_ip and A.sql_port = B.inner_port
+                       left join oceanbase.__all_zone C on B.zone = C.zone and C.name = 'idc'
+                       left join oceanbase.__all_zone D on B.zone = D.zone and D.name = 'region'
                        WHERE tenant_name = '{}' and database_name='{}' and table_name = '{}' and partition_id in ({})",
-                      &key.tenant_name,
-                      &key.database_name,
-                      &key.table_name,
-                      &part_str);
+                    &key.tenant_name,
+                    &key.database_name,
+                    &key.table_name,
+                    &part_str)
+        };
 
         let mut parititon_location = HashMap::new();
 
@@ -1013,7 +1396,7 @@ impl ObTableLocation {
                 );
                 e
             })?;
-            let (part_id, svr_ip, sql_port, role, svr_port, status, stop_time) =
+            let (id, svr_ip, sql_port, role, svr_port, status, stop_time, zone, idc, region, ls_id) =
                 match my::from_row_opt(row) {
                     Ok(tuple) => tuple,
                     Err(e) => {
@@ -1025,7 +1408,15 @@ impl ObTableLocation {
                     }
                 };
 
-            let partition_id = part_id;
+            let (partition_id, tablet_id) = if is_4x {
+                match tablet_part_ids.get(&id) {
+                    Some(part_id) => (*part_id, id),
+                    None => continue,
+                }
+            } else {
+                (id, OB_INVALID_ID)
+            };
+            let ls_id: Option<i64> = ls_id;
             let role = ObServerRole::from_int(role);
             let status = ObServerStatus::from_string(status);
 
@@ -1033,6 +1424,7 @@ impl ObTableLocation {
             observer_addr.address(svr_ip);
             observer_addr.set_sql_port(sql_port);
             observer_addr.set_svr_port(svr_port);
+            observer_addr.set_ldc(zone, idc, region);
 
             let observer_info = ObServerInfo { stop_time, status };
             if !observer_info.is_active() {
@@ -1056,6 +1448,8 @@ impl ObTableLocation {
                     .or_insert(ObPartitionLocation {
                         leader: None,
                         followers: vec![],
+                        tablet_id,
+                        ls_id: ls_id.unwrap_or(OB_INVALID_ID),
                     });
 
             match role {
@@ -1066,7 +1460,7 @@ impl ObTableLocation {
         }
 
         // Check partition info.
-        for part_id in 0..table_entry.partition_num {
+        for part_id in part_ids {
             let location = parititon_location.get(&part_id);
 
             if location.is_none() {
@@ -1120,6 +1514,9 @@ mod test {
             ip: TEST_OB_SERVER_IP.to_string(),
             sql_port: 2881,
             svr_port: 2882,
+            zone: "".to_owned(),
+            idc: "".to_owned(),
+            region: "".to_owned(),
             priority: Arc::new(AtomicIsize::new(0)),
             grant_priority_times: Arc::new(AtomicUsize::new(0)),
         };
@@ -1150,6 +1547,9 @@ mod test {
             ip: TEST_OB_SERVER_IP.to_string(),
             sql_port: 2881,
             svr_port: 2882,
+            zone: "".to_owned(),
+            idc: "".to_owned(),
+            region: "".to_owned(),
             priority: Arc::new(AtomicIsize::new(0)),
             grant_priority_times: Arc::new(AtomicUsize::new(0)),
         };
@@ -1164,8 +1564,8 @@ mod test {
         let location = ObTableLocation::new(ClientConfig::default());
 
         let _result = location
-            .load_table_entry_randomly(
-                &vec![addr],
+            .get_table_entry_from_remote(
+                &addr,
                 &key,
                 Duration::from_secs(10),
                 Duration::from_secs(10),
@@ -1205,4 +1605,144 @@ mod test {
         assert!(v0 < v1);
         assert!(v0 > v2);
     }
+
+    fn new_replica(ip: &str, role: ObServerRole, status: ObServerStatus) -> ReplicaLocation {
+        let mut addr = ObServerAddr::new();
+        addr.address(format!("{}:2882", ip));
+        ReplicaLocation {
+            addr,
+            info: ObServerInfo {
+                stop_time: 0,
+                status,
+            },
+            role,
+        }
+    }
+
+    #[test]
+    fn test_get_replica() {
+        let ldc = ObServerLdc::default();
+        let leader = new_replica("127.0.0.1", ObServerRole::Leader, ObServerStatus::Active);
+        let mut location = ObPartitionLocation {
+            leader: Some(leader.clone()),
+            followers: vec![new_replica(
+                "127.0.0.2",
+                ObServerRole::Follower,
+                ObServerStatus::Inactive,
+            )],
+            tablet_id: OB_INVALID_ID,
+            ls_id: OB_INVALID_ID,
+        };
+
+        // no active follower
+        for level in [
+            ObTableConsistencyLevel::Strong,
+            ObTableConsistencyLevel::Eventual,
+        ] {
+            assert_eq!(Some(&leader), location.get_replica(level, &ldc));
+        }
+
+        let follower = new_replica("127.0.0.3", ObServerRole::Follower, ObServerStatus::Active);
+        location.followers.push(follower.clone());
+        assert_eq!(
+            Some(&leader),
+            location.get_replica(ObTableConsistencyLevel::Strong, &ldc)
+        );
+        assert_eq!(
+            Some(&follower),
+            location.get_replica(ObTableConsistencyLevel::Eventual, &ldc)
+        );
+    }
+
+    fn new_ldc_replica(ip: &str, role: ObServerRole, idc: &str, region: &str) -> ReplicaLocation {
+        let mut replica = new_replica(ip, role, ObServerStatus::Active);
+        replica.addr.set_ldc(
+            format!("zone_{}", idc),
+            Some(idc.to_owned()),
+            Some(region.to_owned()),
+        );
+        replica
+    }
+
+    #[test]
+    fn test_get_replica_with_ldc() {
+        let leader = new_ldc_replica("127.0.0.1", ObServerRole::Leader, "idc1", "region1");
+        let same_region = new_ldc_replica("127.0.0.2", ObServerRole::Follower, "idc2", "region1");
+        let remote = new_ldc_replica("127.0.0.3", ObServerRole::Follower, "idc3", "region2");
+        let mut location = ObPartitionLocation {
+            leader: Some(leader.clone()),
+            followers: vec![same_region.clone(), remote.clone()],
+            tablet_id: OB_INVALID_ID,
+            ls_id: OB_INVALID_ID,
+        };
+        let servers: Vec<ObServerAddr> = vec![
+            leader.addr().clone(),
+            same_region.addr().clone(),
+            remote.addr().clone(),
+        ];
+
+        let mut ldc = ObServerLdc::new(Some("idc2".to_owned()));
+        ldc.resolve_region(&servers);
+        assert_eq!(Some("region1"), ldc.region());
+        assert_eq!(ObServerLdcType::SameIdc, ldc.ldc_type(same_region.addr()));
+        assert_eq!(ObServerLdcType::SameRegion, ldc.ldc_type(leader.addr()));
+        assert_eq!(ObServerLdcType::Remote, ldc.ldc_type(remote.addr()));
+        assert_eq!(Some(same_region.addr()), ldc.choose(&servers));
+
+        assert_eq!(
+            Some(&same_region),
+            location.get_replica(ObTableConsistencyLevel::Eventual, &ldc)
+        );
+        assert_eq!(
+            Some(&leader),
+            location.get_replica(ObTableConsistencyLevel::Strong, &ldc)
+        );
+
+        // fall back to the same region, then remote
+        location.followers.retain(|replica| replica != &same_region);
+        assert_eq!(
+            Some(&leader),
+            location.get_replica(ObTableConsistencyLevel::Eventual, &ldc)
+        );
+        let mut ldc = ObServerLdc::new(Some("idc4".to_owned()));
+        ldc.resolve_region(&servers);
+        assert_eq!(None, ldc.region());
+        assert_eq!(
+            Some(&remote),
+            location.get_replica(ObTableConsistencyLevel::Eventual, &ldc)
+        );
+    }
+
+    #[test]
+    fn test_route_id() {
+        let mut location = ObPartitionLocation {
+            leader: None,
+            followers: vec![],
+            tablet_id: OB_INVALID_ID,
+            ls_id: OB_INVALID_ID,
+        };
+        // the partition id before 4.x
+        assert_eq!(3, location.route_id(3));
+
+        location.tablet_id = 200_001;
+        location.ls_id = 1_001;
+        assert_eq!(200_001, location.route_id(3));
+    }
+
+    #[test]
+    fn test_ob_version() {
+        let version = ObVersion::from_str("3.1.2").unwrap();
+        assert_eq!(ObVersion::new(3, 1, 2, 0), version);
+        assert!(!version.is_4x());
+
+        let version = ObVersion::from_str("4.1.0.0").unwrap();
+        assert_eq!(ObVersion::new(4, 1, 0, 0), version);
+        assert!(version.is_4x());
+        assert!(ObVersion::from_str("3.2.3.1").unwrap() < version);
+        assert_eq!("4.1.0.0", version.to_string());
+
+        assert!(!ObVersion::default().is_4x());
+        assert!(ObVersion::from_str("4.x").is_err());
+        assert!(ObVersion::from_str("4.1.0.0.1").is_err());
+    }
 }
@@ -32,12 +32,16 @@ use crate::{
     serde_obkv::value::{CollationType, ObjType, Value},
 };
 
+/// The max number of the partition values enumerated from a scan range to
+/// prune the key partitions, all the partitions are scanned beyond it.
+const MAX_ENUMERATED_PART_VALUES: usize = 1024;
+
 #[derive(Clone, Debug)]
 pub enum ObPartDesc {
     ObRangePartDesc(ObRangePartDesc),
     ObHashPartDesc(ObHashPartDesc),
     ObKeyPartDesc(ObKeyPartDesc),
-    // TODO: impl list part
+    ObListPartDesc(ObListPartDesc),
 }
 
 impl ObPartDesc {
@@ -54,6 +58,10 @@ impl ObPartDesc {
             ObPartDesc::ObKeyPartDesc(ref mut v) => {
                 v.ob_part_desc_obj.set_row_key_element(row_key_element)
             }
+
+            ObPartDesc::ObListPartDesc(ref mut v) => {
+                v.ob_part_desc_obj.set_row_key_element(row_key_element)
+            }
         }
     }
 
@@ -62,6 +70,7 @@ impl ObPartDesc {
             ObPartDesc::ObRangePartDesc(v) => v.ob_part_desc_obj.part_func_type.clone(),
             ObPartDesc::ObHashPartDesc(v) => v.ob_part_desc_obj.part_func_type.clone(),
             ObPartDesc::ObKeyPartDesc(v) => v.ob_part_desc_obj.part_func_type.clone(),
+            ObPartDesc::ObListPartDesc(v) => v.ob_part_desc_obj.part_func_type.clone(),
         }
     }
 
@@ -70,6 +79,7 @@ impl ObPartDesc {
             ObPartDesc::ObRangePartDesc(v) => &v.ob_part_desc_obj.ordered_part_column_names,
             ObPartDesc::ObHashPartDesc(v) => &v.ob_part_desc_obj.ordered_part_column_names,
             ObPartDesc::ObKeyPartDesc(v) => &v.ob_part_desc_obj.ordered_part_column_names,
+            ObPartDesc::ObListPartDesc(v) => &v.ob_part_desc_obj.ordered_part_column_names,
         }
     }
 
@@ -78,6 +88,7 @@ impl ObPartDesc {
             ObPartDesc::ObRangePartDesc(v) => &v.ob_part_desc_obj.part_name_id_map,
             ObPartDesc::ObHashPartDesc(v) => &v.ob_part_desc_obj.part_name_id_map,
             ObPartDesc::ObKeyPartDesc(v) => &v.ob_part_desc_obj.part_name_id_map,
+            ObPartDesc::ObListPartDesc(v) => &v.ob_part_desc_obj.part_name_id_map,
         }
     }
 
@@ -86,6 +97,7 @@ impl ObPartDesc {
             ObPartDesc::ObRangePartDesc(v) => v.get_part_id(row_key),
             ObPartDesc::ObHashPartDesc(v) => v.get_part_id(row_key),
             ObPartDesc::ObKeyPartDesc(v) => v.get_part_id(row_key),
+            ObPartDesc::ObListPartDesc(v) => v.get_part_id(row_key),
         }
     }
 
@@ -106,6 +118,9 @@ impl ObPartDesc {
             ObPartDesc::ObKeyPartDesc(v) => {
                 v.get_part_ids(start, start_inclusive, end, end_inclusive)
             }
+            ObPartDesc::ObListPartDesc(v) => {
+                v.get_part_ids(start, start_inclusive, end, end_inclusive)
+            }
         }
     }
 
@@ -116,6 +131,7 @@ impl ObPartDesc {
             }
             ObPartDesc::ObHashPartDesc(ref mut v) => v.ob_part_desc_obj.part_columns = part_columns,
             ObPartDesc::ObKeyPartDesc(ref mut v) => v.ob_part_desc_obj.part_columns = part_columns,
+            ObPartDesc::ObListPartDesc(ref mut v) => v.ob_part_desc_obj.part_columns = part_columns,
         }
     }
 
@@ -124,6 +140,7 @@ impl ObPartDesc {
             ObPartDesc::ObRangePartDesc(ref mut v) => {
                 v.ordered_compare_column = ordered_part_column
             }
+            ObPartDesc::ObListPartDesc(ref mut v) => v.ordered_compare_column = ordered_part_column,
 
             ObPartDesc::ObHashPartDesc(_) => (),
             ObPartDesc::ObKeyPartDesc(_) => (),
@@ -143,6 +160,10 @@ impl ObPartDesc {
             ObPartDesc::ObKeyPartDesc(ref mut v) => {
                 v.ob_part_desc_obj.part_name_id_map = part_name_id_map
             }
+
+            ObPartDesc::ObListPartDesc(ref mut v) => {
+                v.ob_part_desc_obj.part_name_id_map = part_name_id_map
+            }
         }
     }
 
@@ -151,6 +172,7 @@ impl ObPartDesc {
             ObPartDesc::ObRangePartDesc(v) => v.ob_part_desc_obj.part_func_type.is_list_part(),
             ObPartDesc::ObHashPartDesc(v) => v.ob_part_desc_obj.part_func_type.is_list_part(),
             ObPartDesc::ObKeyPartDesc(v) => v.ob_part_desc_obj.part_func_type.is_list_part(),
+            ObPartDesc::ObListPartDesc(v) => v.ob_part_desc_obj.part_func_type.is_list_part(),
         }
     }
 
@@ -159,6 +181,7 @@ impl ObPartDesc {
             ObPartDesc::ObRangePartDesc(v) => v.ob_part_desc_obj.part_func_type.is_key_part(),
             ObPartDesc::ObHashPartDesc(v) => v.ob_part_desc_obj.part_func_type.is_key_part(),
             ObPartDesc::ObKeyPartDesc(v) => v.ob_part_desc_obj.part_func_type.is_key_part(),
+            ObPartDesc::ObListPartDesc(v) => v.ob_part_desc_obj.part_func_type.is_key_part(),
         }
     }
 
@@ -167,6 +190,7 @@ impl ObPartDesc {
             ObPartDesc::ObRangePartDesc(v) => v.ob_part_desc_obj.part_func_type.is_range_part(),
             ObPartDesc::ObHashPartDesc(v) => v.ob_part_desc_obj.part_func_type.is_range_part(),
             ObPartDesc::ObKeyPartDesc(v) => v.ob_part_desc_obj.part_func_type.is_range_part(),
+            ObPartDesc::ObListPartDesc(v) => v.ob_part_desc_obj.part_func_type.is_range_part(),
         }
     }
 
@@ -175,6 +199,7 @@ impl ObPartDesc {
             ObPartDesc::ObRangePartDesc(v) => v.ob_part_desc_obj.prepare(),
             ObPartDesc::ObHashPartDesc(v) => v.ob_part_desc_obj.prepare(),
             ObPartDesc::ObKeyPartDesc(v) => v.ob_part_desc_obj.prepare(),
+            ObPartDesc::ObListPartDesc(v) => v.ob_part_desc_obj.prepare(),
         }
     }
 }
@@ -311,6 +336,105 @@ impl ObPartDescObj {
         Ok(eval_values)
     }
 
+    /// Get the values of the partition columns referred by the row key, the
+    /// values of the columns referring to a single row key column are taken
+    /// as they are.
+    pub fn get_part_values(&self, row_key: &[Value]) -> Result<Vec<Value>> {
+        let mut part_values =
+            Vec::with_capacity(self.ordered_part_ref_column_row_key_relations.len());
+        for (column, indexes) in &self.ordered_part_ref_column_row_key_relations {
+            let mut refs = Vec::with_capacity(indexes.len());
+            for index in indexes {
+                match row_key.get(*index as usize) {
+                    Some(v) => refs.push(v.clone()),
+                    None => {
+                        error!(
+                            "ObPartDescObj::get_part_values row key:{:?} has no column of index:{}",
+                            row_key, index
+                        );
+                        return Err(CommonErr(
+                            CommonErrCode::PartitionError,
+                            format!(
+                                "ObPartDescObj::get_part_values row key:{:?} has no column of index:{}",
+                                row_key, index
+                            ),
+                        ));
+                    }
+                }
+            }
+            if refs.len() == 1 {
+                part_values.push(refs.pop().unwrap());
+            } else {
+                part_values.push(column.eval_value(&refs)?);
+            }
+        }
+        Ok(part_values)
+    }
+
+    /// Enumerate the values of the partition columns of all the rows in the
+    /// range, `None` is returned if they can't be enumerated within the limit.
+    ///
+    /// A partition column is fixed if it only refers to the leading row key
+    /// columns equal in both the start and the end, and the first column not
+    /// equal is enumerated if it is a bounded integer range.
+    pub fn enumerate_part_values(
+        &self,
+        start: &[Value],
+        start_inclusive: bool,
+        end: &[Value],
+        end_inclusive: bool,
+        limit: usize,
+    ) -> Option<Vec<Vec<Value>>> {
+        let len = start.len().min(end.len());
+        let prefix_len = start
+            .iter()
+            .zip(end.iter())
+            .take_while(|(s, e)| s == e && !s.is_min() && !s.is_max())
+            .count();
+
+        let mut part_values: Vec<Vec<Value>> = vec![vec![]];
+        for (column, indexes) in &self.ordered_part_ref_column_row_key_relations {
+            let candidates = if indexes.iter().all(|i| (*i as usize) < prefix_len) {
+                let refs: Vec<Value> = indexes.iter().map(|i| start[*i as usize].clone()).collect();
+                if refs.len() == 1 {
+                    refs
+                } else {
+                    vec![column.eval_value(&refs).ok()?]
+                }
+            } else if indexes.len() == 1 && indexes[0] as usize == prefix_len && prefix_len < len {
+                // the bounds of the last row key column decide the inclusiveness
+                let is_last = prefix_len + 1 == start.len() && prefix_len + 1 == end.len();
+                let low = integer_value(&start[prefix_len])? as i128
+                    + if is_last && !start_inclusive { 1 } else { 0 };
+                let high = integer_value(&end[prefix_len])? as i128
+                    - if is_last && !end_inclusive { 1 } else { 0 };
+                if low > high {
+                    return Some(vec![]);
+                }
+                if high - low >= limit as i128 {
+                    return None;
+                }
+                (low as i64..=high as i64).map(Value::from).collect()
+            } else {
+                return None;
+            };
+
+            if part_values.len() * candidates.len() > limit {
+                return None;
+            }
+            let mut next_part_values = Vec::with_capacity(part_values.len() * candidates.len());
+            for values in &part_values {
+                for candidate in &candidates {
+                    let mut values = values.clone();
+                    values.push(candidate.clone());
+                    next_part_values.push(values);
+                }
+            }
+            part_values = next_part_values;
+        }
+        Some(part_values)
+    }
+
     pub fn init_comparable_element_by_types(
         &self,
         row_key: &[Value],
@@ -332,6 +456,52 @@ impl ObPartDescObj {
     }
 }
 
+/// Compare the values of a list partition regardless of their metas. The
+/// trailing spaces of the strings are ignored except for the binary collation,
+/// and the `_ci` collation compares the strings case-insensitively.
+///
+/// NOTE: the accents are not folded as `utf8mb4_general_ci` does.
+fn list_value_eq(l: &Value, r: &Value, collation: &CollationType) -> bool {
+    match (l, r) {
+        (Value::String(l, _), Value::String(r, _)) => match collation {
+            CollationType::Binary => l == r,
+            CollationType::UTF8MB4GeneralCi => l
+                .trim_end_matches(' ')
+                .chars()
+                .flat_map(char::to_lowercase)
+                .eq(r.trim_end_matches(' ').chars().flat_map(char::to_lowercase)),
+            _ => l.trim_end_matches(' ') == r.trim_end_matches(' '),
+        },
+        (Value::Bytes(l, _), Value::Bytes(r, _)) => l == r,
+        (Value::Decimal(l, _), Value::Decimal(r, _)) => l == r,
+        (Value::Float(l, _), Value::Float(r, _)) => l == r,
+        (Value::Double(l, _), Value::Double(r, _)) => l == r,
+        (Value::Date(l, _), Value::Date(r, _)) => l == r,
+        (Value::DateTime(l, _), Value::DateTime(r, _))
+        | (Value::Timestamp(l, _), Value::Timestamp(r, _))
+        | (Value::TimeOfDay(l, _), Value::TimeOfDay(r, _)) => l == r,
+        (Value::Year(l, _), Value::Year(r, _)) => l == r,
+        _ => match (integer_value(l), integer_value(r)) {
+            (Some(l), Some(r)) => l == r,
+            _ => l.partial_cmp(r) == Some(std::cmp::Ordering::Equal),
+        },
+    }
+}
+
+/// Get the integer of the value, the min/max values are not integers.
+fn integer_value(value: &Value) -> Option<i64> {
+    match value {
+        _ if value.is_min() || value.is_max() => None,
+        Value::Int8(v, _meta) => Some(*v as i64),
+        Value::UInt8(v, _meta) => Some(*v as i64),
+        Value::Int32(v, _meta) => Some(*v as i64),
+        Value::UInt32(v, _meta) => Some(*v as i64),
+        Value::Int64(v, _meta) => Some(*v),
+        Value::UInt64(v, _meta) if *v <= i64::MAX as u64 => Some(*v as i64),
+        _ => None,
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ObRangePartDesc {
     ob_part_desc_obj: ObPartDescObj,
@@ -440,6 +610,143 @@ impl ObRangePartDesc {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct ObListPartDesc {
+    ob_part_desc_obj: ObPartDescObj,
+    ordered_compare_column: Vec<Box<dyn ObColumn>>,
+    ordered_compare_column_types: Vec<ObjType>,
+    sets: Vec<(ObPartitionKey, i64)>,
+    default_part_id: Option<i64>,
+}
+
+impl Default for ObListPartDesc {
+    fn default() -> ObListPartDesc {
+        ObListPartDesc::new()
+    }
+}
+
+impl ObListPartDesc {
+    pub fn new() -> Self {
+        Self {
+            ob_part_desc_obj: ObPartDescObj::new(),
+            ordered_compare_column: Vec::new(),
+            ordered_compare_column_types: Vec::new(),
+            sets: Vec::new(),
+            default_part_id: None,
+        }
+    }
+
+    /// The rows in the list partitions can't be located by a range, so all
+    /// the partitions are scanned unless the range is a single row key.
+    pub fn get_part_ids(
+        &self,
+        start: &[Value],
+        start_inclusive: bool,
+        end: &[Value],
+        end_inclusive: bool,
+    ) -> Result<Vec<i64>> {
+        if start_inclusive && end_inclusive && start == end {
+            return Ok(vec![self.get_part_id(start)?]);
+        }
+
+        let mut part_ids: Vec<i64> = self
+            .ob_part_desc_obj
+            .part_name_id_map
+            .values()
+            .cloned()
+            .collect();
+        part_ids.sort_unstable();
+        part_ids.dedup();
+        Ok(part_ids)
+    }
+
+    pub fn get_part_id(&self, row_key: &[Value]) -> Result<i64> {
+        if row_key.len() != self.ob_part_desc_obj.row_key_element.len() {
+            error!(
+                "ObListPartDesc::get_part_id row key is consist of :{:?}, but found: {:?}",
+                self.ob_part_desc_obj.row_key_element, row_key
+            );
+            return Err(CommonErr(
+                CommonErrCode::PartitionError,
+                format!(
+                    "ObListPartDesc::get_part_id row key is consist of :{:?}, but found: {:?}",
+                    self.ob_part_desc_obj.row_key_element, row_key
+                ),
+            ));
+        }
+        let row_key = self.ob_part_desc_obj.eval_row_key_values(row_key)?;
+        let comparable_element = self
+            .ob_part_desc_obj
+            .init_comparable_element_by_types(&row_key, &self.ordered_compare_column);
+        for (key, part_id) in &self.sets {
+            if self.matches_set(key, &comparable_element) {
+                return Ok(*part_id);
+            }
+        }
+        match self.default_part_id {
+            Some(part_id) => Ok(part_id),
+            None => {
+                error!(
+                    "ObListPartDesc::get_part_id no partition matches the row key:{:?}",
+                    row_key
+                );
+                Err(CommonErr(
+                    CommonErrCode::PartitionError,
+                    format!(
+                        "ObListPartDesc::get_part_id no partition matches the row key:{:?}",
+                        row_key
+                    ),
+                ))
+            }
+        }
+    }
+
+    /// Whether the row key is one of the set, the strings are compared by the
+    /// collations of the columns.
+    fn matches_set(&self, set: &ObPartitionKey, row_key: &[Comparable]) -> bool {
+        let elements = set.partition_elements();
+        elements.len() == row_key.len()
+            && elements
+                .iter()
+                .zip(row_key)
+                .zip(&self.ordered_compare_column)
+                .all(|((l, r), column)| match (l, r) {
+                    (Comparable::Value(l), Comparable::Value(r)) => {
+                        list_value_eq(l, r, column.get_ob_collation_type())
+                    }
+                    _ => false,
+                })
+    }
+
+    pub fn get_ordered_compare_column(&self) -> &Vec<Box<dyn ObColumn>> {
+        &self.ordered_compare_column
+    }
+
+    pub fn set_sets(&mut self, sets: Vec<(ObPartitionKey, i64)>) {
+        self.sets = sets;
+    }
+
+    pub fn set_default_part_id(&mut self, default_part_id: Option<i64>) {
+        self.default_part_id = default_part_id;
+    }
+
+    pub fn set_part_func_type(&mut self, part_func_type: PartFuncType) {
+        self.ob_part_desc_obj.part_func_type = part_func_type;
+    }
+
+    pub fn set_part_expr(&mut self, part_expr: String) {
+        self.ob_part_desc_obj.part_expr = part_expr;
+    }
+
+    pub fn set_ordered_part_column_names(&mut self, ordered_part_column_names: Vec<String>) {
+        self.ob_part_desc_obj.ordered_part_column_names = ordered_part_column_names;
+    }
+
+    pub fn set_ordered_compare_column_types(&mut self, ordered_compare_column_types: Vec<ObjType>) {
+        self.ordered_compare_column_types = ordered_compare_column_types;
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ObHashPartDesc {
     ob_part_desc_obj: ObPartDescObj,
@@ -503,47 +810,24 @@ impl ObHashPartDesc {
         end: &[Value],
         end_inclusive: bool,
     ) -> Result<Vec<i64>> {
-        let start_value = &start[0];
-        let end_value = &end[0];
-        // TODO: impl parse_to_i64 in serde_obkv
-        let start_long_value: i64 = match start_value {
-            Value::String(v, _meta) => v.parse::<i64>()?,
-            Value::Int64(v, _meta) => *v,
-            Value::Int32(v, _meta) => *v as i64,
-            Value::Int8(v, _meta) => *v as i64,
-            // TODO: support value bytes
-            Value::Bytes(_v, _meta) => unimplemented!(),
-            _ => 0,
-        };
-        let end_long_value: i64 = match end_value {
-            Value::String(v, _meta) => v.parse::<i64>()?,
-            Value::Int64(v, _meta) => *v,
-            Value::Int32(v, _meta) => *v as i64,
-            Value::Int8(v, _meta) => *v as i64,
-            // TODO: support value bytes
-            Value::Bytes(_v, _meta) => unimplemented!(),
-            _ => 0,
-        };
-
-        let start_hash_value = if start_inclusive {
-            start_long_value
-        } else {
-            start_long_value + 1
-        };
-        let end_hash_value = if end_inclusive {
-            end_long_value
-        } else {
-            end_long_value - 1
-        };
-
-        if end_hash_value - start_hash_value + 1 >= self.part_num as i64 {
-            Ok(self.complete_works.clone())
-        } else {
-            let mut part_ids = Vec::with_capacity((end_hash_value - start_hash_value + 1) as usize);
-            for i in start_hash_value..=end_hash_value {
-                part_ids.push(self.inner_hash(i));
+        // the consecutive integers as many as the partitions hit all of them
+        match self.ob_part_desc_obj.enumerate_part_values(
+            start,
+            start_inclusive,
+            end,
+            end_inclusive,
+            self.part_num.max(0) as usize,
+        ) {
+            Some(part_values) => {
+                let mut part_ids = Vec::with_capacity(part_values.len());
+                for values in part_values {
+                    part_ids.push(self.get_part_id_by_part_values(&values)?);
+                }
+                part_ids.sort_unstable();
+                part_ids.dedup();
+                Ok(part_ids)
             }
-            Ok(part_ids)
+            None => Ok(self.complete_works.clone()),
         }
     }
 
@@ -558,21 +842,30 @@ impl ObHashPartDesc {
                 "ObHashPartDesc::get_part_id get_part_id: row_key is empty".to_owned(),
             ));
         }
-        // TODO: evalRowKeyValues
+        let part_values = self.ob_part_desc_obj.get_part_values(row_key)?;
+        self.get_part_id_by_part_values(&part_values)
+    }
 
+    fn get_part_id_by_part_values(&self, part_values: &[Value]) -> Result<i64> {
         // Note: There was a loop in java version
-        let value: i64 = match &row_key[0] {
-            Value::String(v, _meta) => v.parse::<i64>()?,
-            Value::Int64(v, _meta) => *v,
-            Value::Int32(v, _meta) => *v as i64,
-            Value::Int8(v, _meta) => *v as i64,
-            // TODO: support value bytes
-            Value::Bytes(_v, _meta) => unimplemented!(),
-            _ => 0,
+        let value: i64 = match part_values.first() {
+            Some(Value::String(v, _meta)) => v.parse::<i64>()?,
+            Some(v) if integer_value(v).is_some() => integer_value(v).unwrap(),
+            v => {
+                error!(
+                    "ObHashPartDesc::get_part_id_by_part_values not supported value:{:?}",
+                    v
+                );
+                return Err(CommonErr(
+                    CommonErrCode::PartitionError,
+                    format!(
+                        "ObHashPartDesc::get_part_id_by_part_values not supported value:{:?}",
+                        v
+                    ),
+                ));
+            }
         };
-        let current_part_id = self.inner_hash(value);
-        // TODO: diff with java sdk
-        Ok(current_part_id)
+        Ok(self.inner_hash(value))
     }
 
     fn inner_hash(&self, value: i64) -> i64 {
@@ -635,41 +928,34 @@ impl ObKeyPartDesc {
     pub fn get_part_ids(
         &self,
         start: &[Value],
-        _start_inclusive: bool,
+        start_inclusive: bool,
         end: &[Value],
-        _end_inclusive: bool,
+        end_inclusive: bool,
     ) -> Result<Vec<i64>> {
-        // diff with java sdk
-        // if start=[min,min,min] end=[max,max,max], scan all partitions
-        let mut is_min_max = true;
-        for v in start {
-            if !is_min_max {
-                break;
-            }
-            is_min_max &= v.is_min();
-        }
-
-        for v in end {
-            if !is_min_max {
-                break;
+        match self.ob_part_desc_obj.enumerate_part_values(
+            start,
+            start_inclusive,
+            end,
+            end_inclusive,
+            MAX_ENUMERATED_PART_VALUES,
+        ) {
+            Some(part_values) => {
+                let mut part_ids = Vec::with_capacity(part_values.len());
+                for values in part_values {
+                    part_ids.push(self.get_part_id_by_part_values(&values)?);
+                }
+                part_ids.sort_unstable();
+                part_ids.dedup();
+                Ok(part_ids)
             }
-            is_min_max &= v.is_max();
-        }
-
-        if is_min_max {
-            let mut part_ids: Vec<i64> = Vec::with_capacity(self.part_num as usize);
-            for i in 0..self.part_num as i64 {
-                part_ids.push(i);
+            None => {
+                let mut part_ids: Vec<i64> = Vec::with_capacity(self.part_num as usize);
+                for i in 0..self.part_num as i64 {
+                    part_ids.push(i);
+                }
+                Ok(part_ids)
             }
-            return Ok(part_ids);
         }
-
-        let mut row_keys: Vec<Value> = Vec::with_capacity(2);
-        row_keys.append(&mut start.to_vec());
-        row_keys.append(&mut end.to_vec());
-        let mut part_ids = Vec::with_capacity(1);
-        part_ids.push(self.get_part_id(&row_keys)?);
-        Ok(part_ids)
     }
 
     pub fn get_part_id(&self, row_key: &[Value]) -> Result<i64> {
@@ -680,23 +966,18 @@ impl ObKeyPartDesc {
                 "ObKeyPartDesc::get_part_id get_part_id: row_key is empty".to_owned(),
             ));
         }
-        // TODO: evalRowKeyValues
-        // let mut part_id: Option<i64> = None;
-        let part_ref_column_size = self
-            .ob_part_desc_obj
-            .ordered_part_ref_column_row_key_relations
-            .len();
+        let part_values = self.ob_part_desc_obj.get_part_values(row_key)?;
+        self.get_part_id_by_part_values(&part_values)
+    }
 
+    fn get_part_id_by_part_values(&self, part_values: &[Value]) -> Result<i64> {
         let mut hash_value = 0i64;
-        for i in 0..part_ref_column_size {
-            hash_value = ObKeyPartDesc::to_hashcode(
-                &row_key[i],
-                &self
-                    .ob_part_desc_obj
-                    .ordered_part_ref_column_row_key_relations[i]
-                    .0,
-                hash_value,
-            )?;
+        for (value, (column, _)) in part_values.iter().zip(
+            &self
+                .ob_part_desc_obj
+                .ordered_part_ref_column_row_key_relations,
+        ) {
+            hash_value = ObKeyPartDesc::to_hashcode(value, column, hash_value)?;
         }
         hash_value = hash_value.abs();
         Ok(
@@ -723,33 +1004,85 @@ impl ObKeyPartDesc {
             Value::Int64(v, _meta) => ObKeyPartDesc::long_hash(*v, hash_code),
             Value::Int32(v, _meta) => ObKeyPartDesc::long_hash(*v as i64, hash_code),
             Value::Int8(v, _meta) => ObKeyPartDesc::long_hash(*v as i64, hash_code),
+            Value::UInt64(v, _meta) => ObKeyPartDesc::unsigned_long_hash(*v, hash_code),
+            Value::UInt32(v, _meta) => ObKeyPartDesc::unsigned_long_hash(*v as u64, hash_code),
+            Value::UInt8(v, _meta) => ObKeyPartDesc::unsigned_long_hash(*v as u64, hash_code),
+            Value::Bool(v, _meta) => ObKeyPartDesc::long_hash(*v as i64, hash_code),
+            Value::Float(v, _meta) => ObKeyPartDesc::float_hash(*v, hash_code),
+            Value::Double(v, _meta) => ObKeyPartDesc::double_hash(*v, hash_code),
             Value::Bytes(v, meta) => ObKeyPartDesc::varchar_hash(
                 Value::Bytes(v.clone(), meta.clone()),
                 ref_column.get_ob_collation_type(),
                 hash_code,
             ),
-            // TODO: support value Time
-            Value::Time(_v, _meta) => unimplemented!(),
-            // TODO: support value Date
-            Value::Date(_v, _meta) => unimplemented!(),
-            _ => Ok(0),
+            // the time is sent in microseconds, see `Value::encode`
+            Value::Time(v, _meta) => ObKeyPartDesc::timestamp_hash(*v * 1000, hash_code),
+            Value::DateTime(v, _meta) | Value::Timestamp(v, _meta) => {
+                ObKeyPartDesc::timestamp_hash(*v, hash_code)
+            }
+            Value::Date(v, _meta) => ObKeyPartDesc::date_hash(*v, hash_code),
+            _ => {
+                error!(
+                    "ObKeyPartDesc::to_hashcode not supported value, value:{:?}, column:{:?}",
+                    value,
+                    ref_column.get_column_name()
+                );
+                Err(CommonErr(
+                    CommonErrCode::PartitionError,
+                    format!(
+                        "ObKeyPartDesc::to_hashcode not supported value, value:{:?}, column:{:?}",
+                        value,
+                        ref_column.get_column_name()
+                    ),
+                ))
+            }
         }
     }
 
-    // TODO: check if murmur2 hash value is correct(java sdk)
+    /// The integers are hashed in their decimal strings.
     pub fn long_hash(value: i64, hash_code: i64) -> Result<i64> {
         Ok(murmur2::murmur64a(value.to_string().as_bytes(), hash_code as u64) as i64)
     }
 
-    // TODO: support value Time
-    //pub fn timestamp_hash() -> Result<i64> {
-    //    unimplemented!();
-    //}
+    /// Same as [`ObKeyPartDesc::long_hash`], the unsigned integers are hashed
+    /// in their own decimal strings, e.g. `u64::MAX` is not hashed as `-1`.
+    pub fn unsigned_long_hash(value: u64, hash_code: i64) -> Result<i64> {
+        Ok(murmur2::murmur64a(value.to_string().as_bytes(), hash_code as u64) as i64)
+    }
+
+    /// The datetime, timestamp and time are hashed in the 8 bytes of the
+    /// int64 value.
+    pub fn timestamp_hash(value: i64, hash_code: i64) -> Result<i64> {
+        Ok(murmur2::murmur64a(&value.to_le_bytes(), hash_code as u64) as i64)
+    }
+
+    /// The date is hashed in the 4 bytes of the int32 value.
+    pub fn date_hash(value: i32, hash_code: i64) -> Result<i64> {
+        Ok(murmur2::murmur64a(&value.to_le_bytes(), hash_code as u64) as i64)
+    }
 
-    // TODO: support value Date
-    //pub fn date_hash() -> Result<i64> {
-    //    unimplemented!();
-    //}
+    /// The negative zero and NaN are normalized before hashing like the server.
+    pub fn float_hash(value: f32, hash_code: i64) -> Result<i64> {
+        let value = if value == 0.0 {
+            0.0
+        } else if value.is_nan() {
+            f32::NAN
+        } else {
+            value
+        };
+        Ok(murmur2::murmur64a(&value.to_le_bytes(), hash_code as u64) as i64)
+    }
+
+    pub fn double_hash(value: f64, hash_code: i64) -> Result<i64> {
+        let value = if value == 0.0 {
+            0.0
+        } else if value.is_nan() {
+            f64::NAN
+        } else {
+            value
+        };
+        Ok(murmur2::murmur64a(&value.to_le_bytes(), hash_code as u64) as i64)
+    }
 
     pub fn varchar_hash(
         value: Value,
@@ -815,3 +1148,296 @@ impl ObKeyPartDesc {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::{
+        rpc::protocol::partition::ob_column::ObSimpleColumn,
+        serde_obkv::value::{ObjMeta, ObjType},
+    };
+
+    #[test]
+    fn test_key_part_hashcode() {
+        let column: Box<dyn ObColumn> = Box::new(ObSimpleColumn::new(
+            "c1".to_owned(),
+            0,
+            ObjType::DateTime,
+            CollationType::Binary,
+        ));
+        let meta = ObjMeta::new_numeric_meta(ObjType::DateTime);
+
+        let values = vec![
+            Value::Time(1_600_000_000_000, meta.clone()),
+            Value::Date(18_000, ObjMeta::new_numeric_meta(ObjType::Date)),
+            Value::UInt64(u64::MAX, ObjMeta::new_numeric_meta(ObjType::UInt64)),
+            Value::Double(1.5, ObjMeta::new_numeric_meta(ObjType::Double)),
+        ];
+        for value in values {
+            assert!(ObKeyPartDesc::to_hashcode(&value, &column, 0).is_ok());
+        }
+        let max_hash = ObKeyPartDesc::to_hashcode(
+            &Value::UInt64(u64::MAX, ObjMeta::new_numeric_meta(ObjType::UInt64)),
+            &column,
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            murmur2::murmur64a(b"18446744073709551615", 0) as i64,
+            max_hash
+        );
+        assert_ne!(ObKeyPartDesc::long_hash(-1, 0).unwrap(), max_hash);
+        assert_eq!(
+            ObKeyPartDesc::long_hash(5, 0).unwrap(),
+            ObKeyPartDesc::to_hashcode(
+                &Value::UInt64(5, ObjMeta::new_numeric_meta(ObjType::UInt64)),
+                &column,
+                0
+            )
+            .unwrap()
+        );
+        // the legacy time in milliseconds is hashed as the datetime sent
+        assert_eq!(
+            ObKeyPartDesc::to_hashcode(
+                &Value::DateTime(1_600_000_000_000_000, meta.clone()),
+                &column,
+                0
+            )
+            .unwrap(),
+            ObKeyPartDesc::to_hashcode(&Value::Time(1_600_000_000_000, meta.clone()), &column, 0)
+                .unwrap()
+        );
+        assert_eq!(
+            ObKeyPartDesc::double_hash(0.0, 0).unwrap(),
+            ObKeyPartDesc::double_hash(-0.0, 0).unwrap()
+        );
+
+        let result = ObKeyPartDesc::to_hashcode(&Value::Null(meta), &column, 0);
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            CommonErr(code, _) => assert_eq!(CommonErrCode::PartitionError, code),
+            e => panic!("unexpected error:{:?}", e),
+        }
+    }
+
+    fn new_part_desc(mut part_desc: ObPartDesc) -> ObPartDesc {
+        let columns: Vec<Box<dyn ObColumn>> = vec![
+            Box::new(ObSimpleColumn::new(
+                "c1".to_owned(),
+                0,
+                ObjType::Int64,
+                CollationType::Binary,
+            )),
+            Box::new(ObSimpleColumn::new(
+                "c2".to_owned(),
+                1,
+                ObjType::Varchar,
+                CollationType::UTF8MB4Bin,
+            )),
+        ];
+        let mut row_key_element = HashMap::new();
+        row_key_element.insert("c1".to_owned(), 0);
+        row_key_element.insert("c2".to_owned(), 1);
+        part_desc.set_part_columns(columns);
+        part_desc.set_row_key_element(row_key_element);
+        part_desc.prepare().expect("fail to prepare part desc");
+        part_desc
+    }
+
+    #[test]
+    fn test_part_ids_pruning() {
+        let mut key_desc = ObKeyPartDesc::new();
+        key_desc.set_part_func_type(PartFuncType::KeyV3);
+        key_desc.set_ordered_part_column_names(vec!["c1".to_owned()]);
+        key_desc.set_part_num(16);
+        let mut hash_desc = ObHashPartDesc::new();
+        hash_desc.set_part_func_type(PartFuncType::HashV2);
+        hash_desc.set_ordered_part_column_names(vec!["c1".to_owned()]);
+        hash_desc.set_part_num(16);
+        hash_desc.set_complete_works((0..16).collect());
+
+        for part_desc in [
+            ObPartDesc::ObKeyPartDesc(key_desc),
+            ObPartDesc::ObHashPartDesc(hash_desc),
+        ] {
+            let part_desc = new_part_desc(part_desc);
+            let part_id_of = |c1: i64| {
+                part_desc
+                    .get_part_id(&[Value::from(c1), Value::from("a")])
+                    .unwrap()
+            };
+
+            // the partition column is fixed while the later column is ranged
+            assert_eq!(
+                vec![part_id_of(1)],
+                part_desc
+                    .get_part_ids(
+                        &[Value::from(1i64), Value::get_min()],
+                        true,
+                        &[Value::from(1i64), Value::get_max()],
+                        true,
+                    )
+                    .unwrap()
+            );
+
+            // a bounded integer range is enumerated
+            let mut expected: Vec<i64> = (1..=3).map(part_id_of).collect();
+            expected.sort_unstable();
+            expected.dedup();
+            assert_eq!(
+                expected,
+                part_desc
+                    .get_part_ids(
+                        &[Value::from(1i64), Value::from("a")],
+                        false,
+                        &[Value::from(3i64), Value::from("a")],
+                        false,
+                    )
+                    .unwrap()
+            );
+
+            assert_eq!(
+                16,
+                part_desc
+                    .get_part_ids(
+                        &[Value::get_min(), Value::get_min()],
+                        true,
+                        &[Value::get_max(), Value::get_max()],
+                        true,
+                    )
+                    .unwrap()
+                    .len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_list_part_desc() {
+        let column: Box<dyn ObColumn> = Box::new(ObSimpleColumn::new(
+            "c1".to_owned(),
+            0,
+            ObjType::Int64,
+            CollationType::Binary,
+        ));
+        let mut row_key_element = HashMap::new();
+        row_key_element.insert("c1".to_owned(), 0);
+        let mut part_name_id_map = HashMap::new();
+        part_name_id_map.insert("p0".to_owned(), 0);
+        part_name_id_map.insert("p1".to_owned(), 1);
+
+        let mut list_desc = ObListPartDesc::new();
+        list_desc.set_part_func_type(PartFuncType::ListColumns);
+        list_desc.set_ordered_part_column_names(vec!["c1".to_owned()]);
+        list_desc.set_sets(vec![(
+            ObPartitionKey::new(vec![Comparable::Value(Value::from(1i64))]),
+            0,
+        )]);
+        let mut part_desc = ObPartDesc::ObListPartDesc(list_desc);
+        part_desc.set_part_columns(vec![column.clone()]);
+        part_desc.set_ordered_compare_columns(vec![column]);
+        part_desc.set_row_key_element(row_key_element);
+        part_desc.set_part_name_id_map(part_name_id_map);
+        part_desc.prepare().expect("fail to prepare list part desc");
+
+        assert_eq!(0, part_desc.get_part_id(&[Value::from(1i64)]).unwrap());
+        assert!(part_desc.get_part_id(&[Value::from(2i64)]).is_err());
+        assert_eq!(
+            vec![0, 1],
+            part_desc
+                .get_part_ids(&[Value::from(1i64)], true, &[Value::from(2i64)], true)
+                .unwrap()
+        );
+
+        if let ObPartDesc::ObListPartDesc(v) = &mut part_desc {
+            v.set_default_part_id(Some(1));
+        }
+        assert_eq!(1, part_desc.get_part_id(&[Value::from(2i64)]).unwrap());
+        assert_eq!(
+            vec![1],
+            part_desc
+                .get_part_ids(&[Value::from(2i64)], true, &[Value::from(2i64)], true)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_list_part_desc_collation() {
+        let column: Box<dyn ObColumn> = Box::new(ObSimpleColumn::new(
+            "c1".to_owned(),
+            0,
+            ObjType::Varchar,
+            CollationType::UTF8MB4GeneralCi,
+        ));
+        let mut row_key_element = HashMap::new();
+        row_key_element.insert("c1".to_owned(), 0);
+
+        let mut list_desc = ObListPartDesc::new();
+        list_desc.set_part_func_type(PartFuncType::ListColumns);
+        list_desc.set_ordered_part_column_names(vec!["c1".to_owned()]);
+        list_desc.set_sets(vec![(
+            ObPartitionKey::new(vec![Comparable::Value(Value::from("a"))]),
+            0,
+        )]);
+        list_desc.set_default_part_id(Some(1));
+        let mut part_desc = ObPartDesc::ObListPartDesc(list_desc);
+        part_desc.set_part_columns(vec![column.clone()]);
+        part_desc.set_ordered_compare_columns(vec![column]);
+        part_desc.set_row_key_element(row_key_element);
+        part_desc.prepare().expect("fail to prepare list part desc");
+
+        assert_eq!(0, part_desc.get_part_id(&[Value::from("a")]).unwrap());
+        assert_eq!(0, part_desc.get_part_id(&[Value::from("A ")]).unwrap());
+        assert_eq!(1, part_desc.get_part_id(&[Value::from("b")]).unwrap());
+    }
+
+    #[test]
+    fn test_list_part_desc_date() {
+        let column: Box<dyn ObColumn> = Box::new(ObSimpleColumn::new(
+            "c1".to_owned(),
+            0,
+            ObjType::Date,
+            CollationType::Binary,
+        ));
+        let mut row_key_element = HashMap::new();
+        row_key_element.insert("c1".to_owned(), 0);
+
+        // the list values are parsed from the strings like the location util
+        let set_value = column
+            .eval_value(&[Value::String(
+                "2023-02-28".to_owned(),
+                ObjMeta::default_obj_meta(ObjType::Date),
+            )])
+            .unwrap();
+        let mut list_desc = ObListPartDesc::new();
+        list_desc.set_part_func_type(PartFuncType::ListColumns);
+        list_desc.set_ordered_part_column_names(vec!["c1".to_owned()]);
+        list_desc.set_sets(vec![(
+            ObPartitionKey::new(vec![Comparable::Value(set_value)]),
+            0,
+        )]);
+        list_desc.set_default_part_id(Some(1));
+        let mut part_desc = ObPartDesc::ObListPartDesc(list_desc);
+        part_desc.set_part_columns(vec![column.clone()]);
+        part_desc.set_ordered_compare_columns(vec![column]);
+        part_desc.set_row_key_element(row_key_element);
+        part_desc.prepare().expect("fail to prepare list part desc");
+
+        let date = NaiveDate::from_ymd_opt(2023, 2, 28).unwrap();
+        assert_eq!(0, part_desc.get_part_id(&[Value::from(date)]).unwrap());
+        assert_eq!(
+            0,
+            part_desc.get_part_id(&[Value::from("2023-02-28")]).unwrap()
+        );
+        let other = NaiveDate::from_ymd_opt(2023, 3, 1).unwrap();
+        assert_eq!(1, part_desc.get_part_id(&[Value::from(other)]).unwrap());
+        assert_eq!(
+            Some(CommonErrCode::PartitionError),
+            part_desc
+                .get_part_id(&[Value::from(1.5f64)])
+                .unwrap_err()
+                .common_err_code()
+        );
+    }
+}
@@ -20,10 +20,12 @@ use std::{collections::HashMap, i32, i64, u8};
 use mysql as my;
 
 use super::{
-    ob_part_constants, part_func_type::PartFuncType, ObHashPartDesc, ObKeyPartDesc, ObPartDesc,
-    ObPartitionInfo, ObPartitionLevel, ObRangePartDesc, TableEntry,
+    ob_part_constants, part_func_type::PartFuncType, ObHashPartDesc, ObKeyPartDesc, ObListPartDesc,
+    ObPartDesc, ObPartitionInfo, ObPartitionLevel, ObRangePartDesc, ObVersion, TableEntry,
+    TableEntryKey,
 };
 use crate::{
+    client::schema::{ColumnSchema, PartitionBound, PartitionDesc, PartitionSchema, TableSchema},
     error::{CommonErrCode, Error::Common as CommonErr, Result},
     rpc::protocol::partition::{
         ob_column::{ObColumn, ObGeneratedColumn, ObSimpleColumn},
@@ -34,6 +36,16 @@ use crate::{
 
 pub const TEMPLATE_PART_ID: i32 = -1;
 
+// Take the value of the column from the row, or the default value if the
+// column is absent or NULL.
+macro_rules! take_or_default {
+    ($row:expr, $column:expr) => {
+        $row.take_opt($column)
+            .and_then(|v| v.ok())
+            .unwrap_or_default()
+    };
+}
+
 //pub const PROXY_PART_INFO_SQL: &str = "SELECT /*+READ_CONSISTENCY(WEAK)*/
 // part_level, part_num, part_type, part_space, part_expr,    part_range_type,
 // part_interval_bin, interval_start_bin,    sub_part_num, sub_part_type,
@@ -43,106 +55,297 @@ pub const TEMPLATE_PART_ID: i32 = -1;
 // __all_virtual_proxy_partition_info    WHERE table_id = {} group by
 // part_key_name order by part_key_name LIMIT {};";
 
+/// A row of the partition info of the table, the rows are kept with the
+/// partition info to rebuild it from the location cache file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObPartInfoRow {
+    part_level: i32,
+    part_num: i32,
+    part_type: i32,
+    part_space: i32,
+    part_expr: String,
+    part_range_type: String,
+    sub_part_num: i32,
+    sub_part_type: i32,
+    sub_part_space: i32,
+    sub_part_range_type: String,
+    sub_part_expr: String,
+    part_key_name: String,
+    part_key_type: u8,
+    part_key_idx: i32,
+    part_key_extra: String,
+    spare1: u8,
+}
+
+impl ObPartInfoRow {
+    fn from_row(mut row: my::Row) -> Self {
+        Self {
+            part_level: take_or_default!(row, "part_level"),
+            part_num: take_or_default!(row, "part_num"),
+            part_type: take_or_default!(row, "part_type"),
+            part_space: take_or_default!(row, "part_space"),
+            part_expr: take_or_default!(row, "part_expr"),
+            part_range_type: take_or_default!(row, "part_range_type"),
+            sub_part_num: take_or_default!(row, "sub_part_num"),
+            sub_part_type: take_or_default!(row, "sub_part_type"),
+            sub_part_space: take_or_default!(row, "sub_part_space"),
+            sub_part_range_type: take_or_default!(row, "sub_part_range_type"),
+            sub_part_expr: take_or_default!(row, "sub_part_expr"),
+            part_key_name: take_or_default!(row, "part_key_name"),
+            part_key_type: take_or_default!(row, "part_key_type"),
+            part_key_idx: take_or_default!(row, "part_key_idx"),
+            part_key_extra: take_or_default!(row, "part_key_extra"),
+            spare1: take_or_default!(row, "spare1"),
+        }
+    }
+}
+
+/// A row of the first or the sub level partitions of the table.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObPartRow {
+    part_id: i64,
+    part_name: String,
+    // only on OceanBase 4.x
+    tablet_id: Option<i64>,
+    high_bound_val: String,
+    list_val: String,
+    // the first level partition of the sub partition, only on OceanBase 4.x
+    #[serde(default)]
+    first_part_id: Option<i64>,
+}
+
+impl ObPartRow {
+    fn from_row(mut row: my::Row, part_id_column_name: &str) -> Self {
+        Self {
+            part_id: take_or_default!(row, part_id_column_name),
+            part_name: take_or_default!(row, "part_name"),
+            tablet_id: row.take_opt("tablet_id").and_then(|v| v.ok()),
+            high_bound_val: take_or_default!(row, "high_bound_val"),
+            list_val: take_or_default!(row, "list_val"),
+            first_part_id: if part_id_column_name == "sub_part_id" {
+                row.take_opt("part_id").and_then(|v| v.ok())
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// The rows the partition info of the table is built from.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ObPartitionRows {
+    info_rows: Vec<ObPartInfoRow>,
+    first_part_rows: Option<Vec<ObPartRow>>,
+    sub_part_rows: Option<Vec<ObPartRow>>,
+    // the primary key columns of the table in order
+    #[serde(default)]
+    row_key_columns: Vec<String>,
+}
+
+/// A row of the columns of the table.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ObColumnRow {
+    column_name: String,
+    rowkey_position: i64,
+    data_type: u8,
+    collation_type: u8,
+    data_length: i32,
+    nullable: bool,
+    is_hidden: bool,
+    cur_default_value: Option<String>,
+}
+
+impl ObColumnRow {
+    fn from_row(mut row: my::Row) -> Self {
+        Self {
+            column_name: take_or_default!(row, "column_name"),
+            rowkey_position: take_or_default!(row, "rowkey_position"),
+            data_type: take_or_default!(row, "data_type"),
+            collation_type: take_or_default!(row, "collation_type"),
+            data_length: take_or_default!(row, "data_length"),
+            nullable: take_or_default!(row, "nullable"),
+            is_hidden: take_or_default!(row, "is_hidden"),
+            cur_default_value: take_or_default!(row, "cur_default_value"),
+        }
+    }
+}
+
 pub struct LocationUtil {}
 
 impl LocationUtil {
-    pub fn fetch_partition_info(
+    /// Fetch the rows of the partition info, and the rows of the partitions
+    /// needed to route the requests.
+    pub fn fetch_partition_rows(
         conn: &mut my::PooledConn,
         table_entry: &TableEntry,
-    ) -> Result<ObPartitionInfo> {
+        key: &TableEntryKey,
+        ob_version: ObVersion,
+    ) -> Result<ObPartitionRows> {
         let sql = format!("SELECT /*+READ_CONSISTENCY(WEAK)*/ part_level, part_num, part_type, part_space, part_expr
 	, part_range_type, part_interval_bin, interval_start_bin, sub_part_num, sub_part_type
 	, sub_part_space, sub_part_range_type, def_sub_part_interval_bin, def_sub_interval_start_bin, sub_part_expr
 	, part_key_name, part_key_type, part_key_idx, part_key_extra, spare1
     FROM oceanbase.__all_virtual_proxy_partition_info
-    WHERE table_id = {}
+    WHERE {}table_id = {}
     GROUP BY part_key_name
     ORDER BY part_key_name
-    LIMIT {};", table_entry.table_id, i64::MAX);
+    LIMIT {};", LocationUtil::tenant_condition(key, ob_version), table_entry.table_id, i64::MAX);
+
+        let mut info_rows = Vec::new();
+        for result in conn.query(sql)? {
+            info_rows.push(ObPartInfoRow::from_row(result?));
+        }
+
+        // the partitions to fetch depend on the types of the partitioning
+        let info = LocationUtil::build_partition_info(&info_rows)?;
+        let mut rows = ObPartitionRows {
+            info_rows,
+            first_part_rows: None,
+            sub_part_rows: None,
+            row_key_columns: LocationUtil::fetch_row_key_columns(
+                conn,
+                table_entry,
+                key,
+                ob_version,
+            )?,
+        };
+        if let Some(first_part_desc) = &info.first_part_desc {
+            let ob_part_func_type = first_part_desc.get_part_func_type();
+            // the tablets of all the partitions are fetched on OceanBase 4.x
+            if ob_part_func_type.is_range_part()
+                || ob_part_func_type.is_list_part()
+                || ob_version.is_4x()
+            {
+                rows.first_part_rows = Some(LocationUtil::fetch_first_part_rows(
+                    conn,
+                    table_entry,
+                    key,
+                    ob_version,
+                )?);
+            }
+        }
+        if let Some(sub_part_desc) = &info.sub_part_desc {
+            let ob_part_func_type = sub_part_desc.get_part_func_type();
+            // the tablets of all the sub partitions are fetched on OceanBase 4.x
+            if ob_part_func_type.is_range_part()
+                || ob_part_func_type.is_list_part()
+                || ob_version.is_4x()
+            {
+                rows.sub_part_rows = Some(LocationUtil::fetch_sub_part_rows(
+                    conn,
+                    table_entry,
+                    key,
+                    ob_version,
+                )?);
+            }
+        }
+        Ok(rows)
+    }
 
+    /// Build the partition info of the table from the rows fetched.
+    pub fn build_partition_info_with_rows(
+        table_entry: &mut TableEntry,
+        rows: ObPartitionRows,
+    ) -> Result<()> {
+        table_entry.partition_info = Some(LocationUtil::build_partition_info(&rows.info_rows)?);
+        if let Some(part_rows) = &rows.first_part_rows {
+            LocationUtil::set_first_part(table_entry, part_rows)?;
+        }
+        if let Some(part_rows) = &rows.sub_part_rows {
+            LocationUtil::set_sub_part(table_entry, part_rows)?;
+        }
+        if let Some(info) = &mut table_entry.partition_info {
+            info.part_name_id_map = LocationUtil::build_part_name_id_map(info);
+        }
+        // the row key element set already overrides the one discovered
+        let row_key_element = if table_entry.row_key_element.is_empty() {
+            rows.row_key_columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| (column.to_owned(), i as i32))
+                .collect()
+        } else {
+            table_entry.row_key_element.clone()
+        };
+        table_entry.set_row_key_element(row_key_element);
+        if let Some(info) = &mut table_entry.partition_info {
+            info.rows = rows;
+        }
+        Ok(())
+    }
+
+    fn build_partition_info(info_rows: &[ObPartInfoRow]) -> Result<ObPartitionInfo> {
         let mut info = ObPartitionInfo::new();
         // in java sdk is parsePartitionInfo()
-        let mut is_first_row = true;
-        for result in conn.query(sql)? {
-            result.map(|mut row| {
-                    if is_first_row {
-                        is_first_row = false;
-
-                        // get part level
-                        info.level = ObPartitionLevel::from_int(row.take("part_level").unwrap());
-
-                        // get first part
-                        if info.level.get_index() >= ObPartitionLevel::One.get_index() {
-                            let part_desc: Option<ObPartDesc> = match LocationUtil::build_part_desc(
-                                ObPartitionLevel::One,
-                                row.clone(),
-                            ) {
-                                Ok(v) => v,
-                                Err(e) => {
-                                    warn!(
-                                        "LocationUtil:fetch_partition_info ObPartitionLevel::One build_part_desc,err: {:?}",
-                                        e
-                                    );
-                                    None
-                                }
-                            };
-                            match part_desc {
-                                Some(v) => info.first_part_desc = Some(v),
-                                None => warn!("fail to build first part"),
-                            };
+        for (i, row) in info_rows.iter().enumerate() {
+            if i == 0 {
+                // get part level
+                info.level = ObPartitionLevel::from_int(row.part_level);
+
+                // get first part
+                if info.level.get_index() >= ObPartitionLevel::One.get_index() {
+                    let part_desc: Option<ObPartDesc> = match LocationUtil::build_part_desc(
+                        ObPartitionLevel::One,
+                        row,
+                    ) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            warn!(
+                                    "LocationUtil:build_partition_info ObPartitionLevel::One build_part_desc,err: {:?}",
+                                    e
+                                );
+                            None
                         }
+                    };
+                    match part_desc {
+                        Some(v) => info.first_part_desc = Some(v),
+                        None => warn!("fail to build first part"),
+                    };
+                }
 
-                        // get sub part
-                        if info.level.get_index() == ObPartitionLevel::Two.get_index() {
-                            let part_desc =
-                                match LocationUtil::build_part_desc(ObPartitionLevel::Two, row.clone()) {
-                                    Ok(v) => v,
-                                    Err(e) => {
-                                        warn!(
-                                            "LocationUtil:fetch_partition_info ObPartitionLevel::Two build_part_desc,err: {:?}",
-                                            e
-                                        );
-                                        None
-                                    }
-                                };
-                            match part_desc {
-                                Some(v) => info.sub_part_desc = Some(v),
-                                None => warn!("fail to build sub part"),
-                            };
+                // get sub part
+                if info.level.get_index() == ObPartitionLevel::Two.get_index() {
+                    let part_desc = match LocationUtil::build_part_desc(ObPartitionLevel::Two, row)
+                    {
+                        Ok(v) => v,
+                        Err(e) => {
+                            warn!(
+                                "LocationUtil:build_partition_info ObPartitionLevel::Two build_part_desc,err: {:?}",
+                                e
+                            );
+                            None
                         }
-                    }
-                    let part_key_extra: String = row.take("part_key_extra").unwrap();
-                    let part_key_name: String = row.take("part_key_name").unwrap();
-                    let part_key_idx: i32 = row.take("part_key_idx").unwrap();
-                    let part_key_type: u8 = row.take("part_key_type").unwrap();
-
-                    let spare1: u8 = row.take("spare1").unwrap();
-
-                    // get part key for each loop
-                    if !part_key_extra.is_empty() {
-                        // TODO: new ObGeneratedColumnExpressParser(getPlainString(partKeyExtra)).parse());
-                        let column = ObGeneratedColumn::new(
-                            part_key_name,
-                            part_key_idx,
-                            ObjType::from_u8(part_key_type)
-                                .expect("LocationUtil::fetch_partition_info fail to decode obj type"),
-                            CollationType::from_u8(spare1)
-                                 .expect("LocationUtil::fetch_partition_info fail to decode collation type"),
-                        );
-                        info.part_columns.push(Box::new(column));
-                    } else {
-                        let column = ObSimpleColumn::new(
-                            part_key_name,
-                            part_key_idx,
-                            ObjType::from_u8(part_key_type)
-                                .expect("LocationUtil::fetch_partition_info fail to decode obj type"),
-                            CollationType::from_u8(spare1)
-                                 .expect("LocationUtil::fetch_partition_info fail to decode collation type"),
-                        );
+                    };
+                    match part_desc {
+                        Some(v) => info.sub_part_desc = Some(v),
+                        None => warn!("fail to build sub part"),
+                    };
+                }
+            }
 
-                        info.part_columns.push(Box::new(column));
-                    }
-                })?;
+            let obj_type = ObjType::from_u8(row.part_key_type)?;
+            let collation_type = CollationType::from_u8(row.spare1)?;
+            // get part key for each loop
+            if !row.part_key_extra.is_empty() {
+                // TODO: new ObGeneratedColumnExpressParser(getPlainString(partKeyExtra)).
+                // parse());
+                let column = ObGeneratedColumn::new(
+                    row.part_key_name.clone(),
+                    row.part_key_idx,
+                    obj_type,
+                    collation_type,
+                );
+                info.part_columns.push(Box::new(column));
+            } else {
+                let column = ObSimpleColumn::new(
+                    row.part_key_name.clone(),
+                    row.part_key_idx,
+                    obj_type,
+                    collation_type,
+                );
+
+                info.part_columns.push(Box::new(column));
+            }
         }
 
         // get list partition column types here
@@ -185,18 +388,24 @@ impl LocationUtil {
         Ok(info)
     }
 
-    fn build_part_desc(level: ObPartitionLevel, mut row: my::Row) -> Result<Option<ObPartDesc>> {
-        let part_level_prefix = match level {
-            ObPartitionLevel::Two => "sub_",
-            _ => "",
+    fn build_part_desc(level: ObPartitionLevel, row: &ObPartInfoRow) -> Result<Option<ObPartDesc>> {
+        let (part_type, part_expr, part_range_type, part_num, part_space) = match level {
+            ObPartitionLevel::Two => (
+                row.sub_part_type,
+                &row.sub_part_expr,
+                &row.sub_part_range_type,
+                row.sub_part_num,
+                row.sub_part_space,
+            ),
+            _ => (
+                row.part_type,
+                &row.part_expr,
+                &row.part_range_type,
+                row.part_num,
+                row.part_space,
+            ),
         };
-        let part_type = PartFuncType::from_i32(
-            row.take(&*format!("{}part_type", part_level_prefix))
-                .unwrap(),
-        );
-        let part_expr: String = row
-            .take(&*format!("{}part_expr", part_level_prefix))
-            .unwrap();
+        let part_type = PartFuncType::from_i32(part_type);
         let part_expr = part_expr.trim_matches('`');
 
         if part_type.is_range_part() {
@@ -207,14 +416,25 @@ impl LocationUtil {
                 part_expr.split(',').map(|s| s.to_string()).collect(),
             );
             let mut types: Vec<ObjType> = Vec::new();
-            let obj_type_str: String = row
-                .take(&*format!("{}part_range_type", part_level_prefix))
-                .unwrap();
-            for v in obj_type_str.split(',') {
+            for v in part_range_type.split(',') {
                 types.push(ObjType::from_u8(u8::from_str_radix(v, 10)?)?);
             }
             range_desc.set_ordered_compare_column_types(types);
             return Ok(Some(ObPartDesc::ObRangePartDesc(range_desc)));
+        } else if part_type.is_list_part() {
+            let mut list_desc = ObListPartDesc::new();
+            list_desc.set_part_func_type(part_type);
+            list_desc.set_part_expr(part_expr.to_string());
+            list_desc.set_ordered_part_column_names(
+                part_expr.split(',').map(|s| s.to_string()).collect(),
+            );
+            // the part_range_type is the types of the list columns
+            let mut types: Vec<ObjType> = Vec::new();
+            for v in part_range_type.split(',') {
+                types.push(ObjType::from_u8(u8::from_str_radix(v, 10)?)?);
+            }
+            list_desc.set_ordered_compare_column_types(types);
+            return Ok(Some(ObPartDesc::ObListPartDesc(list_desc)));
         } else if part_type.is_hash_part() {
             let mut hash_desc = ObHashPartDesc::new();
             hash_desc.set_part_func_type(part_type);
@@ -223,10 +443,7 @@ impl LocationUtil {
                 part_expr.split(',').map(|s| s.to_string()).collect(),
             );
 
-            hash_desc.set_part_num(
-                row.take(&*format!("{}part_num", part_level_prefix))
-                    .unwrap(),
-            );
+            hash_desc.set_part_num(part_num);
             // in java sdk, complete_works is implemented in setPartNum()
             let mut complete_works = Vec::new();
             for i in 0..hash_desc.get_part_num() {
@@ -235,10 +452,7 @@ impl LocationUtil {
 
             hash_desc.set_complete_works(complete_works);
 
-            hash_desc.set_part_space(
-                row.take(&*format!("{}part_space", part_level_prefix))
-                    .unwrap(),
-            );
+            hash_desc.set_part_space(part_space);
 
             hash_desc.set_part_name_id_map(LocationUtil::build_default_part_name_id_map(
                 hash_desc.get_part_num(),
@@ -251,14 +465,8 @@ impl LocationUtil {
             key_part_desc.set_ordered_part_column_names(
                 part_expr.split(',').map(|s| s.to_string()).collect(),
             );
-            key_part_desc.set_part_num(
-                row.take(&*format!("{}part_num", part_level_prefix))
-                    .unwrap(),
-            );
-            key_part_desc.set_part_space(
-                row.take(&*format!("{}part_space", part_level_prefix))
-                    .unwrap(),
-            );
+            key_part_desc.set_part_num(part_num);
+            key_part_desc.set_part_space(part_space);
             key_part_desc.set_part_name_id_map(LocationUtil::build_default_part_name_id_map(
                 key_part_desc.get_part_num(),
             ));
@@ -303,11 +511,7 @@ impl LocationUtil {
                         "LocationUtil::set_part_desc_property key part desc is empty".to_owned(),
                     ));
                 }
-            } else if v.is_list_part() {
-                // TODO: impl list part
-                unimplemented!();
-            // v.set_ordered_compare_columns((*part_columns).clone());
-            } else if v.is_range_part() {
+            } else if v.is_list_part() || v.is_range_part() {
                 v.set_ordered_compare_columns(list_part_columns.to_vec());
             }
         }
@@ -345,102 +549,318 @@ impl LocationUtil {
         part_name_map
     }
 
-    // TODO: impl list part
-    pub fn fetch_first_part(
+    /// Fetch the rows of the first level partitions, which carry the tablets
+    /// of the partitions on OceanBase 4.x.
+    fn fetch_first_part_rows(
         conn: &mut my::PooledConn,
-        table_entry: &mut TableEntry,
-        ob_part_func_type: PartFuncType,
-    ) -> Result<()> {
-        let sql = format!(
-            "SELECT /*+READ_CONSISTENCY(WEAK)*/ part_id, part_name, high_bound_val
+        table_entry: &TableEntry,
+        key: &TableEntryKey,
+        ob_version: ObVersion,
+    ) -> Result<Vec<ObPartRow>> {
+        let sql = if ob_version.is_4x() {
+            format!(
+                "SELECT /*+READ_CONSISTENCY(WEAK)*/ part_id, part_name, tablet_id, high_bound_val, list_val
+                               FROM oceanbase.__all_virtual_proxy_partition
+                               WHERE {}table_id = {} ORDER BY part_id LIMIT {};",
+                LocationUtil::tenant_condition(key, ob_version),
+                table_entry.table_id,
+                i32::MAX
+            )
+        } else {
+            format!(
+                "SELECT /*+READ_CONSISTENCY(WEAK)*/ part_id, part_name, high_bound_val, list_val
                                FROM oceanbase.__all_virtual_proxy_partition
                                WHERE table_id = {} LIMIT {};",
-            table_entry.table_id,
-            i32::MAX
+                table_entry.table_id,
+                i32::MAX
+            )
+        };
+        let mut part_rows = Vec::new();
+        for result in conn.query(sql)? {
+            part_rows.push(ObPartRow::from_row(result?, "part_id"));
+        }
+        Ok(part_rows)
+    }
+
+    /// Fetch the rows of the columns of the table in the order of definition.
+    pub fn fetch_column_rows(
+        conn: &mut my::PooledConn,
+        table_entry: &TableEntry,
+        key: &TableEntryKey,
+        ob_version: ObVersion,
+    ) -> Result<Vec<ObColumnRow>> {
+        let tenant_condition = if ob_version.is_4x() {
+            format!(
+                "tenant_id = (SELECT tenant_id FROM oceanbase.__all_tenant WHERE tenant_name = '{}') and ",
+                key.tenant_name
+            )
+        } else {
+            String::new()
+        };
+        let sql = format!(
+            "SELECT /*+READ_CONSISTENCY(WEAK)*/ column_name, rowkey_position, data_type,
+                collation_type, data_length, nullable, is_hidden, cur_default_value
+                FROM oceanbase.__all_virtual_column
+                WHERE {}table_id = {} ORDER BY column_id;",
+            tenant_condition, table_entry.table_id
         );
-        if ob_part_func_type.is_range_part() {
-            // in java sdk is parseFirstPartRange
-            let bounds = LocationUtil::parse_range_part(conn, sql, table_entry, false)?;
-            match &mut table_entry.partition_info {
-                None => {
-                    error!("LocationUtil::fetch_first_part partition_info is None");
-                    return Err(CommonErr(
-                        CommonErrCode::PartitionError,
-                        "LocationUtil:fetch_first_part partition_info is None".to_owned(),
+        let mut column_rows = Vec::new();
+        for result in conn.query(sql)? {
+            column_rows.push(ObColumnRow::from_row(result?));
+        }
+        Ok(column_rows)
+    }
+
+    /// Fetch the primary key columns of the table in order, which the row keys
+    /// of the requests are made of.
+    fn fetch_row_key_columns(
+        conn: &mut my::PooledConn,
+        table_entry: &TableEntry,
+        key: &TableEntryKey,
+        ob_version: ObVersion,
+    ) -> Result<Vec<String>> {
+        let column_rows = LocationUtil::fetch_column_rows(conn, table_entry, key, ob_version)?;
+        Ok(LocationUtil::row_key_columns(&column_rows))
+    }
+
+    fn row_key_columns(column_rows: &[ObColumnRow]) -> Vec<String> {
+        let mut row_key_rows: Vec<&ObColumnRow> = column_rows
+            .iter()
+            .filter(|row| row.rowkey_position > 0)
+            .collect();
+        row_key_rows.sort_by_key(|row| row.rowkey_position);
+        row_key_rows
+            .into_iter()
+            .map(|row| row.column_name.to_owned())
+            .collect()
+    }
+
+    /// Build the schema of the table from the rows of the columns and the rows
+    /// the partition info is built from.
+    pub fn build_table_schema(
+        table_name: &str,
+        table_entry: &TableEntry,
+        column_rows: &[ObColumnRow],
+    ) -> TableSchema {
+        let columns = column_rows
+            .iter()
+            .filter(|row| !row.is_hidden)
+            .map(|row| ColumnSchema {
+                name: row.column_name.to_owned(),
+                obj_type: ObjType::from_u8(row.data_type).ok(),
+                collation_type: CollationType::from_u8(row.collation_type).ok(),
+                data_length: row.data_length,
+                nullable: row.nullable,
+                default_value: row.cur_default_value.clone(),
+                row_key_position: if row.rowkey_position > 0 {
+                    Some(row.rowkey_position as usize)
+                } else {
+                    None
+                },
+            })
+            .collect();
+
+        let mut partition = PartitionSchema {
+            level: ObPartitionLevel::Zero,
+            part_num: table_entry.partition_num,
+            first_part: None,
+            sub_part: None,
+        };
+        if let Some(info) = &table_entry.partition_info {
+            let rows = &info.rows;
+            partition.level = info.level.clone();
+            if let Some(row) = rows.info_rows.first() {
+                if partition.level.get_index() >= ObPartitionLevel::One.get_index() {
+                    partition.first_part = Some(LocationUtil::build_partition_desc(
+                        row.part_type,
+                        &row.part_expr,
+                        row.part_num,
+                        &rows.first_part_rows,
                     ));
                 }
-                Some(info) => match &mut info.first_part_desc {
+                if partition.level.get_index() == ObPartitionLevel::Two.get_index() {
+                    partition.sub_part = Some(LocationUtil::build_partition_desc(
+                        row.sub_part_type,
+                        &row.sub_part_expr,
+                        row.sub_part_num,
+                        &rows
+                            .sub_part_rows
+                            .as_deref()
+                            .map(|rows| LocationUtil::sub_part_template(rows).to_vec()),
+                    ));
+                }
+            }
+        }
+
+        TableSchema {
+            table_name: table_name.to_owned(),
+            table_id: table_entry.table_id,
+            columns,
+            row_key_columns: LocationUtil::row_key_columns(column_rows),
+            partition,
+        }
+    }
+
+    fn build_partition_desc(
+        part_type: i32,
+        expr: &str,
+        part_num: i32,
+        part_rows: &Option<Vec<ObPartRow>>,
+    ) -> PartitionDesc {
+        let func_type = PartFuncType::from_i32(part_type);
+        let bounds = match part_rows {
+            Some(part_rows) if func_type.is_range_part() || func_type.is_list_part() => part_rows
+                .iter()
+                .map(|row| PartitionBound {
+                    part_id: row.part_id,
+                    part_name: row.part_name.to_owned(),
+                    value: if func_type.is_range_part() {
+                        row.high_bound_val.to_owned()
+                    } else {
+                        row.list_val.to_owned()
+                    },
+                })
+                .collect(),
+            _ => vec![],
+        };
+        PartitionDesc {
+            func_type,
+            expr: expr.to_owned(),
+            part_num,
+            bounds,
+        }
+    }
+
+    fn fetch_sub_part_rows(
+        conn: &mut my::PooledConn,
+        table_entry: &TableEntry,
+        key: &TableEntryKey,
+        ob_version: ObVersion,
+    ) -> Result<Vec<ObPartRow>> {
+        // all the sub partitions have their own tablets on OceanBase 4.x,
+        // otherwise only the template is fetched
+        let sql = if ob_version.is_4x() {
+            format!(
+                "SELECT /*+READ_CONSISTENCY(WEAK)*/ part_id, sub_part_id, part_name, tablet_id, high_bound_val, list_val
+                    FROM oceanbase.__all_virtual_proxy_sub_partition
+                    WHERE {}table_id = {} ORDER BY part_id, sub_part_id LIMIT {};",
+                LocationUtil::tenant_condition(key, ob_version),
+                table_entry.table_id,
+                i32::MAX
+            )
+        } else {
+            format!(
+                "SELECT /*+READ_CONSISTENCY(WEAK)*/ sub_part_id, part_name, high_bound_val, list_val
+                    FROM oceanbase.__all_virtual_proxy_sub_partition
+                    WHERE {}table_id = {} and part_id = {} LIMIT {};",
+                LocationUtil::tenant_condition(key, ob_version),
+                table_entry.table_id,
+                TEMPLATE_PART_ID,
+                i32::MAX
+            )
+        };
+        let mut part_rows = Vec::new();
+        for result in conn.query(sql)? {
+            part_rows.push(ObPartRow::from_row(result?, "sub_part_id"));
+        }
+        Ok(part_rows)
+    }
+
+    /// Set the bounds of the first level range or list partitions, and the
+    /// tablets of all the first level partitions on OceanBase 4.x.
+    fn set_first_part(table_entry: &mut TableEntry, part_rows: &[ObPartRow]) -> Result<()> {
+        let ob_part_func_type = match &table_entry.partition_info {
+            Some(ObPartitionInfo {
+                first_part_desc: Some(part_desc),
+                ..
+            }) => part_desc.get_part_func_type(),
+            _ => {
+                error!("LocationUtil::set_first_part first_part_desc is None");
+                return Err(CommonErr(
+                    CommonErrCode::PartitionError,
+                    "LocationUtil:set_first_part first_part_desc is None".to_owned(),
+                ));
+            }
+        };
+        if ob_part_func_type.is_range_part() {
+            // in java sdk is parseFirstPartRange
+            let bounds = LocationUtil::parse_range_part(part_rows, table_entry, false)?;
+            if let Some(info) = &mut table_entry.partition_info {
+                match &mut info.first_part_desc {
                     Some(ObPartDesc::ObRangePartDesc(v)) => v.set_bounds(bounds),
-                    _ => error!("LocationUtil::fetch_first_part never be here"),
-                },
+                    _ => error!("LocationUtil::set_first_part never be here"),
+                }
             }
         } else if ob_part_func_type.is_list_part() {
-            // TODO: impl list part
-            unimplemented!();
-            //            conn.query(sql).map(|result| {
-            //                result
-            //                    .map(|x| x.unwrap())
-            //                    .map(|mut row| {})
-            //                    .collect()
-            //            })?;
+            let (sets, default_part_id) =
+                LocationUtil::parse_list_part(part_rows, table_entry, false)?;
+            if let Some(info) = &mut table_entry.partition_info {
+                match &mut info.first_part_desc {
+                    Some(ObPartDesc::ObListPartDesc(v)) => {
+                        v.set_sets(sets);
+                        v.set_default_part_id(default_part_id);
+                    }
+                    _ => error!("LocationUtil::set_first_part never be here"),
+                }
+            }
+        } else {
+            let mut tablet_ids = Vec::new();
+            for row in part_rows {
+                LocationUtil::take_part_id(row, &mut tablet_ids);
+            }
+            LocationUtil::set_part_tablet_ids(table_entry, tablet_ids);
         }
         Ok(())
     }
 
-    // TODO: impl list part
-    pub fn fetch_sub_part(
-        conn: &mut my::PooledConn,
-        table_entry: &mut TableEntry,
-        sub_part_func_type: PartFuncType,
-    ) -> Result<()> {
-        let sql = format!(
-            "SELECT /*+READ_CONSISTENCY(WEAK)*/ sub_part_id, part_name, high_bound_val
-                FROM oceanbase.__all_virtual_proxy_sub_partition
-                WHERE table_id = {} and part_id = {} LIMIT {};",
-            table_entry.table_id,
-            TEMPLATE_PART_ID,
-            i32::MAX
-        );
+    /// Set the bounds of the sub level range or list partitions by the
+    /// template, and the tablets of all the sub partitions on OceanBase 4.x.
+    fn set_sub_part(table_entry: &mut TableEntry, part_rows: &[ObPartRow]) -> Result<()> {
+        LocationUtil::set_sub_part_tablet_ids(table_entry, part_rows);
+        let part_rows = LocationUtil::sub_part_template(part_rows);
+        let sub_part_func_type = match &table_entry.partition_info {
+            Some(ObPartitionInfo {
+                sub_part_desc: Some(part_desc),
+                ..
+            }) => part_desc.get_part_func_type(),
+            _ => {
+                error!("LocationUtil::set_sub_part sub_part_desc is None");
+                return Err(CommonErr(
+                    CommonErrCode::PartitionError,
+                    "LocationUtil::set_sub_part sub_part_desc is None".to_owned(),
+                ));
+            }
+        };
         if sub_part_func_type.is_range_part() {
             // in java sdk is parseFirstPartRange
-            let bounds = LocationUtil::parse_range_part(conn, sql, table_entry, true)?;
-            match &mut table_entry.partition_info {
-                None => {
-                    error!("LocationUtil::fetch_sub_part partition_info is None");
-                    return Err(CommonErr(
-                        CommonErrCode::PartitionError,
-                        "LocationUtil::fetch_sub_part partition_info is None".to_owned(),
-                    ));
-                }
-                Some(info) => match &mut info.sub_part_desc {
+            let bounds = LocationUtil::parse_range_part(part_rows, table_entry, true)?;
+            if let Some(info) = &mut table_entry.partition_info {
+                match &mut info.sub_part_desc {
                     Some(ObPartDesc::ObRangePartDesc(v)) => v.set_bounds(bounds),
-                    _ => error!("LocationUtil::fetch_sub_part never be here"),
-                },
+                    _ => error!("LocationUtil::set_sub_part never be here"),
+                }
             }
         } else if sub_part_func_type.is_list_part() {
-            // TODO: impl list part
-            unimplemented!();
-            //            conn.query(sql).map(|result| {
-            //                result
-            //                    .map(|x| x.unwrap())
-            //                    .map(|mut row| {})
-            //                    .collect()
-            //            })?;
+            let (sets, default_part_id) =
+                LocationUtil::parse_list_part(part_rows, table_entry, true)?;
+            if let Some(info) = &mut table_entry.partition_info {
+                match &mut info.sub_part_desc {
+                    Some(ObPartDesc::ObListPartDesc(v)) => {
+                        v.set_sets(sets);
+                        v.set_default_part_id(default_part_id);
+                    }
+                    _ => error!("LocationUtil::set_sub_part never be here"),
+                }
+            }
         }
         Ok(())
     }
 
     fn parse_range_part(
-        conn: &mut my::PooledConn,
-        sql: String,
+        part_rows: &[ObPartRow],
         table_entry: &mut TableEntry,
         is_sub_part: bool,
     ) -> Result<Vec<(ObPartitionKey, i64)>> {
-        let part_id_column_name = if is_sub_part {
-            "sub_part_id"
-        } else {
-            "part_id"
-        };
         let mut part_desc = &mut None;
         if let Some(info) = &mut table_entry.partition_info {
             if !is_sub_part {
@@ -453,6 +873,7 @@ impl LocationUtil {
         let mut order_part_columns: &Vec<Box<dyn ObColumn>> = &Vec::new();
         let mut bounds: Vec<(ObPartitionKey, i64)> = Vec::new();
         let mut part_name_id_map: HashMap<String, i64> = HashMap::new();
+        let mut tablet_ids = Vec::new();
 
         if let Some(part_desc) = part_desc {
             match part_desc {
@@ -463,50 +884,245 @@ impl LocationUtil {
             }
         }
 
-        for result in conn.query(sql)? {
-            result.map(|mut row| {
-                let high_bound_val: String = row.take("high_bound_val").unwrap();
-                let splits: Vec<String> =
-                    high_bound_val.split(',').map(|s| s.to_string()).collect();
-                let mut part_elements: Vec<Comparable> = Vec::new();
-                for i in 0..splits.len() {
-                    let element_str = LocationUtil::get_plain_string(&splits[i]);
-                    if element_str.eq_ignore_ascii_case("MAXVALUE") {
-                        part_elements.push(Comparable::MAXVALUE);
-                    } else if element_str.eq_ignore_ascii_case("MINVALUE") {
-                        part_elements.push(Comparable::MINVALUE);
-                    } else {
-                        part_elements.push(Comparable::Value(
-                            order_part_columns[i]
-                                .eval_value(&[Value::String(
-                                    element_str,
-                                    ObjMeta::new(
-                                        order_part_columns[i].get_ob_obj_type().clone(),
-                                        CollationLevel::Numeric,
-                                        order_part_columns[i].get_ob_collation_type().clone(),
-                                        10,
-                                    ),
-                                )])
-                                .unwrap(),
-                        ));
-                    }
+        for row in part_rows {
+            let splits: Vec<String> = row
+                .high_bound_val
+                .split(',')
+                .map(|s| s.to_string())
+                .collect();
+            let mut part_elements: Vec<Comparable> = Vec::new();
+            for i in 0..splits.len() {
+                let element_str = LocationUtil::get_plain_string(&splits[i]);
+                if element_str.eq_ignore_ascii_case("MAXVALUE") {
+                    part_elements.push(Comparable::MAXVALUE);
+                } else if element_str.eq_ignore_ascii_case("MINVALUE") {
+                    part_elements.push(Comparable::MINVALUE);
+                } else {
+                    part_elements.push(Comparable::Value(
+                        order_part_columns[i]
+                            .eval_value(&[Value::String(
+                                element_str,
+                                ObjMeta::new(
+                                    order_part_columns[i].get_ob_obj_type().clone(),
+                                    CollationLevel::Numeric,
+                                    order_part_columns[i].get_ob_collation_type().clone(),
+                                    10,
+                                ),
+                            )])
+                            .unwrap(),
+                    ));
                 }
-                let ob_partition_key = ObPartitionKey::new(part_elements);
-                let part_id: i64 = row.take(part_id_column_name).unwrap();
-                let part_name: String = row.take("part_name").unwrap();
-                bounds.push((ob_partition_key, part_id));
-                part_name_id_map.insert(part_name.to_lowercase(), part_id);
-            })?;
+            }
+            let ob_partition_key = ObPartitionKey::new(part_elements);
+            let part_id = LocationUtil::take_part_id(row, &mut tablet_ids);
+            bounds.push((ob_partition_key, part_id));
+            part_name_id_map.insert(row.part_name.to_lowercase(), part_id);
         }
 
         if let Some(part_desc) = part_desc {
             part_desc.set_part_name_id_map(part_name_id_map);
         }
+        if !is_sub_part {
+            LocationUtil::set_part_tablet_ids(table_entry, tablet_ids);
+        }
         // TODO: check a b partition_elements
         bounds.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
         Ok(bounds)
     }
 
+    /// Parse the list partitions into the sets of the partition keys and the
+    /// id of the DEFAULT partition.
+    fn parse_list_part(
+        part_rows: &[ObPartRow],
+        table_entry: &mut TableEntry,
+        is_sub_part: bool,
+    ) -> Result<(Vec<(ObPartitionKey, i64)>, Option<i64>)> {
+        let mut part_desc = &mut None;
+        if let Some(info) = &mut table_entry.partition_info {
+            if !is_sub_part {
+                part_desc = &mut info.first_part_desc;
+            } else {
+                part_desc = &mut info.sub_part_desc;
+            }
+        }
+
+        let mut order_part_columns: &Vec<Box<dyn ObColumn>> = &Vec::new();
+        let mut sets: Vec<(ObPartitionKey, i64)> = Vec::new();
+        let mut default_part_id = None;
+        let mut part_name_id_map: HashMap<String, i64> = HashMap::new();
+        let mut tablet_ids = Vec::new();
+
+        if let Some(part_desc) = part_desc {
+            match part_desc {
+                ObPartDesc::ObListPartDesc(v) => {
+                    order_part_columns = v.get_ordered_compare_column()
+                }
+                _ => error!("LocationUtil::parse_list_part never be here"),
+            }
+        }
+
+        for row in part_rows {
+            let list_val = &row.list_val;
+            let part_id = LocationUtil::take_part_id(row, &mut tablet_ids);
+            part_name_id_map.insert(row.part_name.to_lowercase(), part_id);
+
+            for set in LocationUtil::split_list_val(list_val) {
+                // the keyword is unquoted, while `'default'` is a string value
+                if set.len() == 1 && set[0].eq_ignore_ascii_case("DEFAULT") {
+                    default_part_id = Some(part_id);
+                    continue;
+                }
+                if set.len() != order_part_columns.len() {
+                    error!(
+                        "LocationUtil::parse_list_part invalid list value:{}, columns:{:?}",
+                        list_val, order_part_columns
+                    );
+                    return Err(CommonErr(
+                        CommonErrCode::PartitionError,
+                        format!(
+                            "LocationUtil::parse_list_part invalid list value:{}, columns:{:?}",
+                            list_val, order_part_columns
+                        ),
+                    ));
+                }
+                let mut part_elements: Vec<Comparable> = Vec::with_capacity(set.len());
+                for (i, element_str) in set.into_iter().enumerate() {
+                    let obj_type = order_part_columns[i].get_ob_obj_type();
+                    part_elements.push(Comparable::Value(order_part_columns[i].eval_value(&[
+                        Value::String(
+                            LocationUtil::get_plain_string(&element_str),
+                            ObjMeta::default_obj_meta(obj_type),
+                        ),
+                    ])?));
+                }
+                sets.push((ObPartitionKey::new(part_elements), part_id));
+            }
+        }
+
+        if let Some(part_desc) = part_desc {
+            part_desc.set_part_name_id_map(part_name_id_map);
+        }
+        if !is_sub_part {
+            LocationUtil::set_part_tablet_ids(table_entry, tablet_ids);
+        }
+        Ok((sets, default_part_id))
+    }
+
+    /// The condition on the tenant of the partition views, the table ids are
+    /// unique in the tenant only on OceanBase 4.x.
+    fn tenant_condition(key: &TableEntryKey, ob_version: ObVersion) -> String {
+        if ob_version.is_4x() {
+            format!("tenant_name = '{}' and ", key.tenant_name)
+        } else {
+            String::new()
+        }
+    }
+
+    /// Take the id of the partition from the row. The partitions are numbered
+    /// by their order and their tablets are collected on OceanBase 4.x, where
+    /// the rows carry the tablet ids.
+    fn take_part_id(row: &ObPartRow, tablet_ids: &mut Vec<i64>) -> i64 {
+        match row.tablet_id {
+            Some(tablet_id) => {
+                tablet_ids.push(tablet_id);
+                tablet_ids.len() as i64 - 1
+            }
+            None => row.part_id,
+        }
+    }
+
+    /// The sub partitions of the first partition, which are the template of
+    /// the sub partitions of all the partitions.
+    fn sub_part_template(part_rows: &[ObPartRow]) -> &[ObPartRow] {
+        let first_part_id = match part_rows.first() {
+            Some(row) if row.first_part_id.is_some() => row.first_part_id,
+            _ => return part_rows,
+        };
+        let len = part_rows
+            .iter()
+            .take_while(|row| row.first_part_id == first_part_id)
+            .count();
+        &part_rows[..len]
+    }
+
+    /// Map the physical ids of the sub partitions to their tablets, the
+    /// partitions and the sub partitions are numbered by their order like the
+    /// first level partitions.
+    fn set_sub_part_tablet_ids(table_entry: &mut TableEntry, part_rows: &[ObPartRow]) {
+        let mut tablet_ids = HashMap::new();
+        let mut last_first_part_id = None;
+        let (mut part_idx, mut sub_part_idx) = (-1, 0);
+        for row in part_rows {
+            let (first_part_id, tablet_id) = match (row.first_part_id, row.tablet_id) {
+                (Some(first_part_id), Some(tablet_id)) => (first_part_id, tablet_id),
+                _ => continue,
+            };
+            if last_first_part_id != Some(first_part_id) {
+                last_first_part_id = Some(first_part_id);
+                part_idx += 1;
+                sub_part_idx = 0;
+            }
+            if let Some(part_id) =
+                ObPartIdCalculator::generate_part_id(Some(part_idx), Some(sub_part_idx))
+            {
+                tablet_ids.insert(part_id, tablet_id);
+            }
+            sub_part_idx += 1;
+        }
+        if tablet_ids.is_empty() {
+            return;
+        }
+        if let Some(info) = &mut table_entry.partition_info {
+            info.sub_part_tablet_ids = tablet_ids;
+        }
+    }
+
+    fn set_part_tablet_ids(table_entry: &mut TableEntry, tablet_ids: Vec<i64>) {
+        if tablet_ids.is_empty() {
+            return;
+        }
+        if let Some(info) = &mut table_entry.partition_info {
+            info.part_tablet_ids = tablet_ids;
+        }
+    }
+
+    /// Split the list values of a partition like `1,2` or `(1,'a'),(2,'b')`
+    /// into the sets of the values, and the quotes of the strings are kept.
+    fn split_list_val(list_val: &str) -> Vec<Vec<String>> {
+        let mut sets = Vec::new();
+        let mut set = Vec::new();
+        let mut element = String::new();
+        let mut in_quote = false;
+        let mut in_paren = false;
+        for c in list_val.chars() {
+            match c {
+                '\'' => {
+                    in_quote = !in_quote;
+                    element.push(c);
+                }
+                '(' if !in_quote => in_paren = true,
+                ')' if !in_quote => in_paren = false,
+                ',' if !in_quote => {
+                    if !element.trim().is_empty() {
+                        set.push(element.trim().to_owned());
+                    }
+                    element.clear();
+                    if !in_paren && !set.is_empty() {
+                        sets.push(std::mem::take(&mut set));
+                    }
+                }
+                _ => element.push(c),
+            }
+        }
+        if !element.trim().is_empty() {
+            set.push(element.trim().to_owned());
+        }
+        if !set.is_empty() {
+            sets.push(set);
+        }
+        sets
+    }
+
     fn get_plain_string(s: &str) -> String {
         let start = if !s.is_empty() && s[..1] == '\''.to_string() {
             1
@@ -539,3 +1155,281 @@ impl ObPartIdCalculator {
         None
     }
 }
+
+/// A hash partitioned table by `c1` on OceanBase 4.x, whose partitions are
+/// on the tablets from 200001 and led by the server, to route the requests
+/// without the cluster in the tests.
+#[cfg(test)]
+pub(crate) fn new_hash_table_entry(part_num: i64, leader: &super::ObServerAddr) -> TableEntry {
+    use std::sync::{atomic::AtomicUsize, Arc};
+
+    use super::{
+        ObPartitionEntry, ObPartitionLocation, ObServerInfo, ObServerRole, ObServerStatus,
+        ReplicaLocation, TableLocation,
+    };
+    use crate::util::current_time_millis;
+
+    let mut table_entry = TableEntry {
+        table_id: 500_002,
+        tablet_id: -1,
+        partition_num: part_num,
+        refresh_time_mills: Arc::new(AtomicUsize::new(current_time_millis() as usize)),
+        partition_info: None,
+        table_location: TableLocation {
+            replica_locations: vec![],
+        },
+        partition_entry: None,
+        row_key_element: HashMap::new(),
+    };
+    let rows = ObPartitionRows {
+        info_rows: vec![ObPartInfoRow {
+            part_level: 1,
+            part_num: part_num as i32,
+            part_type: PartFuncType::HASH as i32,
+            part_expr: "c1".to_owned(),
+            part_key_name: "c1".to_owned(),
+            part_key_type: ObjType::Int64 as u8,
+            spare1: CollationType::Binary as u8,
+            ..Default::default()
+        }],
+        first_part_rows: Some(
+            (0..part_num)
+                .map(|i| ObPartRow {
+                    part_id: i,
+                    part_name: format!("p{}", i),
+                    tablet_id: Some(200_001 + i),
+                    ..Default::default()
+                })
+                .collect(),
+        ),
+        sub_part_rows: None,
+        row_key_columns: vec!["c1".to_owned()],
+    };
+    LocationUtil::build_partition_info_with_rows(&mut table_entry, rows)
+        .expect("fail to build partition info");
+    table_entry.prepare().expect("fail to prepare table entry");
+
+    let parititon_location = (0..part_num)
+        .map(|i| {
+            let leader = ReplicaLocation {
+                addr: leader.clone(),
+                info: ObServerInfo {
+                    stop_time: 0,
+                    status: ObServerStatus::Active,
+                },
+                role: ObServerRole::Leader,
+            };
+            let location = ObPartitionLocation {
+                leader: Some(leader),
+                followers: vec![],
+                tablet_id: 200_001 + i,
+                ls_id: 1_001,
+            };
+            (i, location)
+        })
+        .collect();
+    table_entry.set_partition_entry(Some(ObPartitionEntry { parititon_location }));
+    table_entry
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{atomic::AtomicUsize, Arc};
+
+    use super::*;
+    use crate::location::{ObPartitionEntry, ObPartitionLocation, TableLocation};
+
+    #[test]
+    fn test_split_list_val() {
+        assert_eq!(
+            vec![vec!["1".to_owned()], vec!["2".to_owned()]],
+            LocationUtil::split_list_val("1,2")
+        );
+        assert_eq!(
+            vec![
+                vec!["1".to_owned(), "'a,b'".to_owned()],
+                vec!["2".to_owned(), "'c'".to_owned()]
+            ],
+            LocationUtil::split_list_val("(1,'a,b'),(2,'c')")
+        );
+        assert_eq!(
+            vec![vec!["default".to_owned()]],
+            LocationUtil::split_list_val("default")
+        );
+        assert_eq!(
+            vec![vec!["'default'".to_owned()], vec!["DEFAULT".to_owned()]],
+            LocationUtil::split_list_val("'default',DEFAULT")
+        );
+    }
+
+    #[test]
+    fn test_build_partition_info_with_rows() {
+        let mut table_entry = TableEntry {
+            table_id: 1099511677777,
+            tablet_id: -1,
+            partition_num: 2,
+            refresh_time_mills: Arc::new(AtomicUsize::new(0)),
+            partition_info: None,
+            table_location: TableLocation {
+                replica_locations: vec![],
+            },
+            partition_entry: None,
+            row_key_element: HashMap::new(),
+        };
+        let rows = ObPartitionRows {
+            info_rows: vec![ObPartInfoRow {
+                part_level: 1,
+                part_num: 2,
+                part_type: PartFuncType::RANGE as i32,
+                part_expr: "c1".to_owned(),
+                part_range_type: (ObjType::Int64 as u8).to_string(),
+                part_key_name: "c1".to_owned(),
+                part_key_type: ObjType::Int64 as u8,
+                spare1: CollationType::Binary as u8,
+                ..Default::default()
+            }],
+            first_part_rows: Some(vec![
+                ObPartRow {
+                    part_id: 0,
+                    part_name: "p0".to_owned(),
+                    high_bound_val: "100".to_owned(),
+                    ..Default::default()
+                },
+                ObPartRow {
+                    part_id: 1,
+                    part_name: "p1".to_owned(),
+                    high_bound_val: "MAXVALUE".to_owned(),
+                    ..Default::default()
+                },
+            ]),
+            sub_part_rows: None,
+            row_key_columns: vec!["c1".to_owned(), "c2".to_owned()],
+        };
+
+        LocationUtil::build_partition_info_with_rows(&mut table_entry, rows.clone())
+            .expect("fail to build partition info");
+        assert_eq!(Some(&1), table_entry.row_key_element().get("c2"));
+        let mut row_key_element = HashMap::new();
+        row_key_element.insert("c1".to_owned(), 0);
+        table_entry.set_row_key_element(row_key_element);
+        table_entry.prepare().expect("fail to prepare table entry");
+
+        let column_rows = vec![
+            ObColumnRow {
+                column_name: "c1".to_owned(),
+                rowkey_position: 1,
+                data_type: ObjType::Int64 as u8,
+                collation_type: CollationType::Binary as u8,
+                ..Default::default()
+            },
+            ObColumnRow {
+                column_name: "c2".to_owned(),
+                data_type: ObjType::Varchar as u8,
+                collation_type: CollationType::UTF8MB4GeneralCi as u8,
+                data_length: 20,
+                nullable: true,
+                ..Default::default()
+            },
+        ];
+        let schema = LocationUtil::build_table_schema("t", &table_entry, &column_rows);
+        assert_eq!(vec!["c1".to_owned()], schema.row_key_columns);
+        assert_eq!(
+            Some(ObjType::Varchar),
+            schema.column("C2").unwrap().obj_type
+        );
+        assert_eq!(ObPartitionLevel::One, schema.partition.level);
+        let first_part = schema.partition.first_part.unwrap();
+        assert_eq!(PartFuncType::RANGE, first_part.func_type);
+        assert_eq!("MAXVALUE", first_part.bounds[1].value);
+
+        let info = table_entry.partition_info().as_ref().unwrap();
+        assert_eq!(rows, info.rows);
+        assert_eq!(Some(&1), info.part_name_id_map.get("p1"));
+        let part_desc = info.first_part_desc().as_ref().unwrap();
+        assert_eq!(0, part_desc.get_part_id(&[Value::from(50i64)]).unwrap());
+        assert_eq!(1, part_desc.get_part_id(&[Value::from(200i64)]).unwrap());
+    }
+
+    #[test]
+    fn test_sub_part_tablet_ids() {
+        let mut table_entry = TableEntry {
+            table_id: 500_002,
+            tablet_id: -1,
+            partition_num: 4,
+            refresh_time_mills: Arc::new(AtomicUsize::new(0)),
+            partition_info: None,
+            table_location: TableLocation {
+                replica_locations: vec![],
+            },
+            partition_entry: None,
+            row_key_element: HashMap::new(),
+        };
+        let sub_part_row = |first_part_id: i64, part_id: i64, tablet_id: i64| ObPartRow {
+            part_id,
+            part_name: format!("p{}sp{}", first_part_id, part_id),
+            tablet_id: Some(tablet_id),
+            first_part_id: Some(first_part_id),
+            ..Default::default()
+        };
+        let rows = ObPartitionRows {
+            info_rows: vec![ObPartInfoRow {
+                part_level: 2,
+                part_num: 2,
+                part_type: PartFuncType::HASH as i32,
+                part_expr: "c1".to_owned(),
+                sub_part_num: 2,
+                sub_part_type: PartFuncType::HASH as i32,
+                sub_part_expr: "c2".to_owned(),
+                part_key_name: "c1".to_owned(),
+                part_key_type: ObjType::Int64 as u8,
+                spare1: CollationType::Binary as u8,
+                ..Default::default()
+            }],
+            first_part_rows: None,
+            // the ids of the partitions are not numbered from 0 on OceanBase 4.x
+            sub_part_rows: Some(vec![
+                sub_part_row(500_003, 500_005, 200_001),
+                sub_part_row(500_003, 500_006, 200_002),
+                sub_part_row(500_004, 500_007, 200_003),
+                sub_part_row(500_004, 500_008, 200_004),
+            ]),
+            row_key_columns: vec!["c1".to_owned(), "c2".to_owned()],
+        };
+
+        LocationUtil::build_partition_info_with_rows(&mut table_entry, rows)
+            .expect("fail to build partition info");
+        let info = table_entry.partition_info().as_ref().unwrap();
+        assert_eq!(ObPartitionLevel::Two, info.level());
+        let part_id = |part: i64, sub_part: i64| {
+            ObPartIdCalculator::generate_part_id(Some(part), Some(sub_part)).unwrap()
+        };
+        assert_eq!(Some(200_001), info.part_tablet_id(part_id(0, 0)));
+        assert_eq!(Some(200_002), info.part_tablet_id(part_id(0, 1)));
+        assert_eq!(Some(200_003), info.part_tablet_id(part_id(1, 0)));
+        assert_eq!(Some(200_004), info.part_tablet_id(part_id(1, 1)));
+        assert_eq!(None, info.part_tablet_id(1));
+        assert_eq!(
+            vec![part_id(0, 0), part_id(0, 1), part_id(1, 0), part_id(1, 1)],
+            info.tablet_part_ids()
+        );
+        assert_eq!(
+            2,
+            LocationUtil::sub_part_template(&info.rows.sub_part_rows.clone().unwrap()).len()
+        );
+
+        // route by the tablet of the sub partition located
+        let mut parititon_location = HashMap::new();
+        parititon_location.insert(
+            part_id(1, 0),
+            ObPartitionLocation {
+                leader: None,
+                followers: vec![],
+                tablet_id: 200_003,
+                ls_id: 1_001,
+            },
+        );
+        table_entry.set_partition_entry(Some(ObPartitionEntry { parititon_location }));
+        assert_eq!(200_003, table_entry.route_id(part_id(1, 0)));
+        assert_eq!(part_id(0, 0), table_entry.route_id(part_id(0, 0)));
+    }
+}
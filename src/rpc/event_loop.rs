@@ -0,0 +1,782 @@
+/*-
+ * #%L
+ * OBKV Table Client Framework
+ * %%
+ * Copyright (C) 2021 OceanBase
+ * %%
+ * OBKV Table Client Framework is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *          http://license.coscl.org.cn/MulanPSL2
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ * #L%
+ */
+
+//! Event loops which multiplex the socket I/O of all the connections, so that
+//! the number of I/O threads doesn't grow with the number of connections.
+
+use std::{
+    collections::HashMap,
+    io::{ErrorKind, Read, Write},
+    net::{Shutdown, SocketAddr, TcpStream as StdTcpStream},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use bytes::BytesMut;
+use crossbeam::{unbounded, Receiver, Sender};
+use mio::{net::TcpStream, Events, Interest, Poll, Registry, Token, Waker};
+
+use super::{
+    protocol::{ObTablePacket, ObTablePacketCodec, TransportCode},
+    Connection, RequestsMap, OBKV_RPC_HISTOGRAM_NUM_VEC, OBKV_RPC_HISTOGRAM_VEC,
+    OB_MYSQL_MAX_PACKET_LENGTH, READ_BUF_SIZE,
+};
+use crate::error::{CommonErrCode, Error::Common as CommonErr, Result};
+
+const WAKER_TOKEN: Token = Token(0);
+const EVENTS_CAPACITY: usize = 1024;
+pub const DEFAULT_EVENT_LOOP_NUM: usize = 4;
+/// The max bytes waiting to be written into a connection, the writes beyond
+/// it fail until the remote catches up. A single packet is always accepted
+/// when nothing is pending, whatever its size.
+pub const MAX_PENDING_WRITE_BYTES: usize = 4 * OB_MYSQL_MAX_PACKET_LENGTH;
+
+lazy_static! {
+    /// The event loops shared by the connections built without specifying
+    /// any event loop group.
+    pub static ref DEFAULT_EVENT_LOOP_GROUP: Arc<EventLoopGroup> = Arc::new(
+        EventLoopGroup::new("conn_io_{}", DEFAULT_EVENT_LOOP_NUM)
+            .expect("Fail to create default event loop group")
+    );
+}
+
+enum Command {
+    Register {
+        token: Token,
+        stream: TcpStream,
+        addr: SocketAddr,
+        requests: RequestsMap,
+        active: Arc<AtomicBool>,
+        pending_bytes: Arc<AtomicUsize>,
+    },
+    Write {
+        token: Token,
+        channel_id: Option<i32>,
+        data: BytesMut,
+        requests: RequestsMap,
+        pending_bytes: Arc<AtomicUsize>,
+    },
+    Close {
+        token: Token,
+    },
+    Shutdown,
+}
+
+impl std::fmt::Debug for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Command::Register { token, addr, .. } => {
+                write!(f, "Register{{token:{:?}, addr:{}}}", token, addr)
+            }
+            Command::Write {
+                token, channel_id, ..
+            } => write!(f, "Write{{token:{:?}, channel_id:{:?}}}", token, channel_id),
+            Command::Close { token } => write!(f, "Close{{token:{:?}}}", token),
+            Command::Shutdown => write!(f, "Shutdown"),
+        }
+    }
+}
+
+/// A fixed set of event loops, the connections are assigned to them in a
+/// round-robin way.
+#[derive(Debug)]
+pub struct EventLoopGroup {
+    loops: Vec<Arc<EventLoop>>,
+    next: AtomicUsize,
+}
+
+impl EventLoopGroup {
+    /// Create `num` event loops, and the `{}` in `name` will be replaced by
+    /// the index of the event loop thread.
+    pub fn new(name: &str, num: usize) -> Result<EventLoopGroup> {
+        if num == 0 {
+            return Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                "EventLoopGroup::new event loop number should be positive".to_owned(),
+            ));
+        }
+
+        let mut loops = Vec::with_capacity(num);
+        for i in 0..num {
+            loops.push(Arc::new(EventLoop::new(
+                name.replace("{}", &i.to_string()),
+            )?));
+        }
+
+        Ok(EventLoopGroup {
+            loops,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    /// Pick the event loop for a new connection.
+    pub fn next(&self) -> Arc<EventLoop> {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.loops.len();
+        self.loops[idx].clone()
+    }
+}
+
+/// A single event loop thread polling the sockets of its connections.
+#[derive(Debug)]
+pub struct EventLoop {
+    name: String,
+    commands: Sender<Command>,
+    waker: Waker,
+    registry: Registry,
+    next_token: AtomicUsize,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl EventLoop {
+    fn new(name: String) -> Result<EventLoop> {
+        let poll = Poll::new()?;
+        let registry = poll.registry().try_clone()?;
+        let waker = Waker::new(poll.registry(), WAKER_TOKEN)?;
+        let (commands, receiver) = unbounded();
+
+        let worker = thread::Builder::new().name(name.clone()).spawn(move || {
+            let mut worker = EventLoopWorker::new(poll, receiver);
+            worker.run();
+        })?;
+
+        Ok(EventLoop {
+            name,
+            commands,
+            waker,
+            registry,
+            next_token: AtomicUsize::new(WAKER_TOKEN.0 + 1),
+            worker: Some(worker),
+        })
+    }
+
+    /// Hand the stream over to the event loop, the returned token identifies
+    /// the connection in the later commands. `pending_bytes` tracks the
+    /// queued data not written into the stream yet.
+    pub(crate) fn register(
+        &self,
+        stream: StdTcpStream,
+        addr: SocketAddr,
+        requests: RequestsMap,
+        active: Arc<AtomicBool>,
+        pending_bytes: Arc<AtomicUsize>,
+    ) -> Result<Token> {
+        stream.set_nonblocking(true)?;
+        let mut stream = TcpStream::from_std(stream);
+        let token = Token(self.next_token.fetch_add(1, Ordering::Relaxed));
+        self.registry
+            .register(&mut stream, token, Interest::READABLE)?;
+
+        self.send(Command::Register {
+            token,
+            stream,
+            addr,
+            requests,
+            active,
+            pending_bytes,
+        })?;
+        Ok(token)
+    }
+
+    /// Queue the encoded packet to be written into the connection, fails
+    /// with [`CommonErrCode::Backpressure`] if the pending data would exceed
+    /// [`MAX_PENDING_WRITE_BYTES`].
+    pub(crate) fn write(
+        &self,
+        token: Token,
+        channel_id: Option<i32>,
+        data: BytesMut,
+        requests: RequestsMap,
+        pending_bytes: &Arc<AtomicUsize>,
+    ) -> Result<()> {
+        let size = data.len();
+        let pending = pending_bytes.fetch_add(size, Ordering::AcqRel);
+        if pending > 0 && pending + size > MAX_PENDING_WRITE_BYTES {
+            pending_bytes.fetch_sub(size, Ordering::AcqRel);
+            return Err(CommonErr(
+                CommonErrCode::Backpressure,
+                format!(
+                    "EventLoop::write too much data pending in {}, pending:{}, size:{}",
+                    self.name, pending, size
+                ),
+            ));
+        }
+
+        self.send(Command::Write {
+            token,
+            channel_id,
+            data,
+            requests,
+            pending_bytes: pending_bytes.clone(),
+        })
+        .map_err(|e| {
+            pending_bytes.fetch_sub(size, Ordering::AcqRel);
+            e
+        })
+    }
+
+    /// Close the connection after the pending data is flushed.
+    pub(crate) fn close(&self, token: Token) -> Result<()> {
+        self.send(Command::Close { token })
+    }
+
+    fn send(&self, command: Command) -> Result<()> {
+        self.commands.send(command).map_err(|e| {
+            CommonErr(
+                CommonErrCode::BrokenPipe,
+                format!("event loop {} exits, command:{:?}", self.name, e.0),
+            )
+        })?;
+        self.waker.wake()?;
+        Ok(())
+    }
+}
+
+impl Drop for EventLoop {
+    fn drop(&mut self) {
+        if let Err(e) = self.send(Command::Shutdown) {
+            warn!("EventLoop::drop fail to shutdown {}, err:{}", self.name, e);
+            return;
+        }
+        if let Some(worker) = self.worker.take() {
+            if let Err(e) = worker.join() {
+                error!("EventLoop::drop fail to join on {}, err:{:?}", self.name, e);
+            }
+        }
+    }
+}
+
+/// The I/O state of a connection owned by the event loop thread.
+struct ConnIo {
+    stream: TcpStream,
+    addr: SocketAddr,
+    requests: RequestsMap,
+    active: Arc<AtomicBool>,
+    pending_bytes: Arc<AtomicUsize>,
+    codec: ObTablePacketCodec,
+    read_buf: BytesMut,
+    write_buf: BytesMut,
+    writable_interest: bool,
+    // Closed by the client, shut down once the pending data is written.
+    closing: bool,
+}
+
+impl ConnIo {
+    /// Read all the available data and notify the requests of the decoded
+    /// responses, returns false if the connection should be closed.
+    fn on_readable(&mut self, read_buf: &mut [u8]) -> bool {
+        let _timer = OBKV_RPC_HISTOGRAM_VEC
+            .with_label_values(&["socket_read"])
+            .start_timer();
+        loop {
+            match self.stream.read(read_buf) {
+                Ok(0) => {
+                    info!(
+                        "ConnIo::on_readable read zero bytes, the connection to {} was closed by remote.",
+                        self.addr
+                    );
+                    return false;
+                }
+                Ok(size) => {
+                    OBKV_RPC_HISTOGRAM_NUM_VEC
+                        .with_label_values(&["read_bytes"])
+                        .observe(size as f64);
+                    self.read_buf.extend_from_slice(&read_buf[0..size]);
+                }
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    error!(
+                        "ConnIo::on_readable encountered IO error: {} for addr {}.",
+                        e, self.addr
+                    );
+                    return false;
+                }
+            }
+        }
+
+        if self.read_buf.is_empty() {
+            return true;
+        }
+
+        OBKV_RPC_HISTOGRAM_NUM_VEC
+            .with_label_values(&["read_buf_bytes"])
+            .observe(self.read_buf.len() as f64);
+
+        if self.read_buf.len() > OB_MYSQL_MAX_PACKET_LENGTH {
+            debug!(
+                "ConnIo::on_readable too much data in read buffer in connection to {}",
+                self.addr
+            );
+        }
+
+        let _timer = OBKV_RPC_HISTOGRAM_VEC
+            .with_label_values(&["decode_responses"])
+            .start_timer();
+        Connection::decode_packets(
+            &mut self.codec,
+            &mut self.read_buf,
+            &self.requests,
+            &self.addr,
+        )
+    }
+
+    /// Write the pending data as much as possible, returns false if the
+    /// connection should be closed.
+    fn flush(&mut self) -> bool {
+        let _timer = OBKV_RPC_HISTOGRAM_VEC
+            .with_label_values(&["socket_write"])
+            .start_timer();
+        while !self.write_buf.is_empty() {
+            match self.stream.write(&self.write_buf) {
+                Ok(0) => {
+                    error!(
+                        "ConnIo::flush write zero bytes into stream connected to {}",
+                        self.addr
+                    );
+                    return false;
+                }
+                Ok(size) => {
+                    let _ = self.write_buf.split_to(size);
+                    self.pending_bytes.fetch_sub(size, Ordering::AcqRel);
+                }
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    error!(
+                        "ConnIo::flush fail to write into stream connected to {}, err: {}",
+                        self.addr, e
+                    );
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Watch the writable event only when there is pending data.
+    fn update_interest(&mut self, registry: &Registry, token: Token) -> bool {
+        let want_writable = !self.write_buf.is_empty();
+        if want_writable == self.writable_interest {
+            return true;
+        }
+        let interest = if want_writable {
+            Interest::READABLE | Interest::WRITABLE
+        } else {
+            Interest::READABLE
+        };
+        match registry.reregister(&mut self.stream, token, interest) {
+            Ok(()) => {
+                self.writable_interest = want_writable;
+                true
+            }
+            Err(e) => {
+                error!(
+                    "ConnIo::update_interest fail to reregister stream connected to {}, err: {}",
+                    self.addr, e
+                );
+                false
+            }
+        }
+    }
+}
+
+struct EventLoopWorker {
+    poll: Poll,
+    commands: Receiver<Command>,
+    conns: HashMap<Token, ConnIo>,
+    read_buf: Vec<u8>,
+}
+
+impl EventLoopWorker {
+    fn new(poll: Poll, commands: Receiver<Command>) -> Self {
+        EventLoopWorker {
+            poll,
+            commands,
+            conns: HashMap::new(),
+            read_buf: vec![0; READ_BUF_SIZE],
+        }
+    }
+
+    fn run(&mut self) {
+        let mut events = Events::with_capacity(EVENTS_CAPACITY);
+        loop {
+            if let Err(e) = self.poll.poll(&mut events, None) {
+                if e.kind() == ErrorKind::Interrupted {
+                    continue;
+                }
+                error!("EventLoopWorker::run fail to poll, err: {}", e);
+                break;
+            }
+
+            for event in events.iter() {
+                let token = event.token();
+                if token == WAKER_TOKEN {
+                    continue;
+                }
+                let mut keep = true;
+                if event.is_readable() || event.is_read_closed() || event.is_error() {
+                    keep = self.on_readable(token);
+                }
+                if keep && event.is_writable() {
+                    self.on_writable(token);
+                }
+            }
+
+            if !self.process_commands() {
+                break;
+            }
+        }
+
+        for (token, conn) in self.conns.drain() {
+            Self::shutdown_conn(self.poll.registry(), token, conn);
+        }
+    }
+
+    /// Returns false if the event loop should exit.
+    fn process_commands(&mut self) -> bool {
+        OBKV_RPC_HISTOGRAM_NUM_VEC
+            .with_label_values(&["request_queue_size"])
+            .observe(self.commands.len() as f64);
+
+        while let Ok(command) = self.commands.try_recv() {
+            match command {
+                Command::Register {
+                    token,
+                    stream,
+                    addr,
+                    requests,
+                    active,
+                    pending_bytes,
+                } => {
+                    self.conns.insert(
+                        token,
+                        ConnIo {
+                            stream,
+                            addr,
+                            requests,
+                            active,
+                            pending_bytes,
+                            codec: ObTablePacketCodec::new(),
+                            read_buf: BytesMut::with_capacity(READ_BUF_SIZE),
+                            write_buf: BytesMut::with_capacity(READ_BUF_SIZE),
+                            writable_interest: false,
+                            closing: false,
+                        },
+                    );
+                    // The readable event may come before the connection is
+                    // inserted, so try to read here in case of missing it.
+                    self.on_readable(token);
+                }
+                Command::Write {
+                    token,
+                    channel_id,
+                    data,
+                    requests,
+                    pending_bytes,
+                } => self.on_write(token, channel_id, data, requests, pending_bytes),
+                Command::Close { token } => self.on_close(token),
+                Command::Shutdown => return false,
+            }
+        }
+        true
+    }
+
+    fn on_write(
+        &mut self,
+        token: Token,
+        channel_id: Option<i32>,
+        data: BytesMut,
+        requests: RequestsMap,
+        pending_bytes: Arc<AtomicUsize>,
+    ) {
+        OBKV_RPC_HISTOGRAM_NUM_VEC
+            .with_label_values(&["write_bytes"])
+            .observe(data.len() as f64);
+
+        let ok = match self.conns.get_mut(&token) {
+            Some(conn) => {
+                conn.write_buf.extend_from_slice(&data);
+                conn.flush() && conn.update_interest(self.poll.registry(), token)
+            }
+            None => {
+                pending_bytes.fetch_sub(data.len(), Ordering::AcqRel);
+                if let Some(id) = channel_id {
+                    Connection::notify_sender(
+                        &requests,
+                        id,
+                        ObTablePacket::TransportPacket {
+                            error: CommonErr(
+                                CommonErrCode::BrokenPipe,
+                                "connection is already closed".to_owned(),
+                            ),
+                            code: TransportCode::SendFailure,
+                        },
+                    );
+                }
+                return;
+            }
+        };
+
+        if !ok {
+            self.close_conn(token);
+        }
+    }
+
+    /// Shut the connection down now if all the data is written, otherwise
+    /// wait for the writable events to drain the write buffer.
+    fn on_close(&mut self, token: Token) {
+        let drained = match self.conns.get_mut(&token) {
+            Some(conn) => {
+                conn.active.store(false, Ordering::Release);
+                conn.closing = true;
+                if !conn.flush() || !conn.update_interest(self.poll.registry(), token) {
+                    true
+                } else {
+                    conn.write_buf.is_empty()
+                }
+            }
+            None => return,
+        };
+        if drained {
+            self.close_conn(token);
+        }
+    }
+
+    fn on_readable(&mut self, token: Token) -> bool {
+        let ok = match self.conns.get_mut(&token) {
+            Some(conn) => conn.on_readable(&mut self.read_buf),
+            None => return false,
+        };
+        if !ok {
+            self.close_conn(token);
+        }
+        ok
+    }
+
+    fn on_writable(&mut self, token: Token) {
+        let ok = match self.conns.get_mut(&token) {
+            Some(conn) => {
+                conn.flush()
+                    && conn.update_interest(self.poll.registry(), token)
+                    && !(conn.closing && conn.write_buf.is_empty())
+            }
+            None => return,
+        };
+        if !ok {
+            self.close_conn(token);
+        }
+    }
+
+    fn close_conn(&mut self, token: Token) {
+        if let Some(conn) = self.conns.remove(&token) {
+            Self::shutdown_conn(self.poll.registry(), token, conn);
+        }
+    }
+
+    fn shutdown_conn(registry: &Registry, token: Token, mut conn: ConnIo) {
+        conn.active.store(false, Ordering::Release);
+        // The unwritten data is dropped together with the connection.
+        conn.pending_bytes
+            .fetch_sub(conn.write_buf.len(), Ordering::AcqRel);
+        if let Err(e) = registry.deregister(&mut conn.stream) {
+            warn!(
+                "EventLoopWorker::shutdown_conn fail to deregister stream connected to {}, token:{:?}, err:{}",
+                conn.addr, token, e
+            );
+        }
+        if let Err(e) = conn.stream.shutdown(Shutdown::Both) {
+            warn!(
+                "EventLoopWorker::shutdown_conn fail to close stream to {}, err:{}",
+                conn.addr, e
+            );
+        }
+        Connection::cancel_requests(&conn.requests);
+        info!("Close stream for connection to {}", conn.addr);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::Read,
+        net::TcpListener,
+        sync::{Arc, Mutex},
+    };
+
+    use futures::{sync::oneshot, Future};
+
+    use super::*;
+
+    #[test]
+    fn test_write_and_close() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = StdTcpStream::connect(addr).unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+
+        let group = EventLoopGroup::new("test_conn_io_{}", 1).unwrap();
+        let event_loop = group.next();
+        let requests: RequestsMap = Arc::new(Mutex::new(HashMap::new()));
+        let active = Arc::new(AtomicBool::new(true));
+        let pending_bytes = Arc::new(AtomicUsize::new(0));
+        let token = event_loop
+            .register(
+                client,
+                addr,
+                requests.clone(),
+                active.clone(),
+                pending_bytes.clone(),
+            )
+            .unwrap();
+
+        let (tx, rx) = oneshot::channel();
+        requests.lock().unwrap().insert(1, tx);
+        event_loop
+            .write(
+                token,
+                Some(1),
+                BytesMut::from(&b"hello"[..]),
+                requests.clone(),
+                &pending_bytes,
+            )
+            .unwrap();
+
+        let mut buf = [0; 5];
+        server.read_exact(&mut buf).unwrap();
+        assert_eq!(b"hello", &buf);
+
+        // the pending request is canceled once the remote closes the connection
+        drop(server);
+        assert!(rx.wait().unwrap().is_err());
+        assert!(!active.load(Ordering::Acquire));
+
+        // requests to the closed connection fail immediately
+        let (tx, rx) = oneshot::channel();
+        requests.lock().unwrap().insert(2, tx);
+        event_loop
+            .write(
+                token,
+                Some(2),
+                BytesMut::from(&b"world"[..]),
+                requests.clone(),
+                &pending_bytes,
+            )
+            .unwrap();
+        match rx.wait().unwrap() {
+            Ok(ObTablePacket::TransportPacket {
+                code: TransportCode::SendFailure,
+                ..
+            }) => (),
+            other => panic!("unexpected response: {:?}", other),
+        }
+        assert_eq!(0, pending_bytes.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn test_close_after_pending_writes() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = StdTcpStream::connect(addr).unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+
+        let group = EventLoopGroup::new("test_conn_close_{}", 1).unwrap();
+        let event_loop = group.next();
+        let requests: RequestsMap = Arc::new(Mutex::new(HashMap::new()));
+        let active = Arc::new(AtomicBool::new(true));
+        let pending_bytes = Arc::new(AtomicUsize::new(0));
+        let token = event_loop
+            .register(
+                client,
+                addr,
+                requests.clone(),
+                active.clone(),
+                pending_bytes.clone(),
+            )
+            .unwrap();
+
+        // more than the socket buffers can hold, so the flush would block
+        let data = vec![7u8; 16 * 1024 * 1024];
+        event_loop
+            .write(
+                token,
+                None,
+                BytesMut::from(&data[..]),
+                requests,
+                &pending_bytes,
+            )
+            .unwrap();
+        event_loop.close(token).unwrap();
+
+        let mut received = Vec::with_capacity(data.len());
+        server.read_to_end(&mut received).unwrap();
+        assert_eq!(data.len(), received.len());
+        assert!(!active.load(Ordering::Acquire));
+        assert_eq!(0, pending_bytes.load(Ordering::Acquire));
+    }
+
+    #[test]
+    fn test_write_backpressure() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = StdTcpStream::connect(addr).unwrap();
+        // never read, so the written data piles up in the write buffer
+        let (_server, _) = listener.accept().unwrap();
+
+        let group = EventLoopGroup::new("test_conn_backpressure_{}", 1).unwrap();
+        let event_loop = group.next();
+        let requests: RequestsMap = Arc::new(Mutex::new(HashMap::new()));
+        let active = Arc::new(AtomicBool::new(true));
+        let pending_bytes = Arc::new(AtomicUsize::new(0));
+        let token = event_loop
+            .register(
+                client,
+                addr,
+                requests.clone(),
+                active,
+                pending_bytes.clone(),
+            )
+            .unwrap();
+
+        // a single packet is accepted even if it's larger than the limit
+        let data = vec![7u8; MAX_PENDING_WRITE_BYTES + 1];
+        event_loop
+            .write(
+                token,
+                None,
+                BytesMut::from(&data[..]),
+                requests.clone(),
+                &pending_bytes,
+            )
+            .unwrap();
+
+        let more = vec![8u8; OB_MYSQL_MAX_PACKET_LENGTH];
+        let err = event_loop
+            .write(
+                token,
+                None,
+                BytesMut::from(&more[..]),
+                requests,
+                &pending_bytes,
+            )
+            .unwrap_err();
+        assert_eq!(Some(CommonErrCode::Backpressure), err.common_err_code());
+        assert!(pending_bytes.load(Ordering::Acquire) <= data.len());
+    }
+}
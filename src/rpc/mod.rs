@@ -16,40 +16,44 @@
  */
 
 pub mod conn_pool;
+pub mod event_loop;
 pub mod protocol;
 pub mod proxy;
 pub mod util;
 
 use std::{
     collections::HashMap,
-    io::{ErrorKind, Read, Write},
-    mem,
-    net::{Shutdown, SocketAddr, TcpStream, ToSocketAddrs},
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
     ops::Drop,
     sync::{
         atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
         Arc, Mutex,
     },
-    thread::{self, JoinHandle},
     time::Duration,
 };
 
 use byteorder::{BigEndian, ByteOrder};
 use bytes::BytesMut;
-use crossbeam::{bounded, unbounded, Receiver, Sender};
+use futures::{future, sync::oneshot, Future};
+use mio::Token;
 use net2::{TcpBuilder, TcpStreamExt};
 use prometheus::*;
+use scheduled_thread_pool::JobHandle;
 use tokio_codec::{Decoder, Encoder};
 use uuid::Uuid;
 
-use self::protocol::{
-    payloads::{ObRpcResultCode, ObTableLoginRequest, ObTableLoginResult},
-    ObPayload, ObRpcPacket, ObRpcPacketHeader, ObTablePacket, ObTablePacketCodec, ProtoDecoder,
-    ProtoEncoder, TransportCode, HEADER_SIZE,
+use self::{
+    event_loop::{EventLoop, EventLoopGroup, DEFAULT_EVENT_LOOP_GROUP},
+    protocol::{
+        payloads::{ObRpcResultCode, ObTableLoginRequest, ObTableLoginResult},
+        ObPayload, ObRpcPacket, ObRpcPacketHeader, ObTablePacket, ObTablePacketCodec, ProtoDecoder,
+        ProtoEncoder, TransportCode, HEADER_SIZE,
+    },
 };
 use crate::{
     error::{CommonErrCode, Error, Error::Common as CommonErr, Result},
     rpc::protocol::TraceId,
+    util::{self, BoxFuture},
 };
 
 lazy_static! {
@@ -69,134 +73,76 @@ lazy_static! {
     .unwrap();
 }
 
-type RequestsMap = Arc<Mutex<HashMap<i32, Sender<Result<ObTablePacket>>>>>;
+type RequestsMap = Arc<Mutex<HashMap<i32, oneshot::Sender<Result<ObTablePacket>>>>>;
+type ResponseReceiver = oneshot::Receiver<Result<ObTablePacket>>;
 
 const CONN_CONTINUOUS_TIMEOUT_CEILING: usize = 10;
 
 ///Send component of OBKV connection.
 #[derive(Debug)]
 pub struct ConnectionSender {
-    sender: Sender<ObTablePacket>,
-    writer: Option<JoinHandle<Result<()>>>,
+    event_loop: Arc<EventLoop>,
+    token: Token,
+    requests: RequestsMap,
+    // Bytes queued to the event loop but not written into the socket yet.
+    pending_bytes: Arc<AtomicUsize>,
+    closed: AtomicBool,
 }
 
 impl ConnectionSender {
     fn new(
-        write_stream: TcpStream,
+        event_loop: Arc<EventLoop>,
+        token: Token,
         requests: RequestsMap,
-        active: Arc<AtomicBool>,
+        pending_bytes: Arc<AtomicUsize>,
     ) -> ConnectionSender {
-        let (sender, receiver): (Sender<ObTablePacket>, Receiver<ObTablePacket>) = unbounded();
-        let mut codec = ObTablePacketCodec::new();
-
-        let writer = thread::Builder::new()
-            .name("conn_writer".to_owned())
-            .spawn(move || {
-                let mut buf = BytesMut::with_capacity(1024);
-                let mut write_stream = write_stream;
-                let addr = write_stream.peer_addr()?;
-                loop {
-                    OBKV_RPC_HISTOGRAM_NUM_VEC
-                        .with_label_values(&["request_queue_size"])
-                        .observe(receiver.len() as f64);
-
-                    match receiver.recv() {
-                        Ok(packet) => {
-                            if packet.is_close_poison() {
-                                break;
-                            }
-                            //clear the buf for reuse
-                            buf.clear();
-                            let channel_id = packet.channel_id();
-                            match codec.encode(packet, &mut buf) {
-                                Ok(()) => {
-                                    OBKV_RPC_HISTOGRAM_NUM_VEC
-                                        .with_label_values(&["write_bytes"])
-                                        .observe(buf.len() as f64);
-                                    let _timer = OBKV_RPC_HISTOGRAM_VEC
-                                        .with_label_values(&["socket_write"])
-                                        .start_timer();
-                                    match write_stream.write_all(&buf) {
-                                        Ok(()) => (),
-                                        Err(e) => {
-                                            error!(
-                                                "Fail to write packet into stream connected to {}, err: {}",
-                                                addr, e
-                                            );
-                                            break;
-                                        }
-                                    }
-                                }
-                                Err(e) => match channel_id {
-                                    Some(id) => {
-                                        Connection::notify_sender(
-                                            &requests,
-                                            id,
-                                            ObTablePacket::TransportPacket {
-                                                error: Error::from(e),
-                                                code: TransportCode::SendFailure,
-                                            },
-                                        );
-                                    }
-                                    None => {
-                                        error!("ConnectionSender fail to encode packet: error={}, but channel id not found in packet", e);
-                                    }
-                                },
-                            }
-                        }
-                        Err(e) => {
-                            error!("Error in connection sender Receiver::recv, {}", e);
-                            break;
-                        }
-                    }
-                }
-
-                active.store(false, Ordering::Release);
-
-                if let Err(err) = write_stream.shutdown(Shutdown::Write) {
-                    error!("Fail to close write stream to {}, err:{}", addr, err);
-                }
-
-                drop(receiver);
-                Connection::cancel_requests(&requests);
-
-                info!("Close write stream for connection to {}", addr);
-                Ok(())
-            }).expect("Fail to create connection_writer thread");
-
         ConnectionSender {
-            sender,
-            writer: Some(writer),
+            event_loop,
+            token,
+            requests,
+            pending_bytes,
+            closed: AtomicBool::new(false),
         }
     }
 
     ///Performs send of request
     ///
-    ///It can fail only when connection gets closed.
-    ///Which means OBKV connection is no longer valid.
+    ///It fails when connection gets closed, which means OBKV connection is
+    ///no longer valid, or when too much data is waiting to be written into
+    ///the connection.
     pub fn request(&self, message: ObTablePacket) -> Result<()> {
-        self.sender.send(message).map_err(Self::broken_pipe)
+        if self.is_closed() {
+            return Err(Self::broken_pipe(()));
+        }
+
+        let channel_id = message.channel_id();
+        let mut buf = BytesMut::new();
+        ObTablePacketCodec::new().encode(message, &mut buf)?;
+
+        self.event_loop
+            .write(
+                self.token,
+                channel_id,
+                buf,
+                self.requests.clone(),
+                &self.pending_bytes,
+            )
+            .map_err(|e| match e.common_err_code() {
+                Some(CommonErrCode::Backpressure) => e,
+                _ => Self::broken_pipe(e),
+            })
     }
 
-    fn close(&mut self) -> Result<()> {
-        self.request(ObTablePacket::ClosePoison)?;
-        let writer = mem::replace(&mut self.writer, None);
+    #[inline]
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
 
-        match writer.unwrap().join() {
-            Ok(_) => (),
-            Err(e) => {
-                error!(
-                    "ConnectionSender::close fail to join on writer, err={:?}",
-                    e
-                );
-                return Err(CommonErr(
-                    CommonErrCode::Rpc,
-                    "ConnectionSender::close fail to join on writer.".to_owned(),
-                ));
-            }
+    fn close(&self) -> Result<()> {
+        if self.closed.swap(true, Ordering::AcqRel) {
+            return Ok(());
         }
-
-        Ok(())
+        self.event_loop.close(self.token)
     }
 
     #[inline]
@@ -212,8 +158,6 @@ impl ConnectionSender {
 pub struct Connection {
     //remote addr
     addr: SocketAddr,
-    reader: Option<JoinHandle<Result<()>>>,
-    reader_signal_sender: Sender<()>,
     sender: ConnectionSender,
     requests: RequestsMap,
     continuous_timeout_failures: AtomicUsize,
@@ -228,7 +172,6 @@ pub struct Connection {
 
 const OB_MYSQL_MAX_PACKET_LENGTH: usize = 1 << 24;
 const READ_BUF_SIZE: usize = 1 << 16;
-const DEFAULT_STACK_SIZE: usize = 2 * 1024 * 1024;
 
 struct LoadCounter<'a>(&'a AtomicUsize);
 
@@ -245,45 +188,48 @@ impl<'a> Drop for LoadCounter<'a> {
     }
 }
 
-impl Connection {
-    fn internal_new(id: u32, addr: SocketAddr, stream: TcpStream) -> Result<Self> {
-        let requests: RequestsMap = Arc::new(Mutex::new(HashMap::new()));
-        let read_requests = requests.clone();
+/// Same as [`LoadCounter`], but owns the connection so that it can be moved
+/// into the futures.
+struct OwnedLoadCounter(Arc<Connection>);
 
-        let read_stream = stream.try_clone()?;
+impl OwnedLoadCounter {
+    fn new(conn: Arc<Connection>) -> Self {
+        conn.load.fetch_add(1, Ordering::Relaxed);
+        Self(conn)
+    }
+}
 
-        let active = Arc::new(AtomicBool::new(false));
-        let read_active = active.clone();
-        let (sender, receiver): (Sender<()>, Receiver<()>) = unbounded();
-
-        let join_handle = thread::Builder::new()
-            .name("conn_reader".to_owned())
-            .stack_size(OB_MYSQL_MAX_PACKET_LENGTH + DEFAULT_STACK_SIZE)
-            .spawn(move || {
-                let addr = read_stream.peer_addr()?;
-
-                Connection::process_reading_data(
-                    receiver,
-                    read_stream,
-                    read_requests.clone(),
-                    &addr,
-                );
+impl Drop for OwnedLoadCounter {
+    fn drop(&mut self) {
+        self.0.load.fetch_sub(1, Ordering::Relaxed);
+    }
+}
 
-                read_active.store(false, Ordering::Release);
-                Connection::cancel_requests(&read_requests);
+impl Connection {
+    fn internal_new(
+        id: u32,
+        addr: SocketAddr,
+        stream: TcpStream,
+        event_loop: Arc<EventLoop>,
+    ) -> Result<Self> {
+        let requests: RequestsMap = Arc::new(Mutex::new(HashMap::new()));
+        let active = Arc::new(AtomicBool::new(false));
+        let pending_bytes = Arc::new(AtomicUsize::new(0));
 
-                info!("Close read stream for connection to {}", addr);
-                Ok(())
-            })?;
+        let token = event_loop.register(
+            stream,
+            addr,
+            requests.clone(),
+            active.clone(),
+            pending_bytes.clone(),
+        )?;
 
         Ok(Connection {
             addr,
-            reader: Some(join_handle),
-            sender: ConnectionSender::new(stream, requests.clone(), active.clone()),
+            sender: ConnectionSender::new(event_loop, token, requests.clone(), pending_bytes),
             requests,
             continuous_timeout_failures: AtomicUsize::new(0),
             continuous_timeout_failures_ceiling: CONN_CONTINUOUS_TIMEOUT_CEILING,
-            reader_signal_sender: sender,
             credential: None,
             tenant_id: None,
             active,
@@ -297,100 +243,19 @@ impl Connection {
         self.load.load(Ordering::Relaxed)
     }
 
-    fn process_reading_data(
-        signal_receiver: Receiver<()>,
-        mut read_stream: TcpStream,
-        read_requests: RequestsMap,
-        addr: &SocketAddr,
-    ) {
-        let mut codec = ObTablePacketCodec::new();
-        let mut read_buf = [0; READ_BUF_SIZE];
-        let mut buf = BytesMut::with_capacity(READ_BUF_SIZE);
-        loop {
-            if let Ok(()) = signal_receiver.try_recv() {
-                break;
-            }
-
-            let timer = OBKV_RPC_HISTOGRAM_VEC
-                .with_label_values(&["socket_read"])
-                .start_timer();
-
-            match read_stream.read(&mut read_buf) {
-                Ok(size) => {
-                    drop(timer);
-                    OBKV_RPC_HISTOGRAM_NUM_VEC
-                        .with_label_values(&["read_bytes"])
-                        .observe(size as f64);
-
-                    if size > 0 {
-                        buf.extend_from_slice(&read_buf[0..size]);
-
-                        OBKV_RPC_HISTOGRAM_NUM_VEC
-                            .with_label_values(&["read_buf_bytes"])
-                            .observe(buf.len() as f64);
-
-                        if buf.len() > OB_MYSQL_MAX_PACKET_LENGTH {
-                            debug!(
-                                "Connection::process_reading_data too much data in read buffer in \
-                                connection to {}",
-                                addr
-                            );
-                        }
-
-                        let _timer = OBKV_RPC_HISTOGRAM_VEC
-                            .with_label_values(&["decode_responses"])
-                            .start_timer();
-
-                        if !Self::decode_packets(&mut codec, &mut buf, &read_requests, addr) {
-                            break;
-                        }
-                    } else {
-                        info!(
-                            "Connection::process_reading_data read zero bytes, \
-                             the connection to {} was closed by remote.",
-                            addr
-                        );
-                        break;
-                    }
-                }
-                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
-                    let _timer = OBKV_RPC_HISTOGRAM_VEC
-                        .with_label_values(&["yield_thread"])
-                        .start_timer();
-                    thread::yield_now();
-                    continue;
-                }
-                Err(e) => {
-                    error!(
-                        "Connection::process_reading_data encountered IO error: {} for addr {}.",
-                        e, addr
-                    );
-                    break;
-                }
-            }
-        }
-        if let Err(err) = read_stream.shutdown(Shutdown::Read) {
-            warn!(
-                "Connection::process_reading_data fail to close read stream to {}, err:{}",
-                addr, err
-            );
-        }
-    }
-
     fn cancel_requests(requests: &RequestsMap) {
         let mut requests = requests.lock().unwrap();
-        for (_, sender) in requests.iter() {
+        for (_, sender) in requests.drain() {
             if let Err(e) = sender.send(Err(CommonErr(
                 CommonErrCode::Rpc,
                 "connection reader exits".to_owned(),
             ))) {
                 error!(
-                    "Connection::cancel_requests: fail to send cancel message, err:{}",
+                    "Connection::cancel_requests: fail to send cancel message, err:{:?}",
                     e
                 );
             }
         }
-        requests.clear();
     }
 
     fn decode_packets(
@@ -526,6 +391,55 @@ impl Connection {
             .with_label_values(&["execute_payload"])
             .start_timer();
 
+        let (rx, trace_id, timeout_handle) = match self.send_payload(payload)? {
+            None => return Ok(()),
+            Some(v) => v,
+        };
+
+        let resp = rx.wait();
+        timeout_handle.cancel();
+
+        self.handle_response(resp, trace_id, response)
+    }
+
+    /// Asynchronous version of [`Connection::execute`], the returned future
+    /// resolves to `response` once the reply of the request arrives, so no
+    /// thread is blocked while the request is in flight.
+    pub fn execute_async<T, R>(self: Arc<Self>, payload: &mut T, mut response: R) -> BoxFuture<R>
+    where
+        T: ObPayload,
+        R: ObPayload + Send + 'static,
+    {
+        let (rx, trace_id, timeout_handle) = match box_future_try!(self.send_payload(payload)) {
+            None => return Box::new(future::ok(response)),
+            Some(v) => v,
+        };
+
+        // the load is decreased even if the future is dropped before the reply
+        let load_counter = OwnedLoadCounter::new(self);
+        let timer = OBKV_RPC_HISTOGRAM_VEC
+            .with_label_values(&["execute_payload_async"])
+            .start_timer();
+
+        Box::new(rx.then(move |resp| {
+            timeout_handle.cancel();
+            drop(timer);
+            load_counter
+                .0
+                .handle_response(resp, trace_id, &mut response)
+                .map(|_| response)
+        }))
+    }
+
+    /// Encode and send the payload, the receiver of the reply is returned
+    /// together with the timeout job which should be canceled after the
+    /// reply arrives.
+    ///
+    /// Returns `None` if the request expects no reply.
+    fn send_payload<T: ObPayload>(
+        &self,
+        payload: &mut T,
+    ) -> Result<Option<(ResponseReceiver, TraceId, JobHandle)>> {
         let timeout = Duration::from_millis(payload.timeout_millis() as u64);
 
         payload.set_tenant_id(self.tenant_id);
@@ -545,15 +459,15 @@ impl Connection {
 
         let channel_id = match req.channel_id() {
             None => {
-                debug!("Connection::execute: send no reply request");
+                debug!("Connection::send_payload: send no reply request");
                 self.sender.request(req).map_err(|e| {
                     error!(
-                        "Connection::execute fail to send no-reply request, err:{}",
+                        "Connection::send_payload fail to send no-reply request, err:{}",
                         e
                     );
                     e
                 })?;
-                return Ok(());
+                return Ok(None);
             }
             Some(id) => id,
         };
@@ -562,10 +476,50 @@ impl Connection {
 
         if payload.timeout_millis() == 0 {
             // no-wait request,return Ok directly
-            return Ok(());
+            return Ok(None);
         }
 
-        let resp = match rx.recv_timeout(timeout) {
+        let timeout_handle = Self::schedule_timeout(&self.requests, channel_id, timeout);
+
+        Ok(Some((rx, trace_id, timeout_handle)))
+    }
+
+    /// Fail the request with a timeout packet if no reply arrives in
+    /// `timeout`.
+    fn schedule_timeout(requests: &RequestsMap, channel_id: i32, timeout: Duration) -> JobHandle {
+        let requests = requests.clone();
+        util::schedule_after(timeout, move || {
+            if let Some(sender) = requests.lock().unwrap().remove(&channel_id) {
+                let _ = sender.send(Ok(ObTablePacket::TransportPacket {
+                    error: CommonErr(CommonErrCode::Rpc, format!("no response in {:?}", timeout)),
+                    code: TransportCode::Timeout,
+                }));
+            }
+        })
+    }
+
+    fn handle_response<R: ObPayload>(
+        &self,
+        resp: std::result::Result<Result<ObTablePacket>, oneshot::Canceled>,
+        trace_id: TraceId,
+        response: &mut R,
+    ) -> Result<()> {
+        let resp = match resp {
+            Ok(Ok(ObTablePacket::TransportPacket {
+                error,
+                code: TransportCode::Timeout,
+            })) => {
+                error!(
+                    "Connection::execute: wait for rpc response timeout, addr:{}, trace_id:{}, err:{}",
+                    self.addr, trace_id, error
+                );
+
+                self.on_recv_timeout();
+                return Err(CommonErr(
+                    CommonErrCode::Rpc,
+                    format!("wait for rpc response timeout, err:{}", error),
+                ));
+            }
             Ok(resp) => {
                 self.on_recv_in_time();
                 resp.map_err(|e| {
@@ -576,17 +530,12 @@ impl Connection {
                     e
                 })?
             }
-            Err(err) => {
+            Err(e) => {
                 error!(
-                    "Connection::execute: wait for rpc response timeout, addr:{}, trace_id:{}, err:{}",
-                    self.addr, trace_id, err
+                    "Connection::execute: rpc response channel canceled, addr:{}, trace_id:{}, err:{}",
+                    self.addr, trace_id, e
                 );
-
-                self.on_recv_timeout();
-                return Err(CommonErr(
-                    CommonErrCode::Rpc,
-                    format!("wait for rpc response timeout, err:{}", err),
-                ));
+                return Err(Error::from(e));
             }
         };
 
@@ -703,54 +652,25 @@ impl Connection {
 
     /// close the connection
     fn close(&mut self) -> Result<()> {
-        if self.reader.is_none() {
+        if self.sender.is_closed() {
             return Ok(());
         }
         self.set_active(false);
 
-        //1. close writer
-        if let Err(e) = self.sender.close() {
-            error!("Connection::close fail to close writer, err: {}.", e);
-        }
-
-        //2. close reader
-        if let Err(e) = self
-            .reader_signal_sender
-            .send(())
-            .map_err(ConnectionSender::broken_pipe)
-        {
-            error!(
-                "Connection::close fail to send signal to reader, err: {}.",
-                e
-            );
-        }
-
-        let reader = mem::replace(&mut self.reader, None);
-        match reader.unwrap().join() {
-            Ok(_) => (),
-            Err(e) => {
-                error!("Connection::close fail to join on reader, err={:?}", e);
-                return Err(CommonErr(
-                    CommonErrCode::Rpc,
-                    "Connection::close fail to join on reader.".to_owned(),
-                ));
-            }
-        }
-
-        Ok(())
+        self.sender.close().map_err(|e| {
+            error!("Connection::close fail to close connection, err: {}.", e);
+            e
+        })
     }
 
     #[inline]
     ///Performs send of request
     ///
-    ///It can fail only when connection gets closed.
-    ///Which means OBKV connection is no longer valid.
-    pub fn send(
-        &self,
-        message: ObTablePacket,
-        channel_id: i32,
-    ) -> Result<Receiver<Result<ObTablePacket>>> {
-        let (tx, rx) = bounded(1);
+    ///It fails when connection gets closed, which means OBKV connection is
+    ///no longer valid, or when too much data is waiting to be written into
+    ///the connection.
+    pub fn send(&self, message: ObTablePacket, channel_id: i32) -> Result<ResponseReceiver> {
+        let (tx, rx) = oneshot::channel();
         self.requests.lock().unwrap().insert(channel_id, tx);
         self.sender.request(message).map_err(|e| {
             error!("Connection::send: fail to send message, err:{}", e);
@@ -803,6 +723,8 @@ pub struct Builder {
     user_name: String,
     database_name: String,
     password: String,
+
+    event_loop_group: Option<Arc<EventLoopGroup>>,
 }
 
 const SOCKET_KEEP_ALIVE_SECS: u64 = 15 * 60;
@@ -820,6 +742,7 @@ impl Builder {
             user_name: "".to_owned(),
             database_name: "".to_owned(),
             password: "".to_owned(),
+            event_loop_group: None,
         }
     }
 
@@ -873,6 +796,13 @@ impl Builder {
         self
     }
 
+    /// Set the event loops polling the connection, the default shared event
+    /// loops are used if not set.
+    pub fn event_loop_group(mut self, event_loop_group: Arc<EventLoopGroup>) -> Self {
+        self.event_loop_group = Some(event_loop_group);
+        self
+    }
+
     pub fn build(self) -> Result<Connection> {
         let uuid = Uuid::new_v4();
         let id = BigEndian::read_u32(uuid.as_bytes());
@@ -899,12 +829,17 @@ impl Builder {
 
             stream.set_nodelay(true)?;
             stream.set_read_timeout(Some(self.read_timeout))?;
-            stream.set_nonblocking(false)?;
             stream.set_keepalive(Some(Duration::from_secs(SOCKET_KEEP_ALIVE_SECS)))?;
             stream.set_send_buffer_size(READ_BUF_SIZE)?;
             stream.set_recv_buffer_size(2 * READ_BUF_SIZE)?;
 
-            Connection::internal_new(id, addr, stream)
+            let event_loop = self
+                .event_loop_group
+                .as_ref()
+                .unwrap_or(&DEFAULT_EVENT_LOOP_GROUP)
+                .next();
+
+            Connection::internal_new(id, addr, stream, event_loop)
         } else {
             Err(CommonErr(
                 CommonErrCode::InvalidServerAddr,
@@ -948,7 +883,7 @@ mod test {
         let res = conn
             .send(packet, channel_id)
             .expect("fail to send request")
-            .recv();
+            .wait();
         assert!(res.is_ok());
         assert!(conn.close().is_ok());
     }
@@ -1442,6 +1442,26 @@ impl ResultCodes {
         )
     }
 
+    /// Returns true when the schema of the table the client knows may be out
+    /// of date.
+    pub fn is_schema_error(self) -> bool {
+        matches!(
+            self,
+            ResultCodes::OB_SCHEMA_ERROR
+                | ResultCodes::OB_OLD_SCHEMA_VERSION
+                | ResultCodes::OB_ERR_WAIT_REMOTE_SCHEMA_REFRESH
+                | ResultCodes::OB_ERR_SCHEMA_UNSET
+                | ResultCodes::OB_ERR_INVALID_SCHEMA
+                | ResultCodes::OB_TABLE_NOT_EXIST
+                | ResultCodes::OB_ERR_UNKNOWN_TABLE
+                | ResultCodes::OB_ERR_COLUMN_NOT_FOUND
+                | ResultCodes::OB_ERR_BAD_FIELD_ERROR
+                | ResultCodes::OB_ERR_COULUMN_VALUE_NOT_MATCH
+                | ResultCodes::OB_ERR_DATA_TOO_LONG
+                | ResultCodes::OB_OBJ_TYPE_ERROR
+        )
+    }
+
     pub fn need_retry(self) -> bool {
         if self.need_refresh_table() {
             return true;
@@ -579,6 +579,9 @@ pub trait ObPayload: ProtoEncoder + ProtoDecoder {
     fn set_tenant_id(&mut self, _tenant_id: Option<u64>) {}
     //set credential
     fn set_credential(&mut self, _credential: &[u8]) {}
+    //route by the tablet id instead of the partition id, only implemented in
+    //the requests carrying the partition id
+    fn set_tablet_route(&mut self, _tablet_route: bool) {}
     // set request'rpc header into payload
     fn set_header(&mut self, _header: ObRpcPacketHeader) {}
 }
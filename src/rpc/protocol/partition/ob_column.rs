@@ -17,9 +17,11 @@
 
 use std::{clone::Clone, fmt::Debug};
 
+use chrono::{NaiveDate, NaiveDateTime};
+
 use crate::{
-    error::{CommonErrCode, Error::Common as CommonErr, Result},
-    serde_obkv::value::{CollationLevel, CollationType, ObjMeta, ObjType, Value},
+    error::{CommonErrCode, Error, Error::Common as CommonErr, Result},
+    serde_obkv::value::{decimal::Decimal, CollationLevel, CollationType, ObjMeta, ObjType, Value},
 };
 
 pub trait ObColumn: ObColumnClone + Debug + Send + Sync {
@@ -132,6 +134,20 @@ impl ObSimpleColumn {
             ref_column_names,
         }
     }
+
+    fn unsupported_value(&self, value: &Value) -> Error {
+        error!(
+            "ObSimpleColumn::eval_value unsupported value of column {}, type:{:?}, value:{:?}",
+            self.column_name, self.ob_obj_type, value
+        );
+        CommonErr(
+            CommonErrCode::PartitionError,
+            format!(
+                "ObSimpleColumn::eval_value unsupported value of column {}, type:{:?}, value:{:?}",
+                self.column_name, self.ob_obj_type, value
+            ),
+        )
+    }
 }
 
 impl ObColumn for ObSimpleColumn {
@@ -164,11 +180,16 @@ impl ObColumn for ObSimpleColumn {
             ));
         }
         match self.ob_obj_type {
-            ObjType::Varchar => match &refs[0] {
+            ObjType::Varchar | ObjType::Char => match &refs[0] {
                 Value::String(_v, _meta) => Ok(refs[0].clone()),
-                _ => unimplemented!(),
+                _ => Err(self.unsupported_value(&refs[0])),
             },
-            ObjType::UInt64 | ObjType::Int64 | ObjType::UInt32 | ObjType::Int32 => {
+            ObjType::TinyInt
+            | ObjType::SmallInt
+            | ObjType::UInt64
+            | ObjType::Int64
+            | ObjType::UInt32
+            | ObjType::Int32 => {
                 if refs[0].is_min() || refs[0].is_max() {
                     return Ok(refs[0].clone());
                 }
@@ -182,6 +203,24 @@ impl ObColumn for ObSimpleColumn {
                             10,
                         ),
                     )),
+                    Value::Int8(v, _meta) => Ok(Value::Int64(
+                        v as i64,
+                        ObjMeta::new(
+                            ObjType::Int64,
+                            CollationLevel::Numeric,
+                            CollationType::Binary,
+                            10,
+                        ),
+                    )),
+                    Value::UInt8(v, _meta) => Ok(Value::Int64(
+                        v as i64,
+                        ObjMeta::new(
+                            ObjType::Int64,
+                            CollationLevel::Numeric,
+                            CollationType::Binary,
+                            10,
+                        ),
+                    )),
                     Value::Int32(v, _meta) => Ok(Value::Int64(
                         v as i64,
                         ObjMeta::new(
@@ -218,10 +257,76 @@ impl ObColumn for ObSimpleColumn {
                             10,
                         ),
                     )),
-                    _ => unimplemented!(),
+                    _ => Err(self.unsupported_value(&refs[0])),
+                }
+            }
+            ObjType::Number | ObjType::UNumber => {
+                if refs[0].is_min() || refs[0].is_max() {
+                    return Ok(refs[0].clone());
+                }
+                let decimal = match &refs[0] {
+                    Value::Decimal(v, _meta) => v.clone(),
+                    Value::String(v, _meta) => v.parse::<Decimal>()?,
+                    Value::Int8(v, _meta) => Decimal::from(*v as i64),
+                    Value::UInt8(v, _meta) => Decimal::from(*v as i64),
+                    Value::Int32(v, _meta) => Decimal::from(*v as i64),
+                    Value::UInt32(v, _meta) => Decimal::from(*v as i64),
+                    Value::Int64(v, _meta) => Decimal::from(*v),
+                    Value::UInt64(v, _meta) => Decimal::from(*v),
+                    _ => {
+                        return Err(CommonErr(
+                            CommonErrCode::InvalidParam,
+                            format!(
+                                "ObSimpleColumn::eval_value invalid number, value:{:?}",
+                                refs[0]
+                            ),
+                        ))
+                    }
+                };
+                Ok(Value::from(decimal))
+            }
+            ObjType::Date => {
+                if refs[0].is_min() || refs[0].is_max() {
+                    return Ok(refs[0].clone());
+                }
+                match &refs[0] {
+                    Value::Date(_v, _meta) => Ok(refs[0].clone()),
+                    Value::String(v, _meta) => NaiveDate::parse_from_str(v, "%Y-%m-%d")
+                        .map(Value::from)
+                        .map_err(|_| self.unsupported_value(&refs[0])),
+                    _ => Err(self.unsupported_value(&refs[0])),
+                }
+            }
+            ObjType::DateTime => {
+                if refs[0].is_min() || refs[0].is_max() {
+                    return Ok(refs[0].clone());
+                }
+                match &refs[0] {
+                    Value::DateTime(_v, _meta) => Ok(refs[0].clone()),
+                    // the legacy datetime in milliseconds
+                    Value::Time(v, _meta) => Ok(Value::DateTime(
+                        v * 1000,
+                        ObjMeta::default_obj_meta(ObjType::DateTime),
+                    )),
+                    Value::String(v, _meta) => parse_datetime(v)
+                        .map(Value::from)
+                        .ok_or_else(|| self.unsupported_value(&refs[0])),
+                    _ => Err(self.unsupported_value(&refs[0])),
                 }
             }
-            _ => unimplemented!(),
+            _ => Err(self.unsupported_value(&refs[0])),
         }
     }
 }
+
+/// Parse the datetime in the format of the partition values, the fractional
+/// seconds and the time are optional.
+fn parse_datetime(s: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+}
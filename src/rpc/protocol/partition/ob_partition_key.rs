@@ -63,6 +63,10 @@ impl ObPartitionKey {
     pub fn new(partition_elements: Vec<Comparable>) -> Self {
         Self { partition_elements }
     }
+
+    pub fn partition_elements(&self) -> &[Comparable] {
+        &self.partition_elements
+    }
 }
 
 impl PartialOrd for ObPartitionKey {
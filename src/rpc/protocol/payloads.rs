@@ -33,6 +33,29 @@ use crate::{
     util::{decode_value, duration_to_millis, security, string_from_bytes},
 };
 
+/// The length of the encoded partition id, the tablet id routed to on
+/// OceanBase 4.x is encoded as a fixed-width integer.
+pub(crate) fn encoded_length_partition_id(partition_id: i64, tablet_route: bool) -> usize {
+    if tablet_route {
+        mem::size_of::<i64>()
+    } else {
+        util::encoded_length_vi64(partition_id)
+    }
+}
+
+pub(crate) fn encode_partition_id(
+    partition_id: i64,
+    tablet_route: bool,
+    buf: &mut BytesMut,
+) -> Result<()> {
+    if tablet_route {
+        buf.put_i64_be(partition_id);
+        Ok(())
+    } else {
+        util::encode_vi64(partition_id, buf)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ObTableEntityType {
     Dynamic = 0,
@@ -40,7 +63,7 @@ pub enum ObTableEntityType {
     HKV = 2,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ObTableConsistencyLevel {
     Strong = 0,
     Eventual = 1,
@@ -330,6 +353,7 @@ pub struct ObTableOperationRequest {
     table_name: String,
     table_id: i64,
     partition_id: i64,
+    tablet_route: bool,
     entity_type: ObTableEntityType,
     table_operation: ObTableOperation,
     consistency_level: ObTableConsistencyLevel,
@@ -358,6 +382,7 @@ impl ObTableOperationRequest {
             table_name: table_name.to_owned(),
             table_id: OB_INVALID_ID,
             partition_id: OB_INVALID_ID,
+            tablet_route: false,
             entity_type: ObTableEntityType::Dynamic,
             table_operation: operation,
             consistency_level: ObTableConsistencyLevel::Strong,
@@ -370,6 +395,18 @@ impl ObTableOperationRequest {
     pub fn set_partition_id(&mut self, partition_id: i64) {
         self.partition_id = partition_id;
     }
+
+    pub fn set_return_row_key(&mut self, return_row_key: bool) {
+        self.return_row_key = return_row_key;
+    }
+
+    pub fn set_return_affected_entity(&mut self, return_affected_entity: bool) {
+        self.return_affected_entity = return_affected_entity;
+    }
+
+    pub fn set_consistency_level(&mut self, consistency_level: ObTableConsistencyLevel) {
+        self.consistency_level = consistency_level;
+    }
 }
 
 impl ObPayload for ObTableOperationRequest {
@@ -377,6 +414,10 @@ impl ObPayload for ObTableOperationRequest {
         self.credential = credential.to_owned();
     }
 
+    fn set_tablet_route(&mut self, tablet_route: bool) {
+        self.tablet_route = tablet_route;
+    }
+
     fn pcode(&self) -> ObTablePacketCode {
         ObTablePacketCode::Execute
     }
@@ -394,7 +435,7 @@ impl ObPayload for ObTableOperationRequest {
         Ok(util::encoded_length_bytes_string(&self.credential)
             + util::encoded_length_vstring(&self.table_name)
             + util::encoded_length_vi64(self.table_id)
-            + util::encoded_length_vi64(self.partition_id)
+            + encoded_length_partition_id(self.partition_id, self.tablet_route)
             + util::encoded_length_vi8(self.entity_type as i8)
             + util::encoded_length_vi8(self.consistency_level as i8)
             + util::encoded_length_vi8(self.return_row_key as i8)
@@ -411,7 +452,7 @@ impl ProtoEncoder for ObTableOperationRequest {
         util::encode_bytes_string(&self.credential, buf)?;
         util::encode_vstring(&self.table_name, buf)?;
         util::encode_vi64(self.table_id, buf)?;
-        util::encode_vi64(self.partition_id, buf)?;
+        encode_partition_id(self.partition_id, self.tablet_route, buf)?;
 
         buf.put_i8(self.entity_type as i8);
         self.table_operation.encode(buf)?;
@@ -450,6 +491,7 @@ pub struct ObTableBatchOperation {
     same_type: bool,
     same_properties_names: bool,
     atomic_op: bool,
+    consistency_level: Option<ObTableConsistencyLevel>,
 }
 
 impl Default for ObTableBatchOperation {
@@ -477,6 +519,7 @@ impl ObTableBatchOperation {
             same_type: true,
             same_properties_names: true,
             atomic_op: false,
+            consistency_level: None,
         }
     }
 
@@ -528,6 +571,16 @@ impl ObTableBatchOperation {
         self.atomic_op
     }
 
+    /// Set the consistency level of the batch, which takes effect only if the
+    /// batch is read only.
+    pub fn set_consistency_level(&mut self, consistency_level: ObTableConsistencyLevel) {
+        self.consistency_level = Some(consistency_level);
+    }
+
+    pub fn consistency_level(&self) -> Option<ObTableConsistencyLevel> {
+        self.consistency_level
+    }
+
     pub fn add_op(&mut self, raw_op: RawObTableOperation) {
         if self.raw {
             self.raw_ops.push(raw_op);
@@ -710,6 +763,7 @@ pub struct ObTableBatchOperationRequest {
     table_name: String,
     table_id: i64,
     partition_id: i64,
+    tablet_route: bool,
     entity_type: ObTableEntityType,
     batch_operation: ObTableBatchOperation,
     consistency_level: ObTableConsistencyLevel,
@@ -730,10 +784,14 @@ impl ObTableBatchOperationRequest {
             table_name: batch_operation.table_name.to_owned(),
             table_id: batch_operation.table_id,
             partition_id: batch_operation.partition_id,
+            tablet_route: false,
             entity_type: ObTableEntityType::Dynamic,
             atomic_op: batch_operation.is_atomic_op(),
+            consistency_level: match batch_operation.consistency_level() {
+                Some(level) if batch_operation.is_read_only() => level,
+                _ => ObTableConsistencyLevel::Strong,
+            },
             batch_operation,
-            consistency_level: ObTableConsistencyLevel::Strong,
             return_row_key: false,
             return_affected_entity: false,
             return_affected_rows: true,
@@ -746,6 +804,10 @@ impl ObPayload for ObTableBatchOperationRequest {
         self.credential = credential.to_owned();
     }
 
+    fn set_tablet_route(&mut self, tablet_route: bool) {
+        self.tablet_route = tablet_route;
+    }
+
     fn pcode(&self) -> ObTablePacketCode {
         ObTablePacketCode::BatchExecute
     }
@@ -763,15 +825,14 @@ impl ObPayload for ObTableBatchOperationRequest {
         Ok(util::encoded_length_bytes_string(&self.credential)
             + util::encoded_length_vstring(&self.table_name)
             + util::encoded_length_vi64(self.table_id)
-            + util::encoded_length_vi64(self.partition_id)
+            + encoded_length_partition_id(self.partition_id, self.tablet_route)
             + self.batch_operation.len()?
             + util::encoded_length_vi8(self.entity_type as i8)
             + util::encoded_length_vi8(self.consistency_level as i8)
             + util::encoded_length_vi8(self.return_row_key as i8)
             + util::encoded_length_vi8(self.return_affected_entity as i8)
             + util::encoded_length_vi8(self.return_affected_rows as i8)
-            + util::encoded_length_vi8(self.atomic_op as i8)
-        )
+            + util::encoded_length_vi8(self.atomic_op as i8))
     }
 }
 
@@ -789,7 +850,7 @@ impl ProtoEncoder for ObTableBatchOperationRequest {
         buf.put_i8(self.return_row_key as i8);
         buf.put_i8(self.return_affected_entity as i8);
         buf.put_i8(self.return_affected_rows as i8);
-        util::encode_vi64(self.partition_id, buf)?;
+        encode_partition_id(self.partition_id, self.tablet_route, buf)?;
         buf.put_i8(self.atomic_op as i8);
         Ok(())
     }
@@ -1379,6 +1440,7 @@ mod test {
             table_name: "test".to_owned(),
             table_id: 1,
             partition_id: 1,
+            tablet_route: false,
             entity_type: ObTableEntityType::KV,
             table_operation: ObTableOperation {
                 base: base.clone(),
@@ -1426,6 +1488,52 @@ mod test {
         assert_eq!(req.len().unwrap(), buf.len());
     }
 
+    #[test]
+    fn test_obtable_request_encode_with_tablet_route() {
+        let tablet_id = 200001;
+        let mut req = ObTableOperationRequest::new(
+            "test",
+            ObTableOperationType::Get,
+            vec![Value::from("test")],
+            None,
+            None,
+            time::Duration::new(OP_TIMEOUT as u64, 0),
+            DEFAULT_FLAG,
+        );
+        req.set_partition_id(tablet_id);
+        let partition_len = req.content_len().unwrap();
+        req.set_tablet_route(true);
+        assert_eq!(
+            partition_len - util::encoded_length_vi64(tablet_id) + 8,
+            req.content_len().unwrap()
+        );
+
+        let mut buf = BytesMut::new();
+        assert!(req.encode(&mut buf).is_ok());
+        assert_eq!(req.len().unwrap(), buf.len());
+
+        let mut batch_op = ObTableBatchOperation::new();
+        batch_op.set_table_name("test".to_owned());
+        batch_op.set_partition_id(tablet_id);
+        batch_op.delete(vec![Value::from("test")]);
+        let mut req = ObTableBatchOperationRequest::new(
+            batch_op,
+            time::Duration::new(OP_TIMEOUT as u64, 0),
+            DEFAULT_FLAG,
+        );
+        req.set_tablet_route(true);
+
+        let mut buf = BytesMut::new();
+        assert!(req.encode(&mut buf).is_ok());
+        assert_eq!(req.len().unwrap(), buf.len());
+        // the tablet id is encoded before the atomic flag in big endian
+        let len = buf.len();
+        assert_eq!(
+            &tablet_id.to_be_bytes()[..],
+            &buf[len - 1 - mem::size_of::<i64>()..len - 1]
+        );
+    }
+
     #[test]
     fn test_obtable_batch_operation_properties() {
         let mut batch_op = ObTableBatchOperation::new();
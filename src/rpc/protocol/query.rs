@@ -20,7 +20,10 @@ use std::{io, mem, time::Duration};
 use bytes::{BufMut, BytesMut};
 
 use super::{
-    payloads::{ObRowKey, ObTableConsistencyLevel, ObTableEntityType},
+    payloads::{
+        encode_partition_id, encoded_length_partition_id, ObRowKey, ObTableBatchOperation,
+        ObTableConsistencyLevel, ObTableEntityType,
+    },
     BasePayLoad, ObPayload, ObRpcPacketHeader, ObTablePacketCode, ProtoDecoder, ProtoEncoder,
     Result, STREAM_FLAG, STREAM_LAST_FLAG,
 };
@@ -585,6 +588,10 @@ impl ObTableQuery {
         self.offset = offset;
     }
 
+    pub fn get_limit(&self) -> i32 {
+        self.limit
+    }
+
     pub fn add_key_range(&mut self, key_range: ObNewRange) {
         self.key_ranges.push(key_range);
     }
@@ -609,6 +616,14 @@ impl ObTableQuery {
         &self.key_ranges
     }
 
+    pub fn get_select_columns(&self) -> &[String] {
+        &self.select_columns
+    }
+
+    pub fn get_scan_order(&self) -> &ObScanOrder {
+        &self.scan_order
+    }
+
     /// Verify whether the query is valid.
     pub fn verify(&self) -> error::Result<()> {
         if self.select_columns.is_empty() {
@@ -629,6 +644,7 @@ pub struct ObTableQueryRequest {
     table_name: String,
     table_id: i64,
     partition_id: i64,
+    tablet_route: bool,
     entity_type: ObTableEntityType,
     table_query: ObTableQuery,
     consistency_level: ObTableConsistencyLevel,
@@ -655,6 +671,7 @@ impl ObTableQueryRequest {
             table_name: table_name.to_owned(),
             table_id: OB_INVALID_ID,
             partition_id,
+            tablet_route: false,
             entity_type,
             table_query,
             consistency_level: ObTableConsistencyLevel::Strong,
@@ -664,6 +681,10 @@ impl ObTableQueryRequest {
             _return_affected_rows: true,
         }
     }
+
+    pub fn set_consistency_level(&mut self, consistency_level: ObTableConsistencyLevel) {
+        self.consistency_level = consistency_level;
+    }
 }
 
 impl ObPayload for ObTableQueryRequest {
@@ -671,6 +692,10 @@ impl ObPayload for ObTableQueryRequest {
         self.credential = credential.to_owned();
     }
 
+    fn set_tablet_route(&mut self, tablet_route: bool) {
+        self.tablet_route = tablet_route;
+    }
+
     fn pcode(&self) -> ObTablePacketCode {
         ObTablePacketCode::ExecuteQuery
     }
@@ -688,7 +713,7 @@ impl ObPayload for ObTableQueryRequest {
         Ok(util::encoded_length_bytes_string(&self.credential)
             + util::encoded_length_vstring(&self.table_name)
             + util::encoded_length_vi64(self.table_id)
-            + util::encoded_length_vi64(self.partition_id)
+            + encoded_length_partition_id(self.partition_id, self.tablet_route)
             + self.table_query.len()?
             + 2)
     }
@@ -701,7 +726,7 @@ impl ProtoEncoder for ObTableQueryRequest {
         util::encode_bytes_string(&self.credential, buf)?;
         util::encode_vstring(&self.table_name, buf)?;
         util::encode_vi64(self.table_id, buf)?;
-        util::encode_vi64(self.partition_id, buf)?;
+        encode_partition_id(self.partition_id, self.tablet_route, buf)?;
         buf.put_i8(self.entity_type as i8);
         buf.put_i8(self.consistency_level as i8);
 
@@ -789,3 +814,442 @@ impl ProtoDecoder for ObTableStreamRequest {
         unimplemented!()
     }
 }
+
+/// Options of [`ObTableQueryAndMutate`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObTableQueryAndMutateFlag {
+    flag: i64,
+}
+
+impl ObTableQueryAndMutateFlag {
+    const FLAG_IS_CHECK_AND_EXECUTE: i64 = 1 << 0;
+    const FLAG_IS_CHECK_NOT_EXISTS: i64 = 1 << 1;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn value(&self) -> i64 {
+        self.flag
+    }
+
+    pub fn set_check_and_execute(&mut self, check_and_execute: bool) {
+        if check_and_execute {
+            self.flag |= Self::FLAG_IS_CHECK_AND_EXECUTE;
+        } else {
+            self.flag &= !Self::FLAG_IS_CHECK_AND_EXECUTE;
+        }
+    }
+
+    pub fn is_check_and_execute(&self) -> bool {
+        self.flag & Self::FLAG_IS_CHECK_AND_EXECUTE != 0
+    }
+
+    pub fn set_check_not_exists(&mut self, check_not_exists: bool) {
+        if check_not_exists {
+            self.flag |= Self::FLAG_IS_CHECK_NOT_EXISTS;
+        } else {
+            self.flag &= !Self::FLAG_IS_CHECK_NOT_EXISTS;
+        }
+    }
+
+    pub fn is_check_not_exists(&self) -> bool {
+        self.flag & Self::FLAG_IS_CHECK_NOT_EXISTS != 0
+    }
+}
+
+/// Mutate the rows matched by the query on the server side.
+///
+/// The mutations are applied to every matched row by default. With
+/// `check_and_execute` set, the query is used as a check instead: the
+/// mutations are executed only if any row is matched, or only if no row is
+/// matched with `check_not_exists` set. The check is only supported by
+/// OceanBase 4.x, which is the only version deserializing the option flag.
+#[derive(Debug, Clone)]
+pub struct ObTableQueryAndMutate {
+    base: BasePayLoad,
+    table_query: ObTableQuery,
+    mutations: ObTableBatchOperation,
+    return_affected_entity: bool,
+    option_flag: ObTableQueryAndMutateFlag,
+    encode_option_flag: bool,
+}
+
+impl ObTableQueryAndMutate {
+    pub fn new(table_query: ObTableQuery, mutations: ObTableBatchOperation) -> Self {
+        Self {
+            base: BasePayLoad::dummy(),
+            table_query,
+            mutations,
+            return_affected_entity: false,
+            option_flag: ObTableQueryAndMutateFlag::new(),
+            encode_option_flag: false,
+        }
+    }
+
+    pub fn table_query(&self) -> &ObTableQuery {
+        &self.table_query
+    }
+
+    pub fn mutations(&self) -> &ObTableBatchOperation {
+        &self.mutations
+    }
+
+    pub fn set_return_affected_entity(&mut self, return_affected_entity: bool) {
+        self.return_affected_entity = return_affected_entity;
+    }
+
+    pub fn is_return_affected_entity(&self) -> bool {
+        self.return_affected_entity
+    }
+
+    pub fn set_check_and_execute(&mut self, check_and_execute: bool) {
+        self.option_flag.set_check_and_execute(check_and_execute);
+    }
+
+    pub fn is_check_and_execute(&self) -> bool {
+        self.option_flag.is_check_and_execute()
+    }
+
+    pub fn set_check_not_exists(&mut self, check_not_exists: bool) {
+        self.option_flag.set_check_not_exists(check_not_exists);
+    }
+
+    pub fn is_check_not_exists(&self) -> bool {
+        self.option_flag.is_check_not_exists()
+    }
+
+    /// Whether to encode the option flag, set for OceanBase 4.x only.
+    pub(crate) fn set_encode_option_flag(&mut self, encode_option_flag: bool) {
+        self.encode_option_flag = encode_option_flag;
+    }
+
+    fn option_flag_len(&self) -> usize {
+        if self.encode_option_flag {
+            util::encoded_length_vi64(self.option_flag.value())
+        } else {
+            0
+        }
+    }
+
+    /// Verify whether the request is valid.
+    pub fn verify(&self) -> error::Result<()> {
+        if self.table_query.get_key_ranges().is_empty() {
+            return Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                "QueryAndMutate scan range is empty.".to_owned(),
+            ));
+        }
+
+        if self.mutations.get_ops().is_empty() {
+            return Err(CommonErr(
+                CommonErrCode::InvalidParam,
+                "QueryAndMutate mutations is empty.".to_owned(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ObPayload for ObTableQueryAndMutate {
+    fn base(&self) -> &BasePayLoad {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BasePayLoad {
+        &mut self.base
+    }
+
+    //payload size, without header bytes
+    fn content_len(&self) -> Result<usize> {
+        Ok(self.table_query.len()? + self.mutations.len()? + 1 + self.option_flag_len())
+    }
+}
+
+impl ProtoEncoder for ObTableQueryAndMutate {
+    fn encode(&self, buf: &mut BytesMut) -> Result<()> {
+        self.encode_header(buf)?;
+
+        self.table_query.encode(buf)?;
+        self.mutations.encode(buf)?;
+        buf.put_i8(self.return_affected_entity as i8);
+        if self.encode_option_flag {
+            util::encode_vi64(self.option_flag.value(), buf)?;
+        } else if self.option_flag.value() != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "ObTableQueryAndMutate::encode check and execute requires OceanBase 4.x",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ProtoDecoder for ObTableQueryAndMutate {
+    fn decode(&mut self, _src: &mut BytesMut) -> Result<()> {
+        unimplemented!();
+    }
+}
+
+pub struct ObTableQueryAndMutateRequest {
+    base: BasePayLoad,
+    credential: Vec<u8>,
+    table_name: String,
+    table_id: i64,
+    partition_id: i64,
+    tablet_route: bool,
+    entity_type: ObTableEntityType,
+    query_and_mutate: ObTableQueryAndMutate,
+}
+
+impl ObTableQueryAndMutateRequest {
+    pub fn new(
+        table_name: &str,
+        partition_id: i64,
+        entity_type: ObTableEntityType,
+        query_and_mutate: ObTableQueryAndMutate,
+        timeout: Duration,
+        flag: u16,
+    ) -> Self {
+        let mut base = BasePayLoad::new();
+        base.timeout = duration_to_millis(&timeout);
+        base.flag = flag;
+        Self {
+            base,
+            credential: vec![],
+            table_name: table_name.to_owned(),
+            table_id: OB_INVALID_ID,
+            partition_id,
+            tablet_route: false,
+            entity_type,
+            query_and_mutate,
+        }
+    }
+}
+
+impl ObPayload for ObTableQueryAndMutateRequest {
+    fn set_credential(&mut self, credential: &[u8]) {
+        self.credential = credential.to_owned();
+    }
+
+    fn set_tablet_route(&mut self, tablet_route: bool) {
+        self.tablet_route = tablet_route;
+        // the requests are routed by the tablet id on OceanBase 4.x only,
+        // which is also the only version deserializing the option flag
+        self.query_and_mutate.set_encode_option_flag(tablet_route);
+    }
+
+    fn pcode(&self) -> ObTablePacketCode {
+        ObTablePacketCode::QueryAndMute
+    }
+
+    fn base(&self) -> &BasePayLoad {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BasePayLoad {
+        &mut self.base
+    }
+
+    //payload size, without header bytes
+    fn content_len(&self) -> Result<usize> {
+        Ok(util::encoded_length_bytes_string(&self.credential)
+            + util::encoded_length_vstring(&self.table_name)
+            + util::encoded_length_vi64(self.table_id)
+            + encoded_length_partition_id(self.partition_id, self.tablet_route)
+            + 1
+            + self.query_and_mutate.len()?)
+    }
+}
+
+impl ProtoEncoder for ObTableQueryAndMutateRequest {
+    fn encode(&self, buf: &mut BytesMut) -> Result<()> {
+        self.encode_header(buf)?;
+
+        util::encode_bytes_string(&self.credential, buf)?;
+        util::encode_vstring(&self.table_name, buf)?;
+        util::encode_vi64(self.table_id, buf)?;
+        encode_partition_id(self.partition_id, self.tablet_route, buf)?;
+        buf.put_i8(self.entity_type as i8);
+
+        self.query_and_mutate.encode(buf)?;
+
+        Ok(())
+    }
+}
+
+impl ProtoDecoder for ObTableQueryAndMutateRequest {
+    fn decode(&mut self, _src: &mut BytesMut) -> Result<()> {
+        unimplemented!()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObTableQueryAndMutateResult {
+    base: BasePayLoad,
+    affected_rows: i64,
+    affected_entity: ObTableQueryResult,
+}
+
+impl Default for ObTableQueryAndMutateResult {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ObTableQueryAndMutateResult {
+    pub fn new() -> Self {
+        Self {
+            base: BasePayLoad::dummy(),
+            affected_rows: 0,
+            affected_entity: ObTableQueryResult::new(),
+        }
+    }
+
+    pub fn affected_rows(&self) -> i64 {
+        self.affected_rows
+    }
+
+    /// The affected rows, returned only if `return_affected_entity` is set.
+    pub fn affected_entity(&self) -> &ObTableQueryResult {
+        &self.affected_entity
+    }
+
+    pub fn take_affected_entity(self) -> ObTableQueryResult {
+        self.affected_entity
+    }
+
+    /// Merge the result of another partition into this one.
+    pub fn merge(&mut self, mut other: ObTableQueryAndMutateResult) {
+        self.affected_rows += other.affected_rows;
+
+        let entity = &mut self.affected_entity;
+        let other_entity = &mut other.affected_entity;
+        if entity.properties_names.is_empty() {
+            entity.properties_names = other_entity.take_properties_names();
+        }
+        entity.row_count += other_entity.row_count;
+        entity
+            .properties_rows
+            .append(&mut other_entity.properties_rows);
+    }
+}
+
+impl ObPayload for ObTableQueryAndMutateResult {
+    fn pcode(&self) -> ObTablePacketCode {
+        ObTablePacketCode::QueryAndMute
+    }
+
+    fn base(&self) -> &BasePayLoad {
+        &self.base
+    }
+
+    fn base_mut(&mut self) -> &mut BasePayLoad {
+        &mut self.base
+    }
+}
+
+impl ProtoEncoder for ObTableQueryAndMutateResult {
+    fn encode(&self, _buf: &mut BytesMut) -> Result<()> {
+        unimplemented!();
+    }
+}
+
+impl ProtoDecoder for ObTableQueryAndMutateResult {
+    fn decode(&mut self, src: &mut BytesMut) -> Result<()> {
+        self.decode_base(src)?;
+
+        self.affected_rows = util::decode_vi64(src)?;
+        self.affected_entity.decode(src)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rpc::protocol::DEFAULT_FLAG;
+
+    fn new_query_and_mutate() -> ObTableQueryAndMutate {
+        let mut table_query = ObTableQuery::new();
+        table_query.add_key_range(ObNewRange::from_keys(
+            vec![Value::from("a")],
+            vec![Value::from("z")],
+        ));
+        table_query.select_columns(vec!["c2".to_owned()]);
+        table_query.set_filter_string("TableCompareFilter(=, 'c2:v')".to_owned());
+
+        let mut mutations = ObTableBatchOperation::new();
+        mutations.update(vec![], vec!["c2".to_owned()], vec![Value::from("v2")]);
+        ObTableQueryAndMutate::new(table_query, mutations)
+    }
+
+    #[test]
+    fn test_query_and_mutate_request_encode() {
+        let mut query_and_mutate = new_query_and_mutate();
+        query_and_mutate.set_return_affected_entity(true);
+        query_and_mutate.set_check_and_execute(true);
+        query_and_mutate.set_check_not_exists(true);
+        assert!(query_and_mutate.verify().is_ok());
+        assert!(query_and_mutate.is_check_and_execute());
+        assert!(query_and_mutate.is_check_not_exists());
+
+        let mut req = ObTableQueryAndMutateRequest::new(
+            "test",
+            1,
+            ObTableEntityType::Dynamic,
+            query_and_mutate,
+            Duration::from_secs(3),
+            DEFAULT_FLAG,
+        );
+        req.set_credential("test".as_bytes());
+        req.set_tablet_route(true);
+
+        let mut buf = BytesMut::new();
+        let ret = req.encode(&mut buf);
+        assert!(ret.is_ok());
+        assert_eq!(req.len().unwrap(), buf.len());
+
+        // the check can't be sent to the servers before 4.x
+        req.set_tablet_route(false);
+        assert!(req.encode(&mut BytesMut::new()).is_err());
+    }
+
+    #[test]
+    fn test_query_and_mutate_encode_option_flag() {
+        // OceanBase 4.x
+        let mut query_and_mutate = new_query_and_mutate();
+        query_and_mutate.set_check_and_execute(true);
+        query_and_mutate.set_encode_option_flag(true);
+        let mut buf = BytesMut::new();
+        assert!(query_and_mutate.encode(&mut buf).is_ok());
+        assert_eq!(query_and_mutate.len().unwrap(), buf.len());
+        assert_eq!(1, buf[buf.len() - 1]);
+
+        // the servers before 4.x
+        let mut old_query_and_mutate = new_query_and_mutate();
+        old_query_and_mutate.set_encode_option_flag(false);
+        let mut old_buf = BytesMut::new();
+        assert!(old_query_and_mutate.encode(&mut old_buf).is_ok());
+        assert_eq!(old_query_and_mutate.len().unwrap(), old_buf.len());
+        assert_eq!(
+            query_and_mutate.content_len().unwrap() - 1,
+            old_query_and_mutate.content_len().unwrap()
+        );
+        assert_eq!(0, old_buf[old_buf.len() - 1]);
+    }
+
+    #[test]
+    fn test_query_and_mutate_flag() {
+        let mut flag = ObTableQueryAndMutateFlag::new();
+        assert_eq!(0, flag.value());
+        flag.set_check_and_execute(true);
+        flag.set_check_not_exists(true);
+        assert_eq!(3, flag.value());
+        flag.set_check_and_execute(false);
+        assert!(!flag.is_check_and_execute());
+        assert!(flag.is_check_not_exists());
+    }
+}
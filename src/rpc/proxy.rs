@@ -17,10 +17,14 @@
 
 use std::sync::Arc;
 
+use futures::{
+    future::{self, Loop},
+    Future,
+};
 use prometheus::*;
 
 use super::{conn_pool::ConnPool, protocol::ObPayload};
-use crate::error::Result;
+use crate::{error::Result, util::BoxFuture};
 
 lazy_static! {
     pub static ref OBKV_PROXY_HISTOGRAM_NUM_VEC: HistogramVec = register_histogram_vec!(
@@ -32,6 +36,9 @@ lazy_static! {
     .unwrap();
 }
 
+// (payload, retry count, retry limit)
+type AsyncRetryState<T> = (T, usize, Option<usize>);
+
 #[derive(Clone)]
 pub struct Proxy(Arc<ConnPool>);
 
@@ -98,4 +105,74 @@ impl Proxy {
             err = res.err().unwrap();
         }
     }
+
+    /// Asynchronous version of [`Proxy::execute`], it retries on broken
+    /// connections in the same way without blocking the caller.
+    pub fn execute_async<T, R>(&self, payload: T) -> BoxFuture<R>
+    where
+        T: ObPayload + Send + 'static,
+        R: ObPayload + Default + Send + 'static,
+    {
+        let pool = self.0.clone();
+        Box::new(future::loop_fn((payload, 0, None), move |state| {
+            Self::execute_once_async(&pool, state)
+        }))
+    }
+
+    fn execute_once_async<T, R>(
+        pool: &Arc<ConnPool>,
+        (mut payload, retry_cnt, retry_limit): AsyncRetryState<T>,
+    ) -> BoxFuture<Loop<R, AsyncRetryState<T>>>
+    where
+        T: ObPayload + Send + 'static,
+        R: ObPayload + Default + Send + 'static,
+    {
+        let conn = box_future_try!(pool.get());
+
+        OBKV_PROXY_HISTOGRAM_NUM_VEC
+            .with_label_values(&["conn_load"])
+            .observe(conn.load() as f64);
+
+        let res = conn.clone().execute_async(&mut payload, R::default());
+        let pool = pool.clone();
+        Box::new(res.then(move |res| {
+            if res.is_ok() || conn.is_active() {
+                if retry_cnt > 0 {
+                    OBKV_PROXY_HISTOGRAM_NUM_VEC
+                        .with_label_values(&["retry_times"])
+                        .observe(retry_cnt as f64);
+                }
+                return res.map(Loop::Break);
+            }
+
+            let err = res.err().unwrap();
+            // retry until all the idle connections are consumed and then a brand new
+            // connection is built or an intact connection is taken because all the
+            // connections may be broken together
+            let retry_limit = retry_limit.unwrap_or_else(|| {
+                let retry_limit = pool.idle_conn_num() + 1;
+                OBKV_PROXY_HISTOGRAM_NUM_VEC
+                    .with_label_values(&["retry_idle_conns"])
+                    .observe((retry_limit - 1) as f64);
+                retry_limit
+            });
+            let retry_cnt = retry_cnt + 1;
+            if retry_cnt > retry_limit {
+                OBKV_PROXY_HISTOGRAM_NUM_VEC
+                    .with_label_values(&["retry_times"])
+                    .observe(retry_cnt as f64);
+
+                error!(
+                    "Proxy::execute_async reach the retry limit:{}, err:{}",
+                    retry_limit, err
+                );
+                return Err(err);
+            }
+            debug!(
+                "Proxy::execute_async retry {} because connection broken, err:{}",
+                retry_cnt, err
+            );
+            Ok(Loop::Continue((payload, retry_cnt, Some(retry_limit))))
+        }))
+    }
 }
@@ -83,7 +83,14 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
             Value::Double(f, _) => visitor.visit_f64(f),
             Value::Bytes(ref vc, _) => visitor.visit_bytes(&vc[..]),
             Value::String(ref s, _) => visitor.visit_str(&s),
-            _ => self.deserialize_map(visitor),
+            Value::Decimal(ref d, _) => visitor.visit_string(d.to_string()),
+            Value::Date(d, _) => visitor.visit_i32(d),
+            Value::Time(t, _)
+            | Value::DateTime(t, _)
+            | Value::Timestamp(t, _)
+            | Value::TimeOfDay(t, _) => visitor.visit_i64(t),
+            Value::Year(y, _) => visitor.visit_u16(y),
+            Value::Bit(v, _) | Value::Enum(v, _) | Value::Set(v, _) => visitor.visit_u64(v),
         }
     }
 
@@ -0,0 +1,366 @@
+/*-
+ * #%L
+ * OBKV Table Client Framework
+ * %%
+ * Copyright (C) 2021 OceanBase
+ * %%
+ * OBKV Table Client Framework is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *          http://license.coscl.org.cn/MulanPSL2
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ * #L%
+ */
+
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use bytes::BytesMut;
+
+use super::super::{
+    error::{Error, Result},
+    util::{decode_vi32, encode_vi32, encoded_length_vi32},
+};
+
+// the digits are stored in base 10^9 like OceanBase
+const BASE: u32 = 1_000_000_000;
+const DIGITS_PER_BASE: usize = 9;
+// the exponent is biased by it in the desc
+const EXP_ZERO: i32 = 0x40;
+const MAX_EXP: i32 = 0x3f;
+const MIN_EXP: i32 = -0x40;
+
+/// The exact decimal number of the NUMBER and the DECIMAL columns, which is
+/// kept in the form of OceanBase: the value is the sum of `digits[i] *
+/// 10^(9 * (exp - i))`.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Decimal {
+    negative: bool,
+    exp: i32,
+    // no leading nor trailing zero digits, empty for zero
+    digits: Vec<u32>,
+}
+
+impl Decimal {
+    fn new(negative: bool, exp: i32, mut digits: Vec<u32>) -> Result<Decimal> {
+        let leading_zeros = digits.iter().take_while(|d| **d == 0).count();
+        digits.drain(..leading_zeros);
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        if digits.is_empty() {
+            return Ok(Decimal::default());
+        }
+
+        let exp = exp - leading_zeros as i32;
+        if exp < MIN_EXP || exp > MAX_EXP {
+            return Err(Error::Custom(
+                format!("Decimal::new exponent out of range, exp={}", exp).into(),
+            ));
+        }
+        Ok(Decimal {
+            negative,
+            exp,
+            digits,
+        })
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The number of the digits after the decimal point.
+    pub fn scale(&self) -> usize {
+        let lowest = self.lowest_exp();
+        if lowest >= 0 {
+            return 0;
+        }
+        let mut last = self.digits[self.digits.len() - 1];
+        let mut trailing_zeros = 0;
+        while last % 10 == 0 {
+            last /= 10;
+            trailing_zeros += 1;
+        }
+        (-lowest) as usize * DIGITS_PER_BASE - trailing_zeros
+    }
+
+    fn lowest_exp(&self) -> i32 {
+        self.exp - (self.digits.len() as i32 - 1)
+    }
+
+    fn digit_at(&self, exp: i32) -> u32 {
+        if exp > self.exp || exp < self.lowest_exp() {
+            0
+        } else {
+            self.digits[(self.exp - exp) as usize]
+        }
+    }
+
+    fn signum(&self) -> i8 {
+        if self.is_zero() {
+            0
+        } else if self.negative {
+            -1
+        } else {
+            1
+        }
+    }
+
+    // the length, the sign and the biased exponent packed like the desc of
+    // ObNumber
+    fn desc(&self) -> u32 {
+        if self.is_zero() {
+            return 0x8000_0000;
+        }
+        let (sign, exp) = if self.negative {
+            (0, EXP_ZERO - self.exp)
+        } else {
+            (1, EXP_ZERO + self.exp)
+        };
+        let se = (sign << 7) | (exp as u32 & 0x7f);
+        (se << 24) | self.digits.len() as u32
+    }
+
+    pub fn encoded_len(&self) -> usize {
+        self.digits
+            .iter()
+            .fold(encoded_length_vi32(self.desc() as i32), |len, d| {
+                len + encoded_length_vi32(*d as i32)
+            })
+    }
+
+    pub fn encode(&self, buf: &mut BytesMut) -> Result<()> {
+        encode_vi32(self.desc() as i32, buf)?;
+        for d in &self.digits {
+            encode_vi32(*d as i32, buf)?;
+        }
+        Ok(())
+    }
+
+    pub fn decode(buf: &mut BytesMut) -> Result<Decimal> {
+        let desc = decode_vi32(buf)? as u32;
+        let len = (desc & 0xff) as usize;
+        let se = desc >> 24;
+        let negative = se & 0x80 == 0;
+        let exp = if negative {
+            EXP_ZERO - (se & 0x7f) as i32
+        } else {
+            (se & 0x7f) as i32 - EXP_ZERO
+        };
+
+        let mut digits = Vec::with_capacity(len);
+        for _ in 0..len {
+            let d = decode_vi32(buf)? as u32;
+            if d >= BASE {
+                return Err(Error::Custom(
+                    format!("Decimal::decode invalid digit, digit={}", d).into(),
+                ));
+            }
+            digits.push(d);
+        }
+        Decimal::new(negative, exp, digits)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Decimal> {
+        let invalid = || Error::Custom(format!("Decimal::from_str invalid decimal:{}", s).into());
+        let s = s.trim();
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (int_part, frac_part) = match unsigned.find('.') {
+            Some(i) => (&unsigned[..i], &unsigned[i + 1..]),
+            None => (unsigned, ""),
+        };
+        if int_part.is_empty() && frac_part.is_empty()
+            || !int_part
+                .chars()
+                .chain(frac_part.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let int_part = int_part.trim_start_matches('0');
+        let frac_part = frac_part.trim_end_matches('0');
+        // align the integer part to the left and the fraction to the right
+        let int_padding = (DIGITS_PER_BASE - int_part.len() % DIGITS_PER_BASE) % DIGITS_PER_BASE;
+        let frac_padding = (DIGITS_PER_BASE - frac_part.len() % DIGITS_PER_BASE) % DIGITS_PER_BASE;
+        let aligned = format!(
+            "{}{}{}{}",
+            "0".repeat(int_padding),
+            int_part,
+            frac_part,
+            "0".repeat(frac_padding)
+        );
+        let digits = aligned
+            .as_bytes()
+            .chunks(DIGITS_PER_BASE)
+            .map(|chunk| chunk.iter().fold(0u32, |d, c| d * 10 + u32::from(c - b'0')))
+            .collect();
+        let exp = ((int_part.len() + int_padding) / DIGITS_PER_BASE) as i32 - 1;
+        Decimal::new(negative, exp, digits)
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        if self.exp < 0 {
+            write!(f, "0")?;
+        } else {
+            write!(f, "{}", self.digit_at(self.exp))?;
+            for exp in (0..self.exp).rev() {
+                write!(f, "{:09}", self.digit_at(exp))?;
+            }
+        }
+
+        let lowest = self.lowest_exp();
+        if lowest < 0 {
+            let mut frac = String::with_capacity((-lowest) as usize * DIGITS_PER_BASE);
+            for exp in (lowest..0).rev() {
+                frac.push_str(&format!("{:09}", self.digit_at(exp)));
+            }
+            write!(f, ".{}", frac.trim_end_matches('0'))?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        let (signum, other_signum) = (self.signum(), other.signum());
+        if signum != other_signum {
+            return signum.cmp(&other_signum);
+        }
+        // the digits are normalized, so the longer ones are larger in the
+        // same exponent
+        let ordering = self
+            .exp
+            .cmp(&other.exp)
+            .then_with(|| self.digits.cmp(&other.digits));
+        if self.negative {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(v: i64) -> Decimal {
+        let mut decimal = Decimal::from(v.unsigned_abs());
+        decimal.negative = v < 0;
+        decimal
+    }
+}
+
+impl From<u64> for Decimal {
+    fn from(mut v: u64) -> Decimal {
+        let mut digits = Vec::with_capacity(3);
+        while v > 0 {
+            digits.insert(0, (v % u64::from(BASE)) as u32);
+            v /= u64::from(BASE);
+        }
+        let exp = digits.len() as i32 - 1;
+        // at most 3 digits, which is always in range
+        Decimal::new(false, exp, digits).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decimal(s: &str) -> Decimal {
+        s.parse().expect("fail to parse decimal")
+    }
+
+    #[test]
+    fn test_decimal_string() {
+        for s in &[
+            "0",
+            "1",
+            "-1",
+            "123.45",
+            "-0.000000001",
+            "1000000000",
+            "12345678901234567890.0987654321",
+            "0.5",
+        ] {
+            assert_eq!(*s, decimal(s).to_string());
+        }
+        assert_eq!("100", decimal("00100.000").to_string());
+        assert_eq!("0", decimal("-0.0").to_string());
+        assert_eq!(Decimal::from(-1_000_000_001i64), decimal("-1000000001"));
+        assert_eq!(2, decimal("1.50").scale());
+        assert_eq!(10, decimal("0.0000000001").scale());
+
+        assert!("".parse::<Decimal>().is_err());
+        assert!(".".parse::<Decimal>().is_err());
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        assert!("1e5".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_decimal_cmp() {
+        let mut decimals: Vec<Decimal> = ["10", "-1.5", "0", "2", "-10", "1.5", "1.05"]
+            .iter()
+            .map(|s| decimal(s))
+            .collect();
+        decimals.sort();
+        let sorted: Vec<String> = decimals.iter().map(|d| d.to_string()).collect();
+        assert_eq!(vec!["-10", "-1.5", "0", "1.05", "1.5", "2", "10"], sorted);
+    }
+
+    #[test]
+    fn test_decimal_codec() {
+        for s in &[
+            "0",
+            "123.45",
+            "-123.45",
+            "-0.000000001",
+            "99999999999999999999",
+        ] {
+            let d = decimal(s);
+            let mut buf = BytesMut::with_capacity(d.encoded_len());
+            d.encode(&mut buf).expect("fail to encode decimal");
+            assert_eq!(d.encoded_len(), buf.len());
+            assert_eq!(
+                d,
+                Decimal::decode(&mut buf).expect("fail to decode decimal")
+            );
+        }
+
+        // 1.5 is 1 * 10^0 + 500000000 * 10^-9 with the exponent biased
+        let mut buf = BytesMut::with_capacity(16);
+        decimal("1.5").encode(&mut buf).unwrap();
+        let desc = decode_vi32(&mut buf).unwrap() as u32;
+        assert_eq!(0xc000_0002, desc);
+        assert_eq!(1, decode_vi32(&mut buf).unwrap());
+        assert_eq!(500_000_000, decode_vi32(&mut buf).unwrap());
+    }
+}
@@ -15,9 +15,41 @@
  * #L%
  */
 
-use std::borrow::Cow;
+use std::{borrow::Cow, convert::TryFrom};
 
-use super::{ObjMeta, ObjType, Value};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+
+use super::{
+    super::error::{Error, Result},
+    decimal::Decimal,
+    CollationLevel, CollationType, ObjMeta, ObjType, Value,
+};
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_DAY: i64 = 86_400 * MICROS_PER_SECOND;
+
+macro_rules! try_from_value {
+    ($($ty:ty, $conv:ident)*) => {
+        $(
+            impl TryFrom<Value> for $ty {
+                type Error = Error;
+
+                fn try_from(v: Value) -> Result<Self> {
+                    $conv(&v).ok_or_else(|| {
+                        Error::Custom(
+                            format!(
+                                "Fail to convert value to {}, value:{:?}",
+                                stringify!($ty),
+                                v
+                            )
+                            .into(),
+                        )
+                    })
+                }
+            }
+        )*
+    };
+}
 
 macro_rules! from_i32 {
     ($($tx:ident,$ty:ident)*) => {
@@ -130,7 +162,114 @@ impl<'a> From<&'a [u8]> for Value {
     }
 }
 
-//TODO date and time
+impl From<Decimal> for Value {
+    fn from(d: Decimal) -> Self {
+        // the scale is unknown if it doesn't fit
+        let scale = if d.scale() <= i8::MAX as usize {
+            d.scale() as i8
+        } else {
+            -1
+        };
+        let meta = ObjMeta::new(
+            ObjType::Number,
+            CollationLevel::Numeric,
+            CollationType::Binary,
+            scale,
+        );
+        Value::Decimal(d, meta)
+    }
+}
+
+fn epoch_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
+}
+
+fn datetime_to_micros(d: &NaiveDateTime) -> i64 {
+    d.timestamp() * MICROS_PER_SECOND + i64::from(d.timestamp_subsec_micros())
+}
+
+fn micros_to_datetime(micros: i64) -> Option<NaiveDateTime> {
+    NaiveDateTime::from_timestamp_opt(
+        micros.div_euclid(MICROS_PER_SECOND),
+        (micros.rem_euclid(MICROS_PER_SECOND) * 1000) as u32,
+    )
+}
+
+impl From<NaiveDate> for Value {
+    fn from(d: NaiveDate) -> Self {
+        let days = d.signed_duration_since(epoch_date()).num_days();
+        Value::Date(days as i32, ObjMeta::default_obj_meta(ObjType::Date))
+    }
+}
+
+impl From<NaiveDateTime> for Value {
+    fn from(d: NaiveDateTime) -> Self {
+        Value::DateTime(
+            datetime_to_micros(&d),
+            ObjMeta::default_obj_meta(ObjType::DateTime),
+        )
+    }
+}
+
+impl From<DateTime<Utc>> for Value {
+    fn from(d: DateTime<Utc>) -> Self {
+        Value::Timestamp(
+            datetime_to_micros(&d.naive_utc()),
+            ObjMeta::default_obj_meta(ObjType::Timestamp),
+        )
+    }
+}
+
+impl From<NaiveTime> for Value {
+    fn from(t: NaiveTime) -> Self {
+        let micros = i64::from(t.num_seconds_from_midnight()) * MICROS_PER_SECOND
+            + i64::from(t.nanosecond() / 1000);
+        Value::TimeOfDay(micros, ObjMeta::default_obj_meta(ObjType::Time))
+    }
+}
+
+fn to_naive_date(v: &Value) -> Option<NaiveDate> {
+    match v {
+        Value::Date(days, _) => epoch_date().checked_add_signed(Duration::days(i64::from(*days))),
+        _ => None,
+    }
+}
+
+fn to_naive_datetime(v: &Value) -> Option<NaiveDateTime> {
+    match v {
+        Value::DateTime(micros, _) => micros_to_datetime(*micros),
+        _ => None,
+    }
+}
+
+fn to_utc_datetime(v: &Value) -> Option<DateTime<Utc>> {
+    match v {
+        Value::Timestamp(micros, _) => {
+            micros_to_datetime(*micros).map(|d| DateTime::<Utc>::from_utc(d, Utc))
+        }
+        _ => None,
+    }
+}
+
+// the time beyond the day is not a time of the day
+fn to_naive_time(v: &Value) -> Option<NaiveTime> {
+    match v {
+        Value::TimeOfDay(micros, _) if (0..MICROS_PER_DAY).contains(micros) => {
+            NaiveTime::from_num_seconds_from_midnight_opt(
+                (micros / MICROS_PER_SECOND) as u32,
+                ((micros % MICROS_PER_SECOND) * 1000) as u32,
+            )
+        }
+        _ => None,
+    }
+}
+
+try_from_value! {
+    NaiveDate, to_naive_date
+    NaiveDateTime, to_naive_datetime
+    DateTime<Utc>, to_utc_datetime
+    NaiveTime, to_naive_time
+}
 
 #[cfg(test)]
 mod test {
@@ -141,4 +280,41 @@ mod test {
         let v = Value::from(32);
         assert_eq!(32, v.as_i32());
     }
+
+    #[test]
+    fn test_chrono_conversions() {
+        let date = NaiveDate::from_ymd_opt(2023, 2, 28).unwrap();
+        let v = Value::from(date);
+        assert_eq!(
+            Value::Date(19_416, ObjMeta::default_obj_meta(ObjType::Date)),
+            v
+        );
+        assert_eq!(date, NaiveDate::try_from(v).unwrap());
+
+        let datetime = NaiveDate::from_ymd_opt(1969, 12, 31)
+            .unwrap()
+            .and_hms_micro_opt(23, 59, 59, 123_456)
+            .unwrap();
+        let v = Value::from(datetime);
+        assert_eq!(
+            Value::DateTime(-876_544, ObjMeta::default_obj_meta(ObjType::DateTime)),
+            v
+        );
+        assert_eq!(datetime, NaiveDateTime::try_from(v).unwrap());
+
+        let timestamp = DateTime::<Utc>::from_utc(datetime, Utc);
+        let v = Value::from(timestamp);
+        assert!(NaiveDateTime::try_from(v.clone()).is_err());
+        assert_eq!(timestamp, DateTime::<Utc>::try_from(v).unwrap());
+
+        let time = NaiveTime::from_hms_micro_opt(1, 2, 3, 4).unwrap();
+        let v = Value::from(time);
+        assert_eq!(
+            Value::TimeOfDay(3_723_000_004, ObjMeta::default_obj_meta(ObjType::Time)),
+            v
+        );
+        assert_eq!(time, NaiveTime::try_from(v).unwrap());
+        let v = Value::TimeOfDay(-1, ObjMeta::default_obj_meta(ObjType::Time));
+        assert!(NaiveTime::try_from(v).is_err());
+    }
 }
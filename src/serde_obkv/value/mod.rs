@@ -21,12 +21,14 @@
 #![allow(clippy::derive_hash_xor_eq)]
 #![allow(clippy::wrong_self_convention)]
 
+pub mod decimal;
 pub mod from;
 use std::hash::{Hash, Hasher};
 
 use bytes::{Buf, BufMut, BytesMut, IntoBuf};
 use serde::ser::{Serialize, Serializer};
 
+use self::decimal::Decimal;
 use super::{
     error::{Error, Result},
     util::{
@@ -133,7 +135,12 @@ pub enum ObjType {
     Text = 28,
     MediumText = 29,
     LongText = 30,
-    Bit = 31, //TODO
+    Bit = 31,
+    Enum = 32,
+    Set = 33,
+    // NOTE: only the type is known, the values are sent in the binary json
+    // form inside the lob, which is not supported, see `Value::decode`.
+    Json = 47,
 }
 
 impl ObjType {
@@ -168,6 +175,9 @@ impl ObjType {
             29 => Ok(ObjType::MediumText),
             30 => Ok(ObjType::LongText),
             31 => Ok(ObjType::Bit),
+            32 => Ok(ObjType::Enum),
+            33 => Ok(ObjType::Set),
+            47 => Ok(ObjType::Json),
             _ => Err(Error::Custom(
                 format!("ObjType::from_u8 invalid ob obj type, v={}", v).into(),
             )),
@@ -176,22 +186,31 @@ impl ObjType {
 }
 
 //Represent any valid OBKV value.
+//The json values are not supported yet, so there is no variant for them.
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub enum Value {
     Null(ObjMeta),
-    Bool(bool, ObjMeta),     //tinyint
-    Int8(i8, ObjMeta),       //tinyint
-    UInt8(u8, ObjMeta),      //utinyint
-    Int32(i32, ObjMeta),     //i8,i16,i24,i32
-    Int64(i64, ObjMeta),     //i64
-    UInt32(u32, ObjMeta),    //u8,u16,u24,u23
-    UInt64(u64, ObjMeta),    //u64
-    Float(f32, ObjMeta),     //f32, uf32
-    Double(f64, ObjMeta),    //f64,uf64
-    Date(i32, ObjMeta),      //date, in seconds
-    Time(i64, ObjMeta),      //datetime, timestamp, in millseconds
-    Bytes(Vec<u8>, ObjMeta), //varchar
-    String(String, ObjMeta), //text,char
+    Bool(bool, ObjMeta),       //tinyint
+    Int8(i8, ObjMeta),         //tinyint
+    UInt8(u8, ObjMeta),        //utinyint
+    Int32(i32, ObjMeta),       //i8,i16,i24,i32
+    Int64(i64, ObjMeta),       //i64
+    UInt32(u32, ObjMeta),      //u8,u16,u24,u23
+    UInt64(u64, ObjMeta),      //u64
+    Float(f32, ObjMeta),       //f32, uf32
+    Double(f64, ObjMeta),      //f64,uf64
+    Date(i32, ObjMeta),        //date, in days since 1970-01-01
+    Time(i64, ObjMeta),        //datetime, timestamp, in millseconds, prefer the variants below
+    DateTime(i64, ObjMeta),    //datetime, in microseconds since 1970-01-01 00:00:00
+    Timestamp(i64, ObjMeta),   //timestamp, in microseconds since 1970-01-01 00:00:00 UTC
+    TimeOfDay(i64, ObjMeta),   //time, in microseconds, may be negative or beyond a day
+    Year(u16, ObjMeta),        //year, 0 or from 1901 to 2155
+    Bytes(Vec<u8>, ObjMeta),   //varchar
+    String(String, ObjMeta),   //text,char
+    Decimal(Decimal, ObjMeta), //number, unumber
+    Bit(u64, ObjMeta),         //bit, the length is kept in the scale of the meta
+    Enum(u64, ObjMeta),        //enum, the 1-based index of the member, 0 is the empty member
+    Set(u64, ObjMeta),         //set, the bitmap of the members
 }
 
 //TODO refactor, introduce trait for encoder
@@ -243,11 +262,25 @@ impl ObjMeta {
         }
     }
 
+    pub fn cs_type(&self) -> &CollationType {
+        &self.cs_type
+    }
+
     pub fn new_numeric_meta(obj_type: ObjType) -> ObjMeta {
         ObjMeta::new(obj_type, CollationLevel::Numeric, CollationType::Binary, 10)
     }
 
-    fn default_obj_meta(t: ObjType) -> ObjMeta {
+    // The bit column keeps its length in the scale, from 1 to 64.
+    pub fn new_bit_meta(bit_len: i8) -> ObjMeta {
+        ObjMeta::new(
+            ObjType::Bit,
+            CollationLevel::Numeric,
+            CollationType::Binary,
+            bit_len,
+        )
+    }
+
+    pub fn default_obj_meta(t: ObjType) -> ObjMeta {
         match t {
             ObjType::Null => ObjMeta::new(t, CollationLevel::Ignorable, CollationType::Binary, 10),
             ObjType::TinyInt => ObjMeta::new_numeric_meta(t),
@@ -287,7 +320,12 @@ impl ObjMeta {
             ObjType::Text => ObjMeta::new_numeric_meta(t),
             ObjType::MediumText => ObjMeta::new_numeric_meta(t),
             ObjType::LongText => ObjMeta::new_numeric_meta(t),
-            ObjType::Bit => ObjMeta::new_numeric_meta(t),
+            ObjType::Bit => ObjMeta::new_bit_meta(64),
+            ObjType::Enum => ObjMeta::new_numeric_meta(t),
+            ObjType::Set => ObjMeta::new_numeric_meta(t),
+            ObjType::Json => {
+                ObjMeta::new(t, CollationLevel::Implict, CollationType::UTF8MB4Bin, 10)
+            }
         }
     }
 }
@@ -363,6 +401,17 @@ impl Value {
         }
     }
 
+    pub fn is_decimal(&self) -> bool {
+        matches!(self, Value::Decimal(_, _))
+    }
+
+    pub fn as_decimal(self) -> Decimal {
+        match self {
+            Value::Decimal(d, _) => d,
+            _ => panic!("Fail to cast: {:?}", self),
+        }
+    }
+
     pub fn as_i64(&self) -> i64 {
         match self {
             Value::Int64(i, _) => *i as i64,
@@ -462,13 +511,21 @@ impl Value {
                 meta.len() + util::encoded_length_vi64(f.to_bits() as i64)
             }
             Value::Date(d, ref meta) => meta.len() + util::encoded_length_vi32(d),
-            Value::Time(d, ref meta) => meta.len() + util::encoded_length_vi64(d),
+            Value::Time(d, ref meta) => meta.len() + util::encoded_length_vi64(d * 1000),
+            Value::DateTime(d, ref meta) => meta.len() + util::encoded_length_vi64(d),
+            Value::Timestamp(d, ref meta) => meta.len() + util::encoded_length_vi64(d),
+            Value::TimeOfDay(d, ref meta) => meta.len() + util::encoded_length_vi64(d),
+            Value::Year(_, ref meta) => meta.len() + 1,
             Value::Bytes(ref vc, ref meta) => {
                 meta.len() + util::encoded_length_vi32(vc.len() as i32) + vc.len() + 1
             }
             Value::String(ref s, ref meta) => {
                 meta.len() + util::encoded_length_vi32(s.len() as i32) + s.len() + 1
             }
+            Value::Decimal(ref d, ref meta) => meta.len() + d.encoded_len(),
+            Value::Bit(v, ref meta) | Value::Enum(v, ref meta) | Value::Set(v, ref meta) => {
+                meta.len() + util::encoded_length_vi64(v as i64)
+            }
         }
     }
 
@@ -491,12 +548,13 @@ impl Value {
             ObjType::Double => Ok(Value::Double(decode_f64(buf)?, meta)),
             ObjType::UFloat => Ok(Value::Float(decode_f32(buf)?, meta)),
             ObjType::UDouble => Ok(Value::Double(decode_f64(buf)?, meta)),
-            //FIXME date and time
-            ObjType::DateTime => unimplemented!(),
-            ObjType::Timestamp => unimplemented!(),
-            ObjType::Date => unimplemented!(),
-            ObjType::Time => unimplemented!(),
-            ObjType::Year => unimplemented!(),
+            ObjType::Number => Ok(Value::Decimal(Decimal::decode(buf)?, meta)),
+            ObjType::UNumber => Ok(Value::Decimal(Decimal::decode(buf)?, meta)),
+            ObjType::DateTime => Ok(Value::DateTime(decode_vi64(buf)?, meta)),
+            ObjType::Timestamp => Ok(Value::Timestamp(decode_vi64(buf)?, meta)),
+            ObjType::Date => Ok(Value::Date(decode_vi32(buf)?, meta)),
+            ObjType::Time => Ok(Value::TimeOfDay(decode_vi64(buf)?, meta)),
+            ObjType::Year => Ok(Value::Year(decode_year(decode_u8(buf)?), meta)),
             ObjType::Varchar => Self::decode_binary(buf, meta),
             ObjType::Char => Self::decode_binary(buf, meta),
             ObjType::Extend => Ok(Value::Int64(decode_vi64(buf)?, meta)),
@@ -504,8 +562,13 @@ impl Value {
             ObjType::Text => Self::decode_binary(buf, meta),
             ObjType::MediumText => Self::decode_binary(buf, meta),
             ObjType::LongText => Self::decode_binary(buf, meta),
-            ObjType::Bit => Ok(Value::Int64(decode_vi64(buf)?, meta)),
-            _ => Err(Error::Custom("Unsupported obj type.".into())),
+            ObjType::Bit => Ok(Value::Bit(decode_vi64(buf)? as u64, meta)),
+            ObjType::Enum => Ok(Value::Enum(decode_vi64(buf)? as u64, meta)),
+            ObjType::Set => Ok(Value::Set(decode_vi64(buf)? as u64, meta)),
+            // the json is sent in the binary form, which is not supported yet
+            ObjType::Json => Err(Error::Custom(
+                "Value::decode json values are not supported yet".into(),
+            )),
         }
     }
 
@@ -563,6 +626,18 @@ impl Value {
                 meta.encode(buf)?;
                 encode_vi64(d * 1000, buf)
             }
+            Value::DateTime(d, ref meta)
+            | Value::Timestamp(d, ref meta)
+            | Value::TimeOfDay(d, ref meta) => {
+                meta.encode(buf)?;
+                encode_vi64(d, buf)
+            }
+            Value::Year(y, ref meta) => {
+                meta.encode(buf)?;
+                buf.reserve(1);
+                buf.put_u8(encode_year(y)?);
+                Ok(())
+            }
             Value::Bytes(ref vc, ref meta) => {
                 meta.encode(buf)?;
                 //refactor encode binary
@@ -575,10 +650,37 @@ impl Value {
                 meta.encode(buf)?;
                 encode_vstring(&s, buf)
             }
+            Value::Decimal(ref d, ref meta) => {
+                meta.encode(buf)?;
+                d.encode(buf)
+            }
+            Value::Bit(v, ref meta) | Value::Enum(v, ref meta) | Value::Set(v, ref meta) => {
+                meta.encode(buf)?;
+                encode_vi64(v as i64, buf)
+            }
         }
     }
 }
 
+// The year is stored as the offset to 1900 in a byte, and 0 is the year 0.
+fn encode_year(year: u16) -> Result<u8> {
+    match year {
+        0 => Ok(0),
+        1901..=2155 => Ok((year - 1900) as u8),
+        _ => Err(Error::Custom(
+            format!("Value::encode invalid year, year={}", year).into(),
+        )),
+    }
+}
+
+fn decode_year(v: u8) -> u16 {
+    if v == 0 {
+        0
+    } else {
+        u16::from(v) + 1900
+    }
+}
+
 impl Default for Value {
     fn default() -> Value {
         Value::Null(ObjMeta::default_obj_meta(ObjType::Null))
@@ -602,8 +704,16 @@ impl Hash for Value {
             Value::Double(f, _) => f.to_bits().hash(state),
             Value::Date(d, _) => (5, d).hash(state),
             Value::Time(t, _) => (6, t).hash(state),
+            Value::DateTime(t, _) => (10, t).hash(state),
+            Value::Timestamp(t, _) => (11, t).hash(state),
+            Value::TimeOfDay(t, _) => (12, t).hash(state),
+            Value::Year(y, _) => (13, y).hash(state),
             Value::Bytes(ref vc, _) => (7, vc).hash(state),
             Value::String(ref s, _) => (8, s).hash(state),
+            Value::Decimal(ref d, _) => (9, d).hash(state),
+            Value::Bit(v, _) => (14, v).hash(state),
+            Value::Enum(v, _) => (15, v).hash(state),
+            Value::Set(v, _) => (16, v).hash(state),
         }
     }
 }
@@ -626,8 +736,14 @@ impl Serialize for Value {
             Value::Double(f, _) => serializer.serialize_f64(f),
             Value::Date(d, _) => serializer.serialize_i32(d),
             Value::Time(t, _) => serializer.serialize_i64(t),
+            Value::DateTime(t, _) => serializer.serialize_i64(t),
+            Value::Timestamp(t, _) => serializer.serialize_i64(t),
+            Value::TimeOfDay(t, _) => serializer.serialize_i64(t),
+            Value::Year(y, _) => serializer.serialize_u16(y),
             Value::Bytes(ref vc, _) => serializer.serialize_bytes(vc),
             Value::String(ref s, _) => serializer.serialize_str(&s),
+            Value::Decimal(ref d, _) => serializer.serialize_str(&d.to_string()),
+            Value::Bit(v, _) | Value::Enum(v, _) | Value::Set(v, _) => serializer.serialize_u64(v),
         }
     }
 }
@@ -659,4 +775,80 @@ mod test {
         assert!(ret.is_ok());
         assert_eq!(35, ret.unwrap().len());
     }
+
+    #[test]
+    fn test_temporal_value_codec() {
+        for (v, obj_type) in vec![
+            (
+                Value::Date(19_000, ObjMeta::default_obj_meta(ObjType::Date)),
+                ObjType::Date,
+            ),
+            (
+                Value::DateTime(
+                    1_600_000_000_123_456,
+                    ObjMeta::default_obj_meta(ObjType::DateTime),
+                ),
+                ObjType::DateTime,
+            ),
+            (
+                Value::Timestamp(-1_000_001, ObjMeta::default_obj_meta(ObjType::Timestamp)),
+                ObjType::Timestamp,
+            ),
+            (
+                Value::TimeOfDay(-3_600_000_000, ObjMeta::default_obj_meta(ObjType::Time)),
+                ObjType::Time,
+            ),
+            (
+                Value::Year(2024, ObjMeta::default_obj_meta(ObjType::Year)),
+                ObjType::Year,
+            ),
+        ] {
+            let mut buf = BytesMut::with_capacity(v.len());
+            v.encode(&mut buf).unwrap();
+            assert_eq!(v.len(), buf.len());
+            assert_eq!(v, Value::decode(&mut buf, obj_type).unwrap());
+        }
+
+        let mut buf = BytesMut::with_capacity(8);
+        let v = Value::Year(1900, ObjMeta::default_obj_meta(ObjType::Year));
+        assert!(v.encode(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_decimal_value_codec() {
+        let v = Value::from("-12.345".parse::<Decimal>().unwrap());
+        let mut buf = BytesMut::with_capacity(v.len());
+        v.encode(&mut buf).unwrap();
+        assert_eq!(v.len(), buf.len());
+        assert_eq!(v, Value::decode(&mut buf, ObjType::Number).unwrap());
+    }
+
+    #[test]
+    fn test_bit_enum_set_value_codec() {
+        for (v, obj_type) in vec![
+            (Value::Bit(0b1011, ObjMeta::new_bit_meta(4)), ObjType::Bit),
+            (
+                Value::Bit(u64::MAX, ObjMeta::default_obj_meta(ObjType::Bit)),
+                ObjType::Bit,
+            ),
+            (
+                Value::Enum(2, ObjMeta::default_obj_meta(ObjType::Enum)),
+                ObjType::Enum,
+            ),
+            (
+                Value::Set(0b101, ObjMeta::default_obj_meta(ObjType::Set)),
+                ObjType::Set,
+            ),
+        ] {
+            let mut buf = BytesMut::with_capacity(v.len());
+            v.encode(&mut buf).unwrap();
+            assert_eq!(v.len(), buf.len());
+            assert_eq!(v, Value::decode(&mut buf, obj_type).unwrap());
+        }
+
+        // the json columns are known but their values are not decoded
+        assert_eq!(ObjType::Json, ObjType::from_u8(47).unwrap());
+        let mut buf = BytesMut::from(&b"\x02{}"[..]);
+        assert!(Value::decode(&mut buf, ObjType::Json).is_err());
+    }
 }
@@ -0,0 +1,114 @@
+/*-
+ * #%L
+ * OBKV Table Client Framework
+ * %%
+ * Copyright (C) 2021 OceanBase
+ * %%
+ * OBKV Table Client Framework is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *          http://license.coscl.org.cn/MulanPSL2
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ * #L%
+ */
+
+//! Adapters polling the `futures` 0.1 futures and streams in the std tasks,
+//! the task is woken by the notification of the inner future, so no reactor
+//! of the runtime is needed.
+
+use std::{
+    future::Future as StdFuture,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Waker},
+};
+
+use futures::{
+    executor::{self, Notify, Spawn},
+    Async, Future, Stream,
+};
+
+struct WakerNotify(Waker);
+
+impl Notify for WakerNotify {
+    fn notify(&self, _id: usize) {
+        self.0.wake_by_ref();
+    }
+}
+
+/// Wraps a `futures` 0.1 future or stream to be polled in the std tasks.
+pub struct Compat<T> {
+    inner: Spawn<T>,
+}
+
+impl<T> Compat<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner: executor::spawn(inner),
+        }
+    }
+}
+
+impl<T: Stream> Compat<T> {
+    /// Poll the next item of the stream in the std task.
+    pub fn poll_next(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<T::Item, T::Error>>> {
+        let notify = Arc::new(WakerNotify(cx.waker().clone()));
+        match self.inner.poll_stream_notify(&notify, 0) {
+            Ok(Async::Ready(item)) => Poll::Ready(item.map(Ok)),
+            Ok(Async::NotReady) => Poll::Pending,
+            Err(e) => Poll::Ready(Some(Err(e))),
+        }
+    }
+}
+
+impl<T: Future + Unpin> StdFuture for Compat<T> {
+    type Output = Result<T::Item, T::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let notify = Arc::new(WakerNotify(cx.waker().clone()));
+        match self.get_mut().inner.poll_future_notify(&notify, 0) {
+            Ok(Async::Ready(v)) => Poll::Ready(Ok(v)),
+            Ok(Async::NotReady) => Poll::Pending,
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use futures::{stream, sync::oneshot};
+
+    use super::*;
+    use crate::util;
+
+    #[test]
+    fn test_await_on_tokio() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+
+        // resolved by the shared timer thread
+        let res = runtime.block_on(util::into_std(util::delay(Duration::from_millis(10))));
+        assert!(res.is_ok());
+
+        // resolved by the thread out of the runtime
+        let (tx, rx) = oneshot::channel();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            tx.send(42).unwrap();
+        });
+        assert_eq!(Ok(42), runtime.block_on(Compat::new(rx)));
+        handle.join().unwrap();
+
+        let mut items = Compat::new(stream::iter_ok::<_, ()>(vec![1, 2]));
+        let next = runtime.block_on(std::future::poll_fn(|cx| items.poll_next(cx)));
+        assert_eq!(Some(Ok(1)), next);
+        let next = runtime.block_on(std::future::poll_fn(|cx| items.poll_next(cx)));
+        assert_eq!(Some(Ok(2)), next);
+        let next = runtime.block_on(std::future::poll_fn(|cx| items.poll_next(cx)));
+        assert_eq!(None, next);
+    }
+}
@@ -16,18 +16,78 @@
  */
 
 use std::{
+    pin::Pin,
     sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
     time::Duration,
 };
 
 use bytes::BytesMut;
 use chrono::Utc;
+use futures::{sync::oneshot, Future};
+use futures_cpupool::{Builder as CpuPoolBuilder, CpuPool};
+use scheduled_thread_pool::{JobHandle, ScheduledThreadPool};
 
-use crate::serde_obkv::value::{ObjType, Value};
+use crate::{
+    error::{Error, Result},
+    serde_obkv::value::{ObjType, Value},
+};
 
+mod compat;
 pub mod permit;
 pub mod security;
 
+pub use self::compat::Compat;
+
+/// Boxed `futures` 0.1 future used inside the client, which is converted by
+/// [`into_std`] for the asynchronous api.
+pub type BoxFuture<T> = Box<dyn Future<Item = T, Error = Error> + Send>;
+
+/// Boxed std future returned by the asynchronous api, which is awaited on the
+/// std runtimes such as tokio.
+pub type StdBoxFuture<T> = Pin<Box<dyn std::future::Future<Output = Result<T>> + Send>>;
+
+/// Convert the future into the std one returned by the asynchronous api.
+#[inline]
+pub fn into_std<T: Send + 'static>(future: BoxFuture<T>) -> StdBoxFuture<T> {
+    Box::pin(Compat::new(future))
+}
+
+lazy_static! {
+    // Timer shared by all the asynchronous requests, it is only responsible
+    // for firing timeouts and retry delays, so one thread is enough.
+    static ref TIMER: ScheduledThreadPool = ScheduledThreadPool::with_name("obkv_timer_{}", 1);
+    // Drives the futures nobody waits for, e.g. the cleanup of the dropped
+    // streams, which only wait for the replies without blocking the thread.
+    static ref DETACHED: CpuPool = CpuPoolBuilder::new()
+        .name_prefix("obkv_detached")
+        .pool_size(1)
+        .create();
+}
+
+/// Run `job` on the shared timer thread after `delay`.
+#[inline]
+pub fn schedule_after<F>(delay: Duration, job: F) -> JobHandle
+where
+    F: FnOnce() + Send + 'static,
+{
+    TIMER.execute_after(delay, job)
+}
+
+/// Run `future` to the end in the background, its result is discarded.
+#[inline]
+pub fn spawn_detached(future: BoxFuture<()>) {
+    DETACHED.spawn(future).forget();
+}
+
+/// Returns a future which resolves after `delay` without blocking any thread.
+pub fn delay(delay: Duration) -> BoxFuture<()> {
+    let (tx, rx) = oneshot::channel();
+    schedule_after(delay, move || {
+        let _ = tx.send(());
+    });
+    Box::new(rx.map_err(Error::from))
+}
+
 #[inline]
 pub fn current_time_millis() -> i64 {
     Utc::now().timestamp_millis()
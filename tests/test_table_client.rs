@@ -98,6 +98,84 @@ fn test_obtable_client_curd() {
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_obtable_client_odp_mode() {
+    let client = utils::common::build_odp_client();
+    const TEST_TABLE_NAME: &str = "test_varchar_table";
+    // detected through the ODP
+    assert!(client.ob_version().major() > 0);
+
+    let result = client.delete(TEST_TABLE_NAME, vec![Value::from("foo")]);
+    assert!(result.is_ok());
+
+    let result = client.insert(
+        TEST_TABLE_NAME,
+        vec![Value::from("foo")],
+        vec!["c2".to_owned()],
+        vec![Value::from("bar")],
+    );
+    assert!(result.is_ok());
+    assert_eq!(1, result.unwrap());
+
+    let result = client.get(
+        TEST_TABLE_NAME,
+        vec![Value::from("foo")],
+        vec!["c2".to_owned()],
+    );
+    assert!(result.is_ok());
+    let mut result = result.unwrap();
+    assert_eq!(1, result.len());
+    assert_eq!("bar", result.remove("c2").unwrap().as_string());
+
+    let mut batch_op = client.batch_operation(2);
+    batch_op.update(
+        vec![Value::from("foo")],
+        vec!["c2".to_owned()],
+        vec![Value::from("car")],
+    );
+    batch_op.get(vec![Value::from("foo")], vec!["c2".to_owned()]);
+    let result = client.execute_batch(TEST_TABLE_NAME, batch_op);
+    assert!(result.is_ok());
+    assert_eq!(2, result.unwrap().len());
+
+    let result_set = client
+        .query(TEST_TABLE_NAME)
+        .select(vec!["c1".to_owned(), "c2".to_owned()])
+        .add_scan_range(vec![Value::get_min()], true, vec![Value::get_max()], true)
+        .execute();
+    assert!(result_set.is_ok());
+    assert!(result_set.unwrap().cache_size() > 0);
+
+    let result = client.delete(TEST_TABLE_NAME, vec![Value::from("foo")]);
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_obtable_client_rs_list() {
+    let client = utils::common::build_rs_list_client();
+    const TEST_TABLE_NAME: &str = "test_varchar_table";
+
+    let result = client.insert_or_update(
+        TEST_TABLE_NAME,
+        vec![Value::from("foo")],
+        vec!["c2".to_owned()],
+        vec![Value::from("bar")],
+    );
+    assert!(result.is_ok());
+
+    let result = client.get(
+        TEST_TABLE_NAME,
+        vec![Value::from("foo")],
+        vec!["c2".to_owned()],
+    );
+    assert!(result.is_ok());
+    let mut result = result.unwrap();
+    assert_eq!("bar", result.remove("c2").unwrap().as_string());
+
+    let result = client.delete(TEST_TABLE_NAME, vec![Value::from("foo")]);
+    assert!(result.is_ok());
+}
+
 #[test]
 fn test_obtable_client_batch_op() {
     let client = utils::common::build_normal_client();
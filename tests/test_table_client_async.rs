@@ -0,0 +1,150 @@
+/*-
+ * #%L
+ * OBKV Table Client Framework
+ * %%
+ * Copyright (C) 2021 OceanBase
+ * %%
+ * OBKV Table Client Framework is licensed under Mulan PSL v2.
+ * You can use this software according to the terms and conditions of the Mulan PSL v2.
+ * You may obtain a copy of Mulan PSL v2 at:
+ *          http://license.coscl.org.cn/MulanPSL2
+ * THIS SOFTWARE IS PROVIDED ON AN "AS IS" BASIS, WITHOUT WARRANTIES OF ANY KIND,
+ * EITHER EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO NON-INFRINGEMENT,
+ * MERCHANTABILITY OR FIT FOR A PARTICULAR PURPOSE.
+ * See the Mulan PSL v2 for more details.
+ * #L%
+ */
+#[allow(unused_imports)]
+#[allow(unused)]
+mod utils;
+
+use obkv::{AsyncTable, TableQuery, Value};
+use test_log::test;
+use tokio::runtime::Runtime;
+
+#[test]
+fn test_obtable_client_async_curd() {
+    let client = utils::common::build_normal_client();
+    const TEST_TABLE_NAME: &str = "test_varchar_table";
+
+    Runtime::new().unwrap().block_on(async {
+        let result = client
+            .delete(TEST_TABLE_NAME, vec![Value::from("async_foo")])
+            .await;
+        assert!(result.is_ok());
+
+        let result = client
+            .insert(
+                TEST_TABLE_NAME,
+                vec![Value::from("async_foo")],
+                vec!["c2".to_owned()],
+                vec![Value::from("bar")],
+            )
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(1, result.unwrap());
+
+        let result = client
+            .get(
+                TEST_TABLE_NAME,
+                vec![Value::from("async_foo")],
+                vec!["c2".to_owned()],
+            )
+            .await;
+        assert!(result.is_ok());
+        let mut result = result.unwrap();
+        assert_eq!(1, result.len());
+        let value = result.remove("c2").unwrap();
+        assert!(value.is_string());
+        assert_eq!("bar", value.as_string());
+
+        let result_set = AsyncTable::query(&client, TEST_TABLE_NAME)
+            .select(vec!["c1".to_owned(), "c2".to_owned()])
+            .add_scan_range(
+                vec![Value::from("async_foo")],
+                true,
+                vec![Value::from("async_foo")],
+                true,
+            )
+            .execute_async()
+            .await;
+        assert!(result_set.is_ok());
+        let result_set = result_set.unwrap();
+        assert_eq!(1, result_set.cache_size());
+
+        let mut batch_op = client.batch_operation(2);
+        batch_op.update(
+            vec![Value::from("async_foo")],
+            vec!["c2".to_owned()],
+            vec![Value::from("car")],
+        );
+        batch_op.get(vec![Value::from("async_foo")], vec!["c2".to_owned()]);
+        let result = client.execute_batch(TEST_TABLE_NAME, batch_op).await;
+        assert!(result.is_ok());
+        assert_eq!(2, result.unwrap().len());
+
+        let result = client
+            .delete(TEST_TABLE_NAME, vec![Value::from("async_foo")])
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(1, result.unwrap());
+    });
+}
+
+#[test]
+fn test_obtable_client_async_stream_query() {
+    let client = utils::common::build_normal_client();
+    const TEST_TABLE_NAME: &str = "test_varchar_table";
+
+    Runtime::new().unwrap().block_on(async {
+        let keys: Vec<String> = (0..10).map(|i| format!("async_stream_{}", i)).collect();
+        for key in &keys {
+            let result = client
+                .insert_or_update(
+                    TEST_TABLE_NAME,
+                    vec![Value::from(key.to_owned())],
+                    vec!["c2".to_owned()],
+                    vec![Value::from("bar")],
+                )
+                .await;
+            assert!(result.is_ok());
+        }
+
+        let query = || {
+            AsyncTable::query(&client, TEST_TABLE_NAME)
+                .select(vec!["c1".to_owned(), "c2".to_owned()])
+                .batch_size(3)
+                .add_scan_range(
+                    vec![Value::from("async_stream_0")],
+                    true,
+                    vec![Value::from("async_stream_9")],
+                    true,
+                )
+        };
+
+        let mut stream = query().execute_async().await.unwrap().into_stream();
+        assert_eq!(vec!["c1".to_owned(), "c2".to_owned()], stream.properties());
+        let mut rows = vec![];
+        while let Some(row) = stream.next_row().await.unwrap() {
+            rows.push(row);
+        }
+        assert_eq!(10, rows.len());
+        for (row, key) in rows.iter().zip(keys.iter()) {
+            assert_eq!(key, &row[0].clone().as_string());
+        }
+
+        // drop the stream before it reaches the end
+        let mut stream = query().execute_async().await.unwrap().into_stream();
+        for _ in 0..4 {
+            assert!(stream.next_row().await.unwrap().is_some());
+        }
+        drop(stream);
+
+        for key in keys {
+            let result = client
+                .delete(TEST_TABLE_NAME, vec![Value::from(key)])
+                .await;
+            assert!(result.is_ok());
+        }
+    });
+}
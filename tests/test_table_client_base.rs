@@ -28,7 +28,7 @@ use std::{
 };
 
 use obkv::error::CommonErrCode;
-use obkv::{ObTableClient, ResultCodes, Table, TableQuery, Value};
+use obkv::{ObTableClient, OperationOptions, ResultCodes, Table, TableQuery, Value};
 use time::PreciseTime;
 
 pub struct BaseTest {
@@ -329,6 +329,18 @@ impl BaseTest {
         assert_eq!(1, result.len());
         let value = result.remove("c3").unwrap();
         assert_eq!(25i64, value.as_i64());
+
+        let result = self.client.increment_with_options(
+            table_name,
+            vec![Value::from("foo")],
+            vec!["c3".to_owned()],
+            vec![Value::from(5i64)],
+            OperationOptions::new().return_affected_entity(true),
+        );
+        assert!(result.is_ok());
+        let entity = result.unwrap();
+        let value = entity.get_attr("c3").unwrap();
+        assert_eq!(30i64, value.as_i64());
     }
 
     pub fn clean_varchar_table(&self, table_name: &str) {
@@ -36,6 +36,11 @@ const TEST_URL: &str = "127.0.0.1";
 const TEST_PASSWORD: &str = "test";
 const TEST_SYS_USER_NAME: &str = "";
 const TEST_SYS_PASSWORD: &str = "";
+const TEST_ODP_IP: &str = "127.0.0.1";
+const TEST_ODP_SQL_PORT: i32 = 2883;
+const TEST_ODP_RPC_PORT: i32 = 2885;
+const TEST_DATABASE: &str = "test";
+const TEST_RS_LIST: &[&str] = &["127.0.0.1:2881"];
 
 pub fn build_client(mode: RunningMode) -> ObTableClient {
     let builder = Builder::new()
@@ -61,4 +66,39 @@ pub fn build_hbase_client() -> ObTableClient {
 
 pub fn build_normal_client() -> ObTableClient {
     build_client(RunningMode::Normal)
+}
+
+pub fn build_odp_client() -> ObTableClient {
+    let builder = Builder::new()
+        .full_user_name(TEST_FULL_USER_NAME)
+        .odp_addr(TEST_ODP_IP, TEST_ODP_SQL_PORT, TEST_ODP_RPC_PORT)
+        .database(TEST_DATABASE)
+        .password(TEST_PASSWORD);
+
+    let client = builder.build();
+
+    assert!(client.is_ok());
+
+    let client = client.unwrap();
+    assert!(client.is_odp_mode());
+    client.init().expect("Fail to create obkv client.");
+    client
+}
+
+pub fn build_rs_list_client() -> ObTableClient {
+    let builder = Builder::new()
+        .full_user_name(TEST_FULL_USER_NAME)
+        .rs_list(TEST_RS_LIST)
+        .database(TEST_DATABASE)
+        .password(TEST_PASSWORD)
+        .sys_user_name(TEST_SYS_USER_NAME)
+        .sys_password(TEST_SYS_PASSWORD);
+
+    let client = builder.build();
+
+    assert!(client.is_ok());
+
+    let client = client.unwrap();
+    client.init().expect("Fail to create obkv client.");
+    client
 }
\ No newline at end of file